@@ -2,7 +2,7 @@ use instant::Instant;
 use std::time::Duration;
 use winit::{
     event::{Event, StartCause, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
+    event_loop::{ControlFlow, EventLoop, Timer},
     window::WindowBuilder,
 };
 
@@ -16,18 +16,26 @@ fn main() {
         .unwrap();
 
     let timer_length = Duration::new(1, 0);
+    // Backends without timers wake up for the deadlines of `ControlFlow::WaitUntil` instead.
+    let mut emulated = false;
 
-    event_loop.run(move |event, _, control_flow| {
+    event_loop.run(move |event, event_loop, control_flow| {
         println!("{:?}", event);
 
         match event {
             Event::NewEvents(StartCause::Init) => {
-                *control_flow = ControlFlow::WaitUntil(Instant::now() + timer_length)
+                if event_loop.set_timer(Timer::Interval(timer_length)).is_ok() {
+                    *control_flow = ControlFlow::Wait;
+                } else {
+                    emulated = true;
+                    *control_flow = ControlFlow::WaitUntil(Instant::now() + timer_length);
+                }
             }
-            Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
-                *control_flow = ControlFlow::WaitUntil(Instant::now() + timer_length);
+            Event::NewEvents(StartCause::ResumeTimeReached { .. }) if emulated => {
                 println!("\nTimer\n");
+                *control_flow = ControlFlow::WaitUntil(Instant::now() + timer_length);
             }
+            Event::TimerExpired(_) => println!("\nTimer\n"),
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
@@ -0,0 +1,140 @@
+use crate::icon::{BadIcon, RgbaIcon};
+use std::{fmt, sync::Arc, time::Duration};
+
+/// The image of a custom cursor, along with its hotspot.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CursorImage {
+    pub(crate) rgba: Vec<u8>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) hotspot_x: u32,
+    pub(crate) hotspot_y: u32,
+}
+
+#[allow(dead_code)] // Only used on X11 and Wayland
+impl CursorImage {
+    /// Returns the pixels of the image as premultiplied ARGB, which is what both X11 and Wayland
+    /// use for cursors.
+    pub(crate) fn argb_pixels(&self) -> Vec<u32> {
+        self.rgba
+            .chunks_exact(4)
+            .map(|pixel| {
+                let alpha = pixel[3] as u32;
+                let premultiply = |channel: u8| (channel as u32 * alpha + 127) / 255;
+                (alpha << 24)
+                    | (premultiply(pixel[0]) << 16)
+                    | (premultiply(pixel[1]) << 8)
+                    | premultiply(pixel[2])
+            })
+            .collect()
+    }
+}
+
+/// A frame of a custom cursor, shown for `duration` before moving on to the next one.
+#[derive(PartialEq, Eq, Hash)]
+pub(crate) struct CursorFrame {
+    pub(crate) image: CursorImage,
+    pub(crate) duration: Duration,
+}
+
+/// A cursor created from an image or an animation, which can be used with
+/// [`Window::set_custom_cursor`].
+///
+/// Cloning a `CustomCursor` is cheap, and cursors with the same frames compare equal, so the
+/// platform cursors built from them can be reused.
+///
+/// [`Window::set_custom_cursor`]: crate::window::Window::set_custom_cursor
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CustomCursor {
+    // Never empty. A static cursor has a single frame, whose duration is ignored.
+    pub(crate) frames: Arc<[CursorFrame]>,
+}
+
+impl fmt::Debug for CustomCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let image = &self.frames[0].image;
+        f.debug_struct("CustomCursor")
+            .field("width", &image.width)
+            .field("height", &image.height)
+            .field("hotspot_x", &image.hotspot_x)
+            .field("hotspot_y", &image.hotspot_y)
+            .field("frames", &self.frames.len())
+            .finish()
+    }
+}
+
+impl CustomCursor {
+    /// Creates a `CustomCursor` from 32bpp RGBA data, with its hotspot at `(hotspot_x, hotspot_y)`
+    /// in pixels from the top left corner of the image.
+    ///
+    /// The length of `rgba` must be divisible by 4, `width * height` must equal `rgba.len() / 4`
+    /// and the hotspot must be within the image. Otherwise, this will return a `BadIcon` error.
+    pub fn from_rgba(
+        rgba: Vec<u8>,
+        width: u32,
+        height: u32,
+        hotspot_x: u32,
+        hotspot_y: u32,
+    ) -> Result<Self, BadIcon> {
+        let RgbaIcon {
+            rgba,
+            width,
+            height,
+        } = RgbaIcon::from_rgba(rgba, width, height)?;
+        if hotspot_x >= width || hotspot_y >= height {
+            return Err(BadIcon::HotspotOutOfBounds {
+                width,
+                height,
+                hotspot_x,
+                hotspot_y,
+            });
+        }
+        let image = CursorImage {
+            rgba,
+            width,
+            height,
+            hotspot_x,
+            hotspot_y,
+        };
+        Ok(CustomCursor {
+            frames: Arc::from(vec![CursorFrame {
+                image,
+                duration: Duration::from_secs(0),
+            }]),
+        })
+    }
+
+    /// Creates an animated `CustomCursor`, which shows each of `frames` for its duration before
+    /// moving on to the next one, and loops back to the first frame after the last one.
+    ///
+    /// The frames are usually created with [`CustomCursor::from_rgba`], and may have different
+    /// sizes and hotspots. If a frame is itself animated, only its first image is used. Returns
+    /// [`BadIcon::NoFrames`] if `frames` is empty.
+    ///
+    /// [`CustomCursor::from_rgba`]: CustomCursor::from_rgba
+    /// [`BadIcon::NoFrames`]: crate::window::BadIcon::NoFrames
+    pub fn from_animation<I>(frames: I) -> Result<Self, BadIcon>
+    where
+        I: IntoIterator<Item = (CustomCursor, Duration)>,
+    {
+        let frames: Vec<_> = frames
+            .into_iter()
+            .map(|(cursor, duration)| CursorFrame {
+                image: cursor.frames[0].image.clone(),
+                duration,
+            })
+            .collect();
+        if frames.is_empty() {
+            return Err(BadIcon::NoFrames);
+        }
+        Ok(CustomCursor {
+            frames: Arc::from(frames),
+        })
+    }
+
+    /// Returns whether the cursor has more than one frame.
+    #[allow(dead_code)] // Only used on X11 and Wayland
+    pub(crate) fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+}
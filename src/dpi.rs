@@ -75,7 +75,8 @@
 //! - **X11:** Many man-hours have been spent trying to figure out how to handle DPI in X11. Winit
 //!   currently uses a three-pronged approach:
 //!   + Use the value in the `WINIT_X11_SCALE_FACTOR` environment variable, if present.
-//!   + If not present, use the value set in `Xft.dpi` in Xresources.
+//!   + If not present, use the `Xft/DPI` setting published over XSETTINGS, which is followed as
+//!     it changes, or else the value set in `Xft.dpi` in Xresources.
 //!   + Otherwise, calcuate the scale factor based on the millimeter monitor dimensions provided by XRandR.
 //!
 //!   If `WINIT_X11_SCALE_FACTOR` is set to `randr`, it'll ignore the `Xft.dpi` field and use the
@@ -13,7 +13,7 @@
 //! while control_flow != ControlFlow::Exit {
 //!     event_handler(NewEvents(start_cause), ..., &mut control_flow);
 //!
-//!     for e in (window events, user events, device events) {
+//!     for e in (ready fds, expired timers, window events, user events, device events) {
 //!         event_handler(e, ..., &mut control_flow);
 //!     }
 //!     event_handler(MainEventsCleared, ..., &mut control_flow);
@@ -34,12 +34,14 @@
 //!
 //! [event_loop_run]: crate::event_loop::EventLoop::run
 use instant::Instant;
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use crate::{
     dpi::{LogicalPosition, PhysicalPosition, PhysicalSize},
+    event_loop::{SourceId, TimerId},
+    monitor::MonitorHandle,
     platform_impl,
-    window::{Theme, WindowId},
+    window::{ClipboardContents, ClipboardKind, Theme, WindowId},
 };
 
 /// Describes a generic event.
@@ -67,6 +69,49 @@ pub enum Event<'a, T: 'static> {
         event: DeviceEvent,
     },
 
+    /// Emitted when a monitor has been connected.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    MonitorAdded(MonitorHandle),
+
+    /// Emitted when a monitor has been disconnected. Contains the last known state of the monitor.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The compositor has already forgotten the monitor, so its handle only
+    ///   returns default values.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    MonitorRemoved(MonitorHandle),
+
+    /// Emitted when the configuration of a monitor has changed, such as its position, resolution,
+    /// orientation, video mode or scale factor.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    MonitorChanged(MonitorHandle),
+
+    /// Emitted when a file descriptor registered with the event loop is ready for the operations
+    /// it was registered for. It keeps being emitted once per iteration until it isn't ready
+    /// anymore, so it has to be read from or written to.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Unix:** See `EventLoopWindowTargetExtUnix::register_fd`.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    FdReady {
+        source: SourceId,
+        readable: bool,
+        writable: bool,
+    },
+
+    /// Emitted when a timer set with
+    /// [`EventLoopWindowTarget::set_timer`](crate::event_loop::EventLoopWindowTarget::set_timer)
+    /// has fired.
+    TimerExpired(TimerId),
+
     /// Emitted when an event is sent from [`EventLoopProxy::send_event`](crate::event_loop::EventLoopProxy::send_event)
     UserEvent(T),
 
@@ -127,6 +172,19 @@ impl<T: Clone> Clone for Event<'static, T> {
                 event: event.clone(),
             },
             UserEvent(event) => UserEvent(event.clone()),
+            MonitorAdded(monitor) => MonitorAdded(monitor.clone()),
+            MonitorRemoved(monitor) => MonitorRemoved(monitor.clone()),
+            MonitorChanged(monitor) => MonitorChanged(monitor.clone()),
+            FdReady {
+                source,
+                readable,
+                writable,
+            } => FdReady {
+                source: *source,
+                readable: *readable,
+                writable: *writable,
+            },
+            TimerExpired(source) => TimerExpired(*source),
             DeviceEvent { device_id, event } => DeviceEvent {
                 device_id: *device_id,
                 event: event.clone(),
@@ -149,6 +207,19 @@ impl<'a, T> Event<'a, T> {
             UserEvent(_) => Err(self),
             WindowEvent { window_id, event } => Ok(WindowEvent { window_id, event }),
             DeviceEvent { device_id, event } => Ok(DeviceEvent { device_id, event }),
+            MonitorAdded(monitor) => Ok(MonitorAdded(monitor)),
+            MonitorRemoved(monitor) => Ok(MonitorRemoved(monitor)),
+            MonitorChanged(monitor) => Ok(MonitorChanged(monitor)),
+            FdReady {
+                source,
+                readable,
+                writable,
+            } => Ok(FdReady {
+                source,
+                readable,
+                writable,
+            }),
+            TimerExpired(source) => Ok(TimerExpired(source)),
             NewEvents(cause) => Ok(NewEvents(cause)),
             MainEventsCleared => Ok(MainEventsCleared),
             RedrawRequested(wid) => Ok(RedrawRequested(wid)),
@@ -169,6 +240,19 @@ impl<'a, T> Event<'a, T> {
                 .map(|event| WindowEvent { window_id, event }),
             UserEvent(event) => Some(UserEvent(event)),
             DeviceEvent { device_id, event } => Some(DeviceEvent { device_id, event }),
+            MonitorAdded(monitor) => Some(MonitorAdded(monitor)),
+            MonitorRemoved(monitor) => Some(MonitorRemoved(monitor)),
+            MonitorChanged(monitor) => Some(MonitorChanged(monitor)),
+            FdReady {
+                source,
+                readable,
+                writable,
+            } => Some(FdReady {
+                source,
+                readable,
+                writable,
+            }),
+            TimerExpired(source) => Some(TimerExpired(source)),
             NewEvents(cause) => Some(NewEvents(cause)),
             MainEventsCleared => Some(MainEventsCleared),
             RedrawRequested(wid) => Some(RedrawRequested(wid)),
@@ -198,6 +282,12 @@ pub enum StartCause {
         requested_resume: Option<Instant>,
     },
 
+    /// Sent if a timer set with
+    /// [`EventLoopWindowTarget::set_timer`](crate::event_loop::EventLoopWindowTarget::set_timer)
+    /// fired after a wait was requested. Contains the timer which fired first, the ones which
+    /// fired with it being reported by the `TimerExpired` events of the iteration.
+    TimerFired(TimerId),
+
     /// Sent if the event loop is being resumed after the loop's control flow was set to
     /// `ControlFlow::Poll`.
     Poll,
@@ -215,6 +305,54 @@ pub enum WindowEvent<'a> {
     /// The position of the window has changed. Contains the window's new position.
     Moved(PhysicalPosition<i32>),
 
+    /// The window has been maximized, minimized, made fullscreen or tiled, or restored, usually
+    /// by the window manager. Contains the window's new state.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Follows `_NET_WM_STATE` and `WM_STATE`. Tiled states are never reported.
+    /// - **Wayland:** Follows the `xdg_toplevel` configure states. Minimized is never reported,
+    ///   and tiled states require the compositor to support version 2 of `xdg_wm_base`.
+    /// - **Headless:** Emitted when `set_maximized`, `set_minimized` or `set_fullscreen` change the
+    ///   state.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    StateChanged(WindowState),
+
+    /// The window has been occluded (completely hidden from view), or is visible again.
+    ///
+    /// This covers the window being minimized, unmapped or fully covered by other windows, so
+    /// applications can stop drawing while it's `true`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Follows `VisibilityNotify` and unmapping, so windows covered by others are only
+    ///   reported as occluded without a compositing window manager.
+    /// - **Wayland:** Derived from frame callbacks, which compositors stop sending for hidden
+    ///   surfaces: a window is occluded once the frame callback requested along with
+    ///   `RedrawRequested` is still pending a second later, which requires the window to be drawn
+    ///   after each `RedrawRequested`. A window not drawn while handling `RedrawRequested`, for
+    ///   example because nothing changed, is reported as occluded until it's drawn again.
+    /// - **Headless:** Emitted when the window is hidden or minimized, and when it's visible again.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    Occluded(bool),
+
+    /// The frame requested with `Window::request_frame_callback` is due, `RedrawRequested` being
+    /// emitted for the window later in the same iteration.
+    ///
+    /// `presentation_time` is when the previous frame was presented, or when the compositor
+    /// started to prepare the next one, measured from an unspecified epoch. Only the differences
+    /// between the times of a window are meaningful, which lets animations pace themselves.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Follows `_NET_WM_FRAME_DRAWN`, the time being in microseconds of the monotonic
+    ///   clock. Only emitted with a compositing window manager supporting it and the XSync
+    ///   extension.
+    /// - **Wayland:** Follows the `wl_surface.frame` callbacks, the time having a millisecond
+    ///   granularity.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    FrameCallback { presentation_time: Duration },
+
     /// The window has been requested to close.
     CloseRequested,
 
@@ -231,17 +369,130 @@ pub enum WindowEvent<'a> {
     ///
     /// When the user hovers multiple files at once, this event will be emitted for each file
     /// separately.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Not emitted, as the files are only read once dropped.
     HoveredFile(PathBuf),
 
     /// A file was hovered, but has exited the window.
     ///
     /// There will be a single `HoveredFileCancelled` event triggered even if multiple files were
     /// hovered.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Not emitted, as the files are only read once dropped.
     HoveredFileCancelled,
 
+    /// A drag and drop operation has entered the window.
+    ///
+    /// While the drag moves over the window, `DragMoved` events are emitted, and the operation
+    /// ends with either `DragDropped` or `DragLeft`. The window accepts or rejects the drag with
+    /// [`Window::accept_drag`] and [`Window::reject_drag`], whose answer may change on every
+    /// event. Drags offering files are accepted by default.
+    ///
+    /// These events are emitted along with the `HoveredFile`, `DroppedFile` and
+    /// `HoveredFileCancelled` events.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Emitted on the first `XdndPosition` message, since `XdndEnter` doesn't tell
+    ///   where the drag is.
+    /// - **Wayland:** Sources can't propose `DragAction::Link`.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ///
+    /// [`Window::accept_drag`]: crate::window::Window::accept_drag
+    /// [`Window::reject_drag`]: crate::window::Window::reject_drag
+    DragEntered {
+        /// The MIME types the dragged data is offered in.
+        mime_types: Vec<String>,
+        /// The position of the cursor, relative to the window.
+        position: PhysicalPosition<f64>,
+        /// The action proposed by the source of the drag.
+        action: DragAction,
+    },
+
+    /// A drag and drop operation has moved over the window, or its proposed action has changed.
+    DragMoved {
+        position: PhysicalPosition<f64>,
+        action: DragAction,
+    },
+
+    /// A drag and drop operation accepted by the window has been dropped on it.
+    ///
+    /// The contents hold the data in the MIME type the drag was accepted with, and the action is
+    /// the one it was accepted with.
+    DragDropped {
+        contents: ClipboardContents,
+        position: PhysicalPosition<f64>,
+        action: DragAction,
+    },
+
+    /// A drag and drop operation has left the window, was cancelled, or was dropped on it while
+    /// rejected.
+    DragLeft,
+
+    /// The target under a drag started with [`Window::start_drag`] has changed the action it
+    /// would perform if the drag was dropped on it, or would reject the drop if `None`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ///
+    /// [`Window::start_drag`]: crate::window::Window::start_drag
+    DragSourceAction(Option<DragAction>),
+
+    /// A drag started with [`Window::start_drag`] has been dropped, and its target has finished
+    /// performing the given action. If the action is `DragAction::Move`, the data should now be
+    /// deleted.
+    ///
+    /// [`Window::start_drag`]: crate::window::Window::start_drag
+    DragSourceFinished(DragAction),
+
+    /// A drag started with [`Window::start_drag`] has been cancelled, dropped where nothing
+    /// accepted it, or its target failed to receive the data.
+    ///
+    /// [`Window::start_drag`]: crate::window::Window::start_drag
+    DragSourceCancelled,
+
     /// The window received a unicode character.
     ReceivedCharacter(char),
 
+    /// The composition (preedit) string of the input method changed.
+    ///
+    /// `text` is the whole string being composed, and should be drawn by the application at the
+    /// position given to [`Window::set_ime_position`]. `cursor_range` is the byte range of the
+    /// cursor within `text`, if the input method wants one to be shown. An empty `text` means
+    /// the composition was cleared.
+    ///
+    /// Only emitted for windows which have input methods allowed with
+    /// [`Window::set_ime_allowed`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Requires an input method supporting the `XIMPreeditCallbacks` style.
+    /// - **Wayland:** Requires the compositor to support `zwp_text_input_v3`.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ///
+    /// [`Window::set_ime_position`]: crate::window::Window::set_ime_position
+    /// [`Window::set_ime_allowed`]: crate::window::Window::set_ime_allowed
+    ImePreedit {
+        text: String,
+        cursor_range: Option<(usize, usize)>,
+    },
+
+    /// The input method committed a string, which should be inserted in place of the current
+    /// composition.
+    ///
+    /// Committed text is reported with `ReceivedCharacter` instead for windows which don't have
+    /// input methods allowed.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ImeCommit(String),
+
     /// The window gained or lost focus.
     ///
     /// The parameter is true if the window has gained focus, and false if it has lost focus.
@@ -317,6 +568,49 @@ pub enum WindowEvent<'a> {
         stage: i64,
     },
 
+    /// A two-finger pinch gesture, usually on a touchpad, has started, progressed or ended.
+    ///
+    /// Once started with a `TouchPhase::Started` event, a gesture is made of `TouchPhase::Moved`
+    /// events, and ends with either a `TouchPhase::Ended` or a `TouchPhase::Cancelled` event.
+    ///
+    /// Hold gestures aren't reported: XInput 2.4 has none, and they were only added in version 3
+    /// of `zwp_pointer_gestures_v1`, which the Wayland protocol bindings in use don't provide.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Requires XInput 2.4.
+    /// - **Wayland:** Requires `zwp_pointer_gestures_v1`.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    PinchGesture {
+        device_id: DeviceId,
+        phase: TouchPhase,
+        /// The change of the scale since the previous event, where the scale is 1.0 at the start
+        /// of the gesture. Positive values mean the fingers are moving apart, which usually
+        /// zooms in.
+        scale_delta: f64,
+        /// The rotation since the previous event, in degrees clockwise.
+        rotation_delta: f64,
+    },
+
+    /// A swipe gesture with three or more fingers, usually on a touchpad, has started, progressed
+    /// or ended.
+    ///
+    /// The phases of the gesture follow those of `WindowEvent::PinchGesture`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Requires XInput 2.4.
+    /// - **Wayland:** Requires `zwp_pointer_gestures_v1`.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    SwipeGesture {
+        device_id: DeviceId,
+        phase: TouchPhase,
+        /// The number of fingers taking part in the gesture.
+        fingers: u32,
+        /// The motion of the center of the fingers since the previous event, in pixels.
+        delta: PhysicalPosition<f64>,
+    },
+
     /// Motion on some analog axis. May report data redundant to other, more specific events.
     AxisMotion {
         device_id: DeviceId,
@@ -327,6 +621,17 @@ pub enum WindowEvent<'a> {
     /// Touch event has been received
     Touch(Touch),
 
+    /// A pen, or another tool of a graphics tablet, has moved, touched the tablet or changed state.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Tools are the XInput2 devices with an "Abs Pressure" axis, which also move the
+    ///   pointer. XInput2 doesn't report proximity, so `ProximityIn` is sent before the first event
+    ///   of a tool over a window, and `ProximityOut` when the pointer leaves the window.
+    /// - **Wayland:** Requires `zwp_tablet_manager_v2`. Tools don't move the pointer.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    PenInput(PenInput),
+
     /// The window's scale factor has changed.
     ///
     /// The following user actions can cause DPI changes:
@@ -350,8 +655,28 @@ pub enum WindowEvent<'a> {
     /// Applications might wish to react to this to change the theme of the content of the window
     /// when the system changes the window theme.
     ///
-    /// At the moment this is only supported on Windows.
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Follows the `Net/ThemeName` XSETTINGS setting, themes whose name ends with
+    ///   `-dark` being dark.
+    /// - **macOS / iOS / Android / Wayland:** Unsupported.
     ThemeChanged(Theme),
+
+    /// The contents of a clipboard have changed.
+    ///
+    /// This is emitted whenever another client takes ownership of the clipboard, or when this
+    /// window gives it up.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Changes made by other clients are only reported if the XFixes extension is
+    ///   available, in which case they're reported to every window. Changes made by this
+    ///   application with `Window::set_clipboard_contents` aren't reported.
+    /// - **Wayland:** Only changes to [`ClipboardKind::Clipboard`] are reported, and only to the
+    ///   window which has keyboard focus. The event is also emitted when a window gains focus,
+    ///   and for the changes made by this application.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ClipboardChanged(ClipboardKind),
 }
 
 impl Clone for WindowEvent<'static> {
@@ -360,12 +685,48 @@ impl Clone for WindowEvent<'static> {
         return match self {
             Resized(size) => Resized(size.clone()),
             Moved(pos) => Moved(pos.clone()),
+            StateChanged(state) => StateChanged(*state),
+            Occluded(occluded) => Occluded(*occluded),
+            FrameCallback { presentation_time } => FrameCallback {
+                presentation_time: *presentation_time,
+            },
             CloseRequested => CloseRequested,
             Destroyed => Destroyed,
             DroppedFile(file) => DroppedFile(file.clone()),
             HoveredFile(file) => HoveredFile(file.clone()),
             HoveredFileCancelled => HoveredFileCancelled,
+            DragEntered {
+                mime_types,
+                position,
+                action,
+            } => DragEntered {
+                mime_types: mime_types.clone(),
+                position: *position,
+                action: *action,
+            },
+            DragMoved { position, action } => DragMoved {
+                position: *position,
+                action: *action,
+            },
+            DragDropped {
+                contents,
+                position,
+                action,
+            } => DragDropped {
+                contents: contents.clone(),
+                position: *position,
+                action: *action,
+            },
+            DragLeft => DragLeft,
+            DragSourceAction(action) => DragSourceAction(*action),
+            DragSourceFinished(action) => DragSourceFinished(*action),
+            DragSourceCancelled => DragSourceCancelled,
             ReceivedCharacter(c) => ReceivedCharacter(*c),
+            ImePreedit { text, cursor_range } => ImePreedit {
+                text: text.clone(),
+                cursor_range: *cursor_range,
+            },
+            ImeCommit(text) => ImeCommit(text.clone()),
             Focused(f) => Focused(*f),
             KeyboardInput {
                 device_id,
@@ -373,7 +734,7 @@ impl Clone for WindowEvent<'static> {
                 is_synthetic,
             } => KeyboardInput {
                 device_id: *device_id,
-                input: *input,
+                input: input.clone(),
                 is_synthetic: *is_synthetic,
             },
 
@@ -427,6 +788,28 @@ impl Clone for WindowEvent<'static> {
                 pressure: *pressure,
                 stage: *stage,
             },
+            PinchGesture {
+                device_id,
+                phase,
+                scale_delta,
+                rotation_delta,
+            } => PinchGesture {
+                device_id: *device_id,
+                phase: *phase,
+                scale_delta: *scale_delta,
+                rotation_delta: *rotation_delta,
+            },
+            SwipeGesture {
+                device_id,
+                phase,
+                fingers,
+                delta,
+            } => SwipeGesture {
+                device_id: *device_id,
+                phase: *phase,
+                fingers: *fingers,
+                delta: *delta,
+            },
             AxisMotion {
                 device_id,
                 axis,
@@ -437,7 +820,9 @@ impl Clone for WindowEvent<'static> {
                 value: *value,
             },
             Touch(touch) => Touch(*touch),
+            PenInput(input) => PenInput(*input),
             ThemeChanged(theme) => ThemeChanged(theme.clone()),
+            ClipboardChanged(kind) => ClipboardChanged(*kind),
             ScaleFactorChanged { .. } => {
                 unreachable!("Static event can't be about scale factor changing")
             }
@@ -451,12 +836,40 @@ impl<'a> WindowEvent<'a> {
         match self {
             Resized(size) => Some(Resized(size)),
             Moved(position) => Some(Moved(position)),
+            StateChanged(state) => Some(StateChanged(state)),
+            Occluded(occluded) => Some(Occluded(occluded)),
+            FrameCallback { presentation_time } => Some(FrameCallback { presentation_time }),
             CloseRequested => Some(CloseRequested),
             Destroyed => Some(Destroyed),
             DroppedFile(file) => Some(DroppedFile(file)),
             HoveredFile(file) => Some(HoveredFile(file)),
             HoveredFileCancelled => Some(HoveredFileCancelled),
+            DragEntered {
+                mime_types,
+                position,
+                action,
+            } => Some(DragEntered {
+                mime_types,
+                position,
+                action,
+            }),
+            DragMoved { position, action } => Some(DragMoved { position, action }),
+            DragDropped {
+                contents,
+                position,
+                action,
+            } => Some(DragDropped {
+                contents,
+                position,
+                action,
+            }),
+            DragLeft => Some(DragLeft),
+            DragSourceAction(action) => Some(DragSourceAction(action)),
+            DragSourceFinished(action) => Some(DragSourceFinished(action)),
+            DragSourceCancelled => Some(DragSourceCancelled),
             ReceivedCharacter(c) => Some(ReceivedCharacter(c)),
+            ImePreedit { text, cursor_range } => Some(ImePreedit { text, cursor_range }),
+            ImeCommit(text) => Some(ImeCommit(text)),
             Focused(focused) => Some(Focused(focused)),
             KeyboardInput {
                 device_id,
@@ -513,6 +926,28 @@ impl<'a> WindowEvent<'a> {
                 pressure,
                 stage,
             }),
+            PinchGesture {
+                device_id,
+                phase,
+                scale_delta,
+                rotation_delta,
+            } => Some(PinchGesture {
+                device_id,
+                phase,
+                scale_delta,
+                rotation_delta,
+            }),
+            SwipeGesture {
+                device_id,
+                phase,
+                fingers,
+                delta,
+            } => Some(SwipeGesture {
+                device_id,
+                phase,
+                fingers,
+                delta,
+            }),
             AxisMotion {
                 device_id,
                 axis,
@@ -523,7 +958,9 @@ impl<'a> WindowEvent<'a> {
                 value,
             }),
             Touch(touch) => Some(Touch(touch)),
+            PenInput(input) => Some(PenInput(input)),
             ThemeChanged(theme) => Some(ThemeChanged(theme)),
+            ClipboardChanged(kind) => Some(ClipboardChanged(kind)),
             ScaleFactorChanged { .. } => None,
         }
     }
@@ -597,7 +1034,7 @@ pub enum DeviceEvent {
 }
 
 /// Describes a keyboard input event.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct KeyboardInput {
     /// Identifies the physical key pressed
@@ -607,6 +1044,15 @@ pub struct KeyboardInput {
     /// game.
     pub scancode: ScanCode,
 
+    /// Identifies the physical key pressed, independently of the keyboard layout.
+    ///
+    /// Unlike `scancode`, this has the same meaning on every platform.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / iOS / Android:** Always `KeyCode::Unidentified`.
+    pub physical_key: KeyCode,
+
     pub state: ElementState,
 
     /// Identifies the semantic meaning of the key
@@ -615,6 +1061,30 @@ pub struct KeyboardInput {
     /// implementing appropriate behavior for "page up."
     pub virtual_keycode: Option<VirtualKeyCode>,
 
+    /// Identifies the meaning of the key, according to the keyboard layout and modifiers.
+    ///
+    /// The same key is reported for the press and the release of a key, unless the modifiers
+    /// changed in between.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / iOS / Android / Web:** Always `Key::Unidentified`.
+    pub logical_key: Key,
+
+    /// The text produced by this key press, with dead keys and compose sequences applied.
+    ///
+    /// This is `None` for key releases and for keys that don't produce text, such as a dead key or
+    /// any other key in the middle of a compose sequence. The key that completes a sequence
+    /// carries the composed text. This removes the need to match `KeyboardInput` events with
+    /// `ReceivedCharacter` ones, which are still sent for compatibility.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The text of compose sequences handled by the input method is only reported
+    ///   through `ReceivedCharacter`.
+    /// - **Windows / macOS / iOS / Android / Web:** Always `None`.
+    pub text: Option<String>,
+
     /// Modifier keys active at the time of this input.
     ///
     /// This is tracked internally to avoid tracking errors arising from modifier key state changes when events from
@@ -721,6 +1191,98 @@ impl Force {
     }
 }
 
+/// The action performed by a drag and drop operation once it is dropped.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DragAction {
+    /// The data is copied to the target.
+    Copy,
+    /// The data is moved to the target, which means the source deletes it once dropped.
+    Move,
+    /// The target links to the data.
+    Link,
+}
+
+/// Describes the state of a tablet tool.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PenPhase {
+    /// The tool came into proximity of the tablet, over the window.
+    ProximityIn,
+    /// The tip of the tool touched the tablet.
+    Down,
+    /// The tool moved, or one of its axes or buttons changed.
+    Moved,
+    /// The tip of the tool was lifted from the tablet.
+    Up,
+    /// The tool left the proximity of the tablet, or the window.
+    ProximityOut,
+}
+
+/// The kind of a tablet tool.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PenTool {
+    Pen,
+    /// The eraser end of a pen, or a dedicated eraser.
+    Eraser,
+    Brush,
+    Pencil,
+    Airbrush,
+    /// A tool that isn't any of the above, such as a tablet mouse.
+    Unknown,
+}
+
+bitflags! {
+    /// The state of a window, as reported by [`WindowEvent::StateChanged`].
+    #[derive(Default)]
+    pub struct WindowState: u32 {
+        const MAXIMIZED = 1 << 0;
+        const MINIMIZED = 1 << 1;
+        const FULLSCREEN = 1 << 2;
+        /// The left edge of the window is tiled against another window or the edge of the
+        /// screen.
+        const TILED_LEFT = 1 << 3;
+        const TILED_RIGHT = 1 << 4;
+        const TILED_TOP = 1 << 5;
+        const TILED_BOTTOM = 1 << 6;
+    }
+}
+
+bitflags! {
+    /// The barrel buttons held on a tablet tool.
+    #[derive(Default)]
+    pub struct PenButtons: u32 {
+        /// The button closest to the tip.
+        const PRIMARY = 1 << 0;
+        const SECONDARY = 1 << 1;
+        const TERTIARY = 1 << 2;
+    }
+}
+
+/// Represents an event of a tablet tool.
+///
+/// Every time a tool comes close to the tablet over a window, a `ProximityIn` event is generated,
+/// which is eventually followed by a `ProximityOut` event. In between, the tip touching and leaving
+/// the tablet is reported with `Down` and `Up` events, and any other change with `Moved` events.
+///
+/// Axes which aren't supported by the tool are `None`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PenInput {
+    pub device_id: DeviceId,
+    pub phase: PenPhase,
+    pub tool: PenTool,
+    pub location: PhysicalPosition<f64>,
+    /// The pressure of the tip, between 0.0 and 1.0 inclusive.
+    pub pressure: Option<f64>,
+    /// The tilt of the tool in degrees from the perpendicular, towards the right and towards the
+    /// bottom of the tablet respectively.
+    pub tilt: Option<(f64, f64)>,
+    /// The rotation of the tool around its axis, in degrees clockwise.
+    pub rotation: Option<f64>,
+    pub buttons: PenButtons,
+}
+
 /// Hardware-dependent keyboard scan code.
 pub type ScanCode = u32;
 
@@ -767,6 +1329,401 @@ pub enum MouseScrollDelta {
     PixelDelta(LogicalPosition<f64>),
 }
 
+/// The physical location of a key on the keyboard, independent of the keyboard layout.
+///
+/// The names follow the [`code` values of the W3C UI Events specification][w3c], and are those of
+/// the keys of a US QWERTY keyboard. For example, `KeyCode::KeyW` is reported for the key that
+/// produces 'Z' on an AZERTY keyboard, which makes it the right choice for movement controls.
+///
+/// [w3c]: https://www.w3.org/TR/uievents-code/
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KeyCode {
+    /// The key couldn't be identified, or the platform doesn't report physical keys.
+    Unidentified,
+
+    // The keys of the writing system section of the keyboard.
+    Backquote,
+    Backslash,
+    BracketLeft,
+    BracketRight,
+    Comma,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Equal,
+    IntlBackslash,
+    IntlRo,
+    IntlYen,
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Minus,
+    Period,
+    Quote,
+    Semicolon,
+    Slash,
+
+    // Functional keys.
+    AltLeft,
+    AltRight,
+    Backspace,
+    CapsLock,
+    ContextMenu,
+    ControlLeft,
+    ControlRight,
+    Enter,
+    MetaLeft,
+    MetaRight,
+    ShiftLeft,
+    ShiftRight,
+    Space,
+    Tab,
+    Convert,
+    KanaMode,
+    Lang1,
+    Lang2,
+    Lang3,
+    Lang4,
+    Lang5,
+    NonConvert,
+
+    // The control pad.
+    Delete,
+    End,
+    Help,
+    Home,
+    Insert,
+    PageDown,
+    PageUp,
+
+    // The arrow pad.
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+
+    // The numpad.
+    NumLock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadBackspace,
+    NumpadClear,
+    NumpadClearEntry,
+    NumpadComma,
+    NumpadDecimal,
+    NumpadDivide,
+    NumpadEnter,
+    NumpadEqual,
+    NumpadHash,
+    NumpadMemoryAdd,
+    NumpadMemoryClear,
+    NumpadMemoryRecall,
+    NumpadMemoryStore,
+    NumpadMemorySubtract,
+    NumpadMultiply,
+    NumpadParenLeft,
+    NumpadParenRight,
+    NumpadStar,
+    NumpadSubtract,
+
+    // The function section.
+    Escape,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    Fn,
+    FnLock,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+
+    // Media keys.
+    BrowserBack,
+    BrowserFavorites,
+    BrowserForward,
+    BrowserHome,
+    BrowserRefresh,
+    BrowserSearch,
+    BrowserStop,
+    Eject,
+    LaunchApp1,
+    LaunchApp2,
+    LaunchMail,
+    MediaPlayPause,
+    MediaSelect,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    Power,
+    Sleep,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    AudioVolumeUp,
+    WakeUp,
+
+    // Legacy and non-standard keys.
+    Again,
+    Copy,
+    Cut,
+    Find,
+    Open,
+    Paste,
+    Props,
+    Select,
+    Undo,
+    Hiragana,
+    Katakana,
+}
+
+/// The meaning of a key, according to the keyboard layout and the active modifiers.
+///
+/// This follows the [`key` values of the W3C UI Events specification][w3c]. For example, the key
+/// located at `KeyCode::KeyQ` is `Key::Character("a")` on an AZERTY keyboard, and
+/// `Key::Character("A")` when Shift is held.
+///
+/// [w3c]: https://www.w3.org/TR/uievents-key/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Key {
+    /// A key that produces a character, such as `"a"`, `"Ä"` or `" "`.
+    Character(String),
+
+    /// A key with a name, which doesn't produce a character on its own.
+    Named(NamedKey),
+
+    /// A dead key, which changes the character produced by the next key press instead of
+    /// producing one itself.
+    ///
+    /// Contains the accent it adds, such as `'´'` for an acute accent, when it's known.
+    Dead(Option<char>),
+
+    /// The key couldn't be identified, or the platform doesn't report logical keys.
+    Unidentified,
+}
+
+/// The keys of [`Key::Named`], named after the W3C `key` values.
+///
+/// [`Key::Named`]: Key::Named
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NamedKey {
+    // Modifier keys.
+    Alt,
+    AltGraph,
+    CapsLock,
+    Control,
+    Fn,
+    FnLock,
+    Hyper,
+    Meta,
+    NumLock,
+    ScrollLock,
+    Shift,
+    Super,
+
+    // Whitespace keys.
+    Enter,
+    Tab,
+
+    // Navigation keys.
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    End,
+    Home,
+    PageDown,
+    PageUp,
+
+    // Editing keys.
+    Backspace,
+    Clear,
+    Copy,
+    Cut,
+    Delete,
+    Insert,
+    Paste,
+    Redo,
+    Undo,
+
+    // UI keys.
+    Cancel,
+    ContextMenu,
+    Escape,
+    Execute,
+    Find,
+    Help,
+    Pause,
+    Select,
+    ZoomIn,
+    ZoomOut,
+
+    // Device keys.
+    BrightnessDown,
+    BrightnessUp,
+    Eject,
+    LogOff,
+    Power,
+    PowerOff,
+    PrintScreen,
+    Hibernate,
+    Standby,
+    WakeUp,
+
+    // IME and composition keys.
+    AllCandidates,
+    CodeInput,
+    Compose,
+    Convert,
+    GroupFirst,
+    GroupLast,
+    GroupNext,
+    GroupPrevious,
+    ModeChange,
+    NonConvert,
+    PreviousCandidate,
+    SingleCandidate,
+    HangulMode,
+    HanjaMode,
+    Eisu,
+    Hankaku,
+    Hiragana,
+    HiraganaKatakana,
+    KanjiMode,
+    Katakana,
+    Romaji,
+    Zenkaku,
+    ZenkakuHankaku,
+
+    // Function keys.
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    F25,
+    F26,
+    F27,
+    F28,
+    F29,
+    F30,
+    F31,
+    F32,
+    F33,
+    F34,
+    F35,
+
+    // Document keys.
+    Close,
+    New,
+    Open,
+    Save,
+
+    // Multimedia keys.
+    MediaPause,
+    MediaPlayPause,
+    MediaRecord,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    AudioVolumeUp,
+
+    // Application and browser keys.
+    LaunchApplication1,
+    LaunchApplication2,
+    LaunchMail,
+    LaunchMediaPlayer,
+    BrowserBack,
+    BrowserFavorites,
+    BrowserForward,
+    BrowserHome,
+    BrowserRefresh,
+    BrowserSearch,
+    BrowserStop,
+}
+
 /// Symbolic name for a keyboard key.
 #[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
 #[repr(u32)]
@@ -11,9 +11,9 @@
 //! [send_event]: crate::event_loop::EventLoopProxy::send_event
 use instant::Instant;
 use std::ops::Deref;
-use std::{error, fmt};
+use std::{error, fmt, time::Duration};
 
-use crate::{event::Event, monitor::MonitorHandle, platform_impl};
+use crate::{error::ExternalError, event::Event, monitor::MonitorHandle, platform_impl};
 
 /// Provides a way to retrieve events from the system and from the windows that were registered to
 /// the events loop.
@@ -93,6 +93,26 @@ impl Default for ControlFlow {
     }
 }
 
+/// Identifies a file descriptor registered with the event loop, in the events emitted for it.
+///
+/// See `EventLoopWindowTargetExtUnix::register_fd`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceId(pub(crate) u64);
+
+/// When a timer set with [`EventLoopWindowTarget::set_timer`] fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timer {
+    /// Fires once, when the given time is reached.
+    Deadline(Instant),
+    /// Fires every time the given duration elapses, starting from when the timer is set, until
+    /// it's cancelled.
+    Interval(Duration),
+}
+
+/// Identifies a timer set with [`EventLoopWindowTarget::set_timer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(pub(crate) u64);
+
 impl EventLoop<()> {
     /// Builds a new event loop with a `()` as the user event type.
     ///
@@ -174,6 +194,40 @@ impl<T> EventLoop<T> {
     }
 }
 
+impl<T> EventLoopWindowTarget<T> {
+    /// Sets a timer, which emits [`Event::TimerExpired`] with the returned id when it fires.
+    ///
+    /// Any number of timers can be set at once. The loop wakes up for them whatever the control
+    /// flow is, starting the iteration with [`StartCause::TimerFired`] unless the deadline of
+    /// `ControlFlow::WaitUntil` was reached too. The timers which fired together are all reported
+    /// in the same iteration, in the order of their deadlines. An interval timer which missed
+    /// several of its periods, for example because the callback was busy, only fires once and
+    /// then keeps its schedule.
+    ///
+    /// Returns an `ExternalError::Os` error for a zero `Timer::Interval`, which would keep the
+    /// loop busy, and for an interval too long to compute the deadline of.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Headless:** The timers follow the virtual clock, which skips right to the next one
+    ///   when the loop would otherwise wait.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported, always returns
+    ///   `ExternalError::NotSupported`.
+    ///
+    /// [`StartCause::TimerFired`]: crate::event::StartCause::TimerFired
+    #[inline]
+    pub fn set_timer(&self, timer: Timer) -> Result<TimerId, ExternalError> {
+        self.p.set_timer(timer)
+    }
+
+    /// Cancels a timer set with [`set_timer`](EventLoopWindowTarget::set_timer). Nothing happens if it was a
+    /// deadline timer which already fired.
+    #[inline]
+    pub fn cancel_timer(&self, id: TimerId) {
+        self.p.cancel_timer(id)
+    }
+}
+
 impl<T> Deref for EventLoop<T> {
     type Target = EventLoopWindowTarget<T>;
     fn deref(&self) -> &EventLoopWindowTarget<T> {
@@ -13,7 +13,8 @@ pub(crate) struct Pixel {
 pub(crate) const PIXEL_SIZE: usize = mem::size_of::<Pixel>();
 
 #[derive(Debug)]
-/// An error produced when using `Icon::from_rgba` with invalid arguments.
+/// An error produced when using `Icon::from_rgba` or `CustomCursor::from_rgba` with invalid
+/// arguments.
 pub enum BadIcon {
     /// Produced when the length of the `rgba` argument isn't divisible by 4, thus `rgba` can't be
     /// safely interpreted as 32bpp RGBA pixels.
@@ -26,6 +27,15 @@ pub enum BadIcon {
         width_x_height: usize,
         pixel_count: usize,
     },
+    /// Produced when the hotspot of a cursor isn't within its image.
+    HotspotOutOfBounds {
+        width: u32,
+        height: u32,
+        hotspot_x: u32,
+        hotspot_y: u32,
+    },
+    /// Produced when `CustomCursor::from_animation` is given no frames.
+    NoFrames,
     /// Produced when underlying OS functionality failed to create the icon
     OsError(io::Error),
 }
@@ -46,6 +56,16 @@ impl fmt::Display for BadIcon {
                 "The specified dimensions ({:?}x{:?}) don't match the number of pixels supplied by the `rgba` argument ({:?}). For those dimensions, the expected pixel count is {:?}.",
                 width, height, pixel_count, width_x_height,
             ),
+            BadIcon::HotspotOutOfBounds {
+                width,
+                height,
+                hotspot_x,
+                hotspot_y,
+            } => write!(f,
+                "The specified hotspot ({:?}, {:?}) is outside of the image bounds ({:?}x{:?}).",
+                hotspot_x, hotspot_y, width, height,
+            ),
+            BadIcon::NoFrames => write!(f, "An animated cursor needs at least one frame."),
             BadIcon::OsError(e) => write!(f, "OS error when instantiating the icon: {:?}", e),
         }
     }
@@ -152,6 +152,7 @@ extern crate std_web as stdweb;
 pub mod dpi;
 #[macro_use]
 pub mod error;
+mod cursor;
 pub mod event;
 pub mod event_loop;
 mod icon;
@@ -162,6 +162,19 @@ impl MonitorHandle {
         self.inner.scale_factor()
     }
 
+    /// Returns the area of the monitor that isn't covered by panels, docks or taskbars, as the
+    /// position of its top-left corner and its size.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Leaves out the struts of the docks, or uses `_NET_WORKAREA` when there are none.
+    /// - **Wayland:** The compositor doesn't tell, so this is the whole monitor.
+    /// - **iOS / Android / Web:** Always the whole monitor.
+    #[inline]
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        self.inner.work_area()
+    }
+
     /// Returns all fullscreen video modes supported by this monitor.
     ///
     /// ## Platform-specific
@@ -1,27 +1,41 @@
 #![cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
 
-use std::{os::raw, ptr, sync::Arc};
+use std::{
+    os::{raw, unix::io::RawFd},
+    ptr,
+    sync::Arc,
+    time::Duration,
+};
+
+use instant::Instant;
 
 use smithay_client_toolkit::window::{ButtonState as SCTKButtonState, Theme as SCTKTheme};
 
 use crate::{
     dpi::Size,
-    event_loop::{EventLoop, EventLoopWindowTarget},
+    error::ExternalError,
+    event::{DeviceEvent, DeviceId, Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget, SourceId},
     monitor::MonitorHandle,
-    window::{Window, WindowBuilder},
+    window::{Window, WindowBuilder, WindowId},
 };
 
 use crate::platform_impl::{
+    headless,
     x11::{ffi::XVisualInfo, XConnection},
-    EventLoop as LinuxEventLoop, EventLoopWindowTarget as LinuxEventLoopWindowTarget,
-    Window as LinuxWindow,
+    DeviceId as LinuxDeviceId, EventLoop as LinuxEventLoop,
+    EventLoopWindowTarget as LinuxEventLoopWindowTarget, Window as LinuxWindow,
+    WindowId as LinuxWindowId,
 };
 
 // TODO: stupid hack so that glutin can do its work
 #[doc(hidden)]
 pub use crate::platform_impl::x11;
 
-pub use crate::platform_impl::{x11::util::WindowType as XWindowType, XNotSupported};
+pub use crate::platform_impl::{
+    x11::{util::WindowType as XWindowType, XSettings},
+    XNotSupported,
+};
 
 /// Additional methods on `EventLoopWindowTarget` that are specific to Unix.
 pub trait EventLoopWindowTargetExtUnix {
@@ -31,6 +45,9 @@ pub trait EventLoopWindowTargetExtUnix {
     /// True if the `EventLoopWindowTarget` uses X11.
     fn is_x11(&self) -> bool;
 
+    /// True if the `EventLoopWindowTarget` uses the headless backend.
+    fn is_headless(&self) -> bool;
+
     #[doc(hidden)]
     fn xlib_xconnection(&self) -> Option<Arc<XConnection>>;
 
@@ -41,6 +58,36 @@ pub trait EventLoopWindowTargetExtUnix {
     ///
     /// The pointer will become invalid when the winit `EventLoop` is destroyed.
     fn wayland_display(&self) -> Option<*mut raw::c_void>;
+
+    /// Returns the settings of the desktop environment, as published over XSETTINGS. They're
+    /// kept up to date when they change.
+    ///
+    /// Returns `None` if the `EventLoopWindowTarget` doesn't use X11.
+    fn xsettings(&self) -> Option<XSettings>;
+
+    /// Watches `fd`, emitting [`Event::FdReady`] in every iteration of the loop that finds it
+    /// ready for the operations of `interest`, until it's unregistered with
+    /// [`unregister_fd`](#tymethod.unregister_fd).
+    ///
+    /// The file descriptor is still owned by the caller. It must be unregistered before it's
+    /// closed.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Headless:** Unsupported, always returns `ExternalError::NotSupported`.
+    fn register_fd(&self, fd: RawFd, interest: FdInterest) -> Result<SourceId, ExternalError>;
+
+    /// Stops watching a file descriptor registered with [`register_fd`](#tymethod.register_fd).
+    fn unregister_fd(&self, source: SourceId);
+}
+
+bitflags! {
+    /// The operations a file descriptor is watched for by
+    /// [`EventLoopWindowTargetExtUnix::register_fd`].
+    pub struct FdInterest: u8 {
+        const READABLE = 0b01;
+        const WRITABLE = 0b10;
+    }
 }
 
 impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
@@ -51,7 +98,12 @@ impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
 
     #[inline]
     fn is_x11(&self) -> bool {
-        !self.p.is_wayland()
+        self.p.is_x11()
+    }
+
+    #[inline]
+    fn is_headless(&self) -> bool {
+        self.p.is_headless()
     }
 
     #[inline]
@@ -72,10 +124,45 @@ impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
             _ => None,
         }
     }
+
+    #[inline]
+    fn xsettings(&self) -> Option<XSettings> {
+        match self.p {
+            LinuxEventLoopWindowTarget::X(ref e) => Some(e.xsettings()),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn register_fd(&self, fd: RawFd, interest: FdInterest) -> Result<SourceId, ExternalError> {
+        self.p.register_fd(fd, interest)
+    }
+
+    #[inline]
+    fn unregister_fd(&self, source: SourceId) {
+        self.p.unregister_fd(source)
+    }
+}
+
+/// The outcome of [`EventLoopExtUnix::pump_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PumpStatus {
+    /// An iteration of the loop was dispatched, and the loop keeps running.
+    Continue,
+    /// Like `Continue`, but the loop has more to dispatch right away without its file descriptor
+    /// becoming readable, because redraws were requested too late for the last iteration or the
+    /// control flow is `Poll`. The loop should be pumped again without waiting.
+    Pending,
+    /// The control flow was set to `Exit` and `LoopDestroyed` has been dispatched. Pumping the
+    /// loop again does nothing.
+    Exit,
 }
 
 /// Additional methods on `EventLoop` that are specific to Unix.
 pub trait EventLoopExtUnix {
+    /// A type provided by the user that can be passed through `Event::UserEvent`.
+    type UserEvent;
+
     /// Builds a new `EventLoop` that is forced to use X11.
     ///
     /// # Panics
@@ -119,6 +206,54 @@ pub trait EventLoopExtUnix {
     fn new_wayland_any_thread() -> Self
     where
         Self: Sized;
+
+    /// Builds a new `EventLoop` that doesn't connect to any display server.
+    ///
+    /// Its windows only exist in memory, and events have to be injected through
+    /// [`EventLoopWindowTargetExtHeadless`]. See [`EventLoopExtHeadless`] for driving the loop
+    /// one iteration at a time.
+    ///
+    /// Unlike the other backends, the headless backend can be created on any thread.
+    fn new_headless() -> Self
+    where
+        Self: Sized;
+
+    /// Dispatches a single iteration of the event loop, from `NewEvents` to
+    /// `RedrawEventsCleared`, without taking over the thread like `run` does.
+    ///
+    /// The first call starts the loop with `StartCause::Init`. Later calls first wait for events
+    /// as the `ControlFlow` set by the previous iteration asks for, but no longer than `timeout`,
+    /// so a `timeout` of zero never blocks and `None` waits as long as `run` would.
+    ///
+    /// This is meant for applications that already have an event loop of their own, which can
+    /// wait on [`as_raw_fd`](#tymethod.as_raw_fd) and pump winit's events when it's readable.
+    /// Events that the X or Wayland libraries already read from the connection while the
+    /// callback ran are dispatched by further iterations before returning, as they don't make the
+    /// file descriptor readable. When `PumpStatus::Pending` is returned, the loop has to be
+    /// pumped again without waiting on the file descriptor.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Headless:** Equivalent to [`EventLoopExtHeadless::step`], `timeout` being ignored.
+    fn pump_events<F>(&mut self, timeout: Option<Duration>, event_handler: F) -> PumpStatus
+    where
+        F: FnMut(
+            Event<'_, Self::UserEvent>,
+            &EventLoopWindowTarget<Self::UserEvent>,
+            &mut ControlFlow,
+        );
+
+    /// Returns a file descriptor that becomes readable when the event loop has events to
+    /// dispatch with [`pump_events`](#tymethod.pump_events).
+    ///
+    /// It doesn't become readable for pending redraws, nor for the timeouts of
+    /// `ControlFlow::WaitUntil` and timers, which the caller has to keep track of itself, or for
+    /// `ControlFlow::Poll`. `PumpStatus::Pending` reports when the loop shouldn't wait on it.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Headless:** Panics, as there is nothing to wait on.
+    fn as_raw_fd(&self) -> RawFd;
 }
 
 fn wrap_ev<T>(event_loop: LinuxEventLoop<T>) -> EventLoop<T> {
@@ -129,6 +264,8 @@ fn wrap_ev<T>(event_loop: LinuxEventLoop<T>) -> EventLoop<T> {
 }
 
 impl<T> EventLoopExtUnix for EventLoop<T> {
+    type UserEvent = T;
+
     #[inline]
     fn new_any_thread() -> Self {
         wrap_ev(LinuxEventLoop::new_any_thread())
@@ -161,6 +298,156 @@ impl<T> EventLoopExtUnix for EventLoop<T> {
                 .expect("failed to open Wayland connection"),
         )
     }
+
+    #[inline]
+    fn new_headless() -> Self {
+        wrap_ev(LinuxEventLoop::new_headless())
+    }
+
+    #[inline]
+    fn pump_events<F>(&mut self, timeout: Option<Duration>, event_handler: F) -> PumpStatus
+    where
+        F: FnMut(Event<'_, T>, &EventLoopWindowTarget<T>, &mut ControlFlow),
+    {
+        self.event_loop.pump_events(timeout, event_handler)
+    }
+
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.event_loop.as_raw_fd()
+    }
+}
+
+/// The outcome of [`EventLoopExtHeadless::step`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepStatus {
+    /// One iteration of the loop was dispatched, from `NewEvents` to `RedrawEventsCleared`.
+    Dispatched,
+    /// The control flow is `Wait` and no events are pending, so the loop would keep waiting.
+    /// Nothing was dispatched.
+    Idle,
+    /// The control flow was set to `Exit` and `LoopDestroyed` has been dispatched. Stepping the
+    /// loop again does nothing.
+    Exited,
+}
+
+/// Additional methods on an `EventLoop` created with [`EventLoopExtUnix::new_headless`].
+///
+/// All these methods panic if the `EventLoop` uses another backend.
+pub trait EventLoopExtHeadless {
+    /// A type provided by the user that can be passed through `Event::UserEvent`.
+    type UserEvent;
+
+    /// Runs a single iteration of the event loop.
+    ///
+    /// The first call starts the loop with `StartCause::Init`. Later calls start the iteration
+    /// according to the `ControlFlow` set by the previous one. With `ControlFlow::WaitUntil`,
+    /// if no events are pending the virtual clock skips ahead to the deadline and the iteration
+    /// starts with `StartCause::ResumeTimeReached`.
+    ///
+    /// Events emitted while an iteration is being dispatched are delivered on the next one.
+    fn step<F>(&mut self, event_handler: F) -> StepStatus
+    where
+        F: FnMut(
+            Event<'_, Self::UserEvent>,
+            &EventLoopWindowTarget<Self::UserEvent>,
+            &mut ControlFlow,
+        );
+
+    /// Moves the virtual clock of the event loop forward by `duration`.
+    ///
+    /// The virtual clock is what `ControlFlow::WaitUntil` deadlines are compared against, see
+    /// [`EventLoopWindowTargetExtHeadless::now`].
+    fn advance_clock(&mut self, duration: Duration);
+}
+
+impl<T> EventLoopExtHeadless for EventLoop<T> {
+    type UserEvent = T;
+
+    #[inline]
+    fn step<F>(&mut self, event_handler: F) -> StepStatus
+    where
+        F: FnMut(Event<'_, T>, &EventLoopWindowTarget<T>, &mut ControlFlow),
+    {
+        headless_event_loop(&mut self.event_loop).step(event_handler)
+    }
+
+    #[inline]
+    fn advance_clock(&mut self, duration: Duration) {
+        headless_event_loop(&mut self.event_loop).advance_clock(duration)
+    }
+}
+
+fn headless_event_loop<T>(event_loop: &mut LinuxEventLoop<T>) -> &mut headless::EventLoop<T> {
+    match *event_loop {
+        LinuxEventLoop::Headless(ref mut evlp) => evlp,
+        _ => panic!("the event loop doesn't use the headless backend"),
+    }
+}
+
+/// Additional methods on the `EventLoopWindowTarget` of a headless event loop, used to simulate
+/// input.
+///
+/// All these methods panic if the `EventLoopWindowTarget` uses another backend.
+pub trait EventLoopWindowTargetExtHeadless {
+    /// Queues an event for the given window, to be dispatched on the next iteration of the loop.
+    ///
+    /// `Resized` and `Moved` events also update the size and position of the window.
+    ///
+    /// # Panics
+    ///
+    /// If `window_id` doesn't belong to a headless window.
+    fn inject_window_event(&self, window_id: WindowId, event: WindowEvent<'static>);
+
+    /// Queues a device event, to be dispatched on the next iteration of the loop.
+    ///
+    /// The event is reported as coming from [`headless_device_id`](#tymethod.headless_device_id).
+    fn inject_device_event(&self, event: DeviceEvent);
+
+    /// The identifier of the single virtual device of the headless backend, which can be used in
+    /// injected window events.
+    fn headless_device_id(&self) -> DeviceId;
+
+    /// The current time of the virtual clock.
+    ///
+    /// The clock starts at the time the event loop was created, and only moves forward when
+    /// the loop skips to a `ControlFlow::WaitUntil` deadline or when
+    /// [`EventLoopExtHeadless::advance_clock`] is called.
+    fn now(&self) -> Instant;
+}
+
+impl<T> EventLoopWindowTargetExtHeadless for EventLoopWindowTarget<T> {
+    #[inline]
+    fn inject_window_event(&self, window_id: WindowId, event: WindowEvent<'static>) {
+        let window_id = match window_id.0 {
+            LinuxWindowId::Headless(id) => id,
+            _ => panic!("`inject_window_event` called with a non-headless window"),
+        };
+        headless_target(self).inject_window_event(window_id, event)
+    }
+
+    #[inline]
+    fn inject_device_event(&self, event: DeviceEvent) {
+        headless_target(self).inject_device_event(event)
+    }
+
+    #[inline]
+    fn headless_device_id(&self) -> DeviceId {
+        headless_target(self);
+        DeviceId(LinuxDeviceId::Headless(headless::DeviceId))
+    }
+
+    #[inline]
+    fn now(&self) -> Instant {
+        headless_target(self).now()
+    }
+}
+
+fn headless_target<T>(target: &EventLoopWindowTarget<T>) -> &headless::EventLoopWindowTarget<T> {
+    match target.p {
+        LinuxEventLoopWindowTarget::Headless(ref target) => target,
+        _ => panic!("the event loop doesn't use the headless backend"),
+    }
 }
 
 /// Additional methods on `Window` that are specific to Unix.
@@ -280,6 +280,19 @@ pub struct EventLoopWindowTarget<T: 'static> {
     _marker: std::marker::PhantomData<T>,
 }
 
+impl<T> EventLoopWindowTarget<T> {
+    pub fn set_timer(
+        &self,
+        _timer: event_loop::Timer,
+    ) -> Result<event_loop::TimerId, error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
+    pub fn cancel_timer(&self, _id: event_loop::TimerId) {}
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct WindowId;
 
@@ -341,6 +354,10 @@ impl Window {
         // TODO
     }
 
+    pub fn request_frame_callback(&self) {
+        self.request_redraw();
+    }
+
     pub fn inner_position(&self) -> Result<PhysicalPosition<i32>, error::NotSupportedError> {
         Err(error::NotSupportedError::new())
     }
@@ -371,14 +388,34 @@ impl Window {
 
     pub fn set_title(&self, _title: &str) {}
 
+    pub fn title(&self) -> String {
+        String::new()
+    }
+
     pub fn set_visible(&self, _visibility: bool) {}
 
+    pub fn is_visible(&self) -> Option<bool> {
+        None
+    }
+
     pub fn set_resizable(&self, _resizeable: bool) {}
 
+    pub fn is_resizable(&self) -> bool {
+        false
+    }
+
     pub fn set_minimized(&self, _minimized: bool) {}
 
+    pub fn is_minimized(&self) -> Option<bool> {
+        None
+    }
+
     pub fn set_maximized(&self, _maximized: bool) {}
 
+    pub fn is_maximized(&self) -> bool {
+        false
+    }
+
     pub fn set_fullscreen(&self, _monitor: Option<window::Fullscreen>) {
         panic!("Cannot set fullscreen on Android");
     }
@@ -389,20 +426,64 @@ impl Window {
 
     pub fn set_decorations(&self, _decorations: bool) {}
 
+    pub fn is_decorated(&self) -> bool {
+        false
+    }
+
+    pub fn has_focus(&self) -> bool {
+        false
+    }
+
     pub fn set_always_on_top(&self, _always_on_top: bool) {}
 
     pub fn set_window_icon(&self, _window_icon: Option<crate::icon::Icon>) {}
 
     pub fn set_ime_position(&self, _position: Position) {}
 
+    pub fn set_ime_allowed(&self, _allowed: bool) {}
+
     pub fn set_cursor_icon(&self, _: window::CursorIcon) {}
 
+    pub fn set_custom_cursor(&self, _: window::CustomCursor) {}
+
     pub fn set_cursor_position(&self, _: Position) -> Result<(), error::ExternalError> {
         Err(error::ExternalError::NotSupported(
             error::NotSupportedError::new(),
         ))
     }
 
+    pub fn set_clipboard_contents(
+        &self,
+        _: window::ClipboardKind,
+        _: window::ClipboardContents,
+    ) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
+    pub fn clipboard_contents(
+        &self,
+        _: window::ClipboardKind,
+        _: &str,
+    ) -> Result<Option<Vec<u8>>, error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
+    pub fn set_drag_response(&self, _: Option<(String, event::DragAction)>) {}
+
+    pub fn start_drag(
+        &self,
+        _: window::ClipboardContents,
+        _: &[event::DragAction],
+    ) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
     pub fn set_cursor_grab(&self, _: bool) -> Result<(), error::ExternalError> {
         Err(error::ExternalError::NotSupported(
             error::NotSupportedError::new(),
@@ -411,6 +492,21 @@ impl Window {
 
     pub fn set_cursor_visible(&self, _: bool) {}
 
+    pub fn drag_window(&self) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
+    pub fn drag_resize_window(
+        &self,
+        _: window::ResizeDirection,
+    ) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
     pub fn raw_window_handle(&self) -> raw_window_handle::RawWindowHandle {
         let a_native_window = if let Some(native_window) = ndk_glue::native_window().as_ref() {
             unsafe { native_window.ptr().as_mut() as *mut _ as *mut _ }
@@ -465,6 +561,10 @@ impl MonitorHandle {
         (0, 0).into()
     }
 
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        (self.position(), self.size())
+    }
+
     pub fn scale_factor(&self) -> f64 {
         let config = CONFIG.read().unwrap();
         config
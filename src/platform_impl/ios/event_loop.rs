@@ -9,9 +9,11 @@ use std::{
 
 use crate::{
     dpi::LogicalSize,
+    error::{ExternalError, NotSupportedError},
     event::Event,
     event_loop::{
-        ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootEventLoopWindowTarget,
+        ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootEventLoopWindowTarget, Timer,
+        TimerId,
     },
     platform::ios::Idiom,
 };
@@ -49,6 +51,14 @@ pub struct EventLoopWindowTarget<T: 'static> {
     sender_to_clone: Sender<T>,
 }
 
+impl<T> EventLoopWindowTarget<T> {
+    pub fn set_timer(&self, _timer: Timer) -> Result<TimerId, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn cancel_timer(&self, _id: TimerId) {}
+}
+
 pub struct EventLoop<T: 'static> {
     window_target: RootEventLoopWindowTarget<T>,
 }
@@ -9,7 +9,7 @@ use objc::runtime::{Class, Object, BOOL, NO, YES};
 use crate::{
     dpi::{self, LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
     error::{ExternalError, NotSupportedError, OsError as RootOsError},
-    event::{Event, WindowEvent},
+    event::{DragAction, Event, WindowEvent},
     icon::Icon,
     monitor::MonitorHandle as RootMonitorHandle,
     platform::ios::{MonitorHandleExtIOS, ScreenEdge, ValidOrientations},
@@ -22,7 +22,10 @@ use crate::{
         },
         monitor, view, EventLoopWindowTarget, MonitorHandle,
     },
-    window::{CursorIcon, Fullscreen, WindowAttributes, WindowId as RootWindowId},
+    window::{
+        ClipboardContents, ClipboardKind, CursorIcon, CustomCursor, Fullscreen, ResizeDirection,
+        WindowAttributes, WindowId as RootWindowId,
+    },
 };
 
 pub struct Inner {
@@ -47,6 +50,10 @@ impl Inner {
         debug!("`Window::set_title` is ignored on iOS")
     }
 
+    pub fn title(&self) -> String {
+        String::new()
+    }
+
     pub fn set_visible(&self, visible: bool) {
         match visible {
             true => unsafe {
@@ -58,6 +65,11 @@ impl Inner {
         }
     }
 
+    pub fn is_visible(&self) -> Option<bool> {
+        let is_hidden: BOOL = unsafe { msg_send![self.window, isHidden] };
+        Some(is_hidden == NO)
+    }
+
     pub fn request_redraw(&self) {
         unsafe {
             if self.gl_or_metal_backed {
@@ -76,6 +88,10 @@ impl Inner {
         }
     }
 
+    pub fn request_frame_callback(&self) {
+        self.request_redraw();
+    }
+
     pub fn inner_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
         unsafe {
             let safe_area = self.safe_area_screen_space();
@@ -157,6 +173,10 @@ impl Inner {
         warn!("`Window::set_resizable` is ignored on iOS")
     }
 
+    pub fn is_resizable(&self) -> bool {
+        false
+    }
+
     pub fn scale_factor(&self) -> f64 {
         unsafe {
             let hidpi: CGFloat = msg_send![self.view, contentScaleFactor];
@@ -168,10 +188,40 @@ impl Inner {
         debug!("`Window::set_cursor_icon` ignored on iOS")
     }
 
+    pub fn set_custom_cursor(&self, _cursor: CustomCursor) {
+        debug!("`Window::set_custom_cursor` ignored on iOS")
+    }
+
     pub fn set_cursor_position(&self, _position: Position) -> Result<(), ExternalError> {
         Err(ExternalError::NotSupported(NotSupportedError::new()))
     }
 
+    pub fn set_clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn set_drag_response(&self, _response: Option<(String, DragAction)>) {}
+
+    pub fn start_drag(
+        &self,
+        _contents: ClipboardContents,
+        _allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     pub fn set_cursor_grab(&self, _grab: bool) -> Result<(), ExternalError> {
         Err(ExternalError::NotSupported(NotSupportedError::new()))
     }
@@ -180,14 +230,30 @@ impl Inner {
         debug!("`Window::set_cursor_visible` is ignored on iOS")
     }
 
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn drag_resize_window(&self, _direction: ResizeDirection) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     pub fn set_minimized(&self, _minimized: bool) {
         warn!("`Window::set_minimized` is ignored on iOS")
     }
 
+    pub fn is_minimized(&self) -> Option<bool> {
+        None
+    }
+
     pub fn set_maximized(&self, _maximized: bool) {
         warn!("`Window::set_maximized` is ignored on iOS")
     }
 
+    pub fn is_maximized(&self) -> bool {
+        false
+    }
+
     pub fn set_fullscreen(&self, monitor: Option<Fullscreen>) {
         unsafe {
             let uiscreen = match monitor {
@@ -246,6 +312,15 @@ impl Inner {
         warn!("`Window::set_decorations` is ignored on iOS")
     }
 
+    pub fn is_decorated(&self) -> bool {
+        false
+    }
+
+    pub fn has_focus(&self) -> bool {
+        let is_key_window: BOOL = unsafe { msg_send![self.window, isKeyWindow] };
+        is_key_window == YES
+    }
+
     pub fn set_always_on_top(&self, _always_on_top: bool) {
         warn!("`Window::set_always_on_top` is ignored on iOS")
     }
@@ -258,6 +333,10 @@ impl Inner {
         warn!("`Window::set_ime_position` is ignored on iOS")
     }
 
+    pub fn set_ime_allowed(&self, _allowed: bool) {
+        warn!("`Window::set_ime_allowed` is ignored on iOS")
+    }
+
     pub fn current_monitor(&self) -> RootMonitorHandle {
         unsafe {
             let uiscreen: id = msg_send![self.window, screen];
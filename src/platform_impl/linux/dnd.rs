@@ -0,0 +1,78 @@
+//! Drag and drop helpers shared by the X11 and Wayland backends.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    str::Utf8Error,
+};
+
+use percent_encoding::percent_decode;
+
+use crate::event::DragAction;
+
+pub const URI_LIST: &str = "text/uri-list";
+
+/// The answer of a window to a drag over it, as the MIME type it wants the data in along with
+/// the action to perform, or `None` if the drag is rejected.
+pub type DragResponse = Option<(String, DragAction)>;
+
+/// Returns the answer given to a drag offering `mime_types` until the window answers it itself,
+/// which only accepts files.
+pub fn default_response<S: AsRef<str>>(mime_types: &[S]) -> DragResponse {
+    if mime_types.iter().any(|m| m.as_ref() == URI_LIST) {
+        Some((URI_LIST.to_owned(), DragAction::Copy))
+    } else {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub enum DndDataParseError {
+    EmptyData,
+    InvalidUtf8(Utf8Error),
+    HostnameSpecified(String),
+    UnexpectedProtocol(String),
+    UnresolvablePath(io::Error),
+}
+
+impl From<Utf8Error> for DndDataParseError {
+    fn from(e: Utf8Error) -> Self {
+        DndDataParseError::InvalidUtf8(e)
+    }
+}
+
+impl From<io::Error> for DndDataParseError {
+    fn from(e: io::Error) -> Self {
+        DndDataParseError::UnresolvablePath(e)
+    }
+}
+
+/// Parses the contents of a `text/uri-list` offer, which must only hold local files.
+pub fn parse_uri_list(data: &[u8]) -> Result<Vec<PathBuf>, DndDataParseError> {
+    if !data.is_empty() {
+        let mut path_list = Vec::new();
+        let decoded = percent_decode(data).decode_utf8()?.into_owned();
+        for uri in decoded.split("\r\n").filter(|u| !u.is_empty()) {
+            // The format is specified as protocol://host/path
+            // However, it's typically simply protocol:///path
+            let path_str = if uri.starts_with("file://") {
+                let path_str = uri.replace("file://", "");
+                if !path_str.starts_with('/') {
+                    // A hostname is specified
+                    // Supporting this case is beyond the scope of my mental health
+                    return Err(DndDataParseError::HostnameSpecified(path_str));
+                }
+                path_str
+            } else {
+                // Only the file protocol is supported
+                return Err(DndDataParseError::UnexpectedProtocol(uri.to_owned()));
+            };
+
+            let path = Path::new(&path_str).canonicalize()?;
+            path_list.push(path);
+        }
+        Ok(path_list)
+    } else {
+        Err(DndDataParseError::EmptyData)
+    }
+}
@@ -0,0 +1,532 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Condvar, Mutex, Weak,
+    },
+    time::Duration,
+};
+
+use instant::Instant;
+
+use crate::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{DeviceEvent, DeviceId as RootDeviceId, Event, StartCause, WindowEvent},
+    event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
+    monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+    platform::unix::StepStatus,
+    platform_impl::platform::{
+        sources::Sources, sticky_exit_callback, DeviceId as PlatformDeviceId,
+        MonitorHandle as PlatformMonitorHandle, VideoMode as PlatformVideoMode,
+        WindowId as PlatformWindowId,
+    },
+    window::{ClipboardContents, ClipboardKind, WindowId as RootWindowId},
+};
+
+use super::{window::WindowState, DeviceId, WindowId};
+
+/// State shared between the event loop and its windows, which may live on other threads.
+pub(crate) struct Shared {
+    state: Mutex<SharedState>,
+    wakeup: Condvar,
+}
+
+pub(crate) struct SharedState {
+    next_window_id: u64,
+    windows: Vec<(WindowId, Weak<Mutex<WindowState>>)>,
+    events: VecDeque<Event<'static, ()>>,
+    redraws: Vec<WindowId>,
+    clipboard: Option<ClipboardContents>,
+    primary: Option<ClipboardContents>,
+    woken: bool,
+}
+
+impl Shared {
+    fn new() -> Shared {
+        Shared {
+            state: Mutex::new(SharedState {
+                next_window_id: 1,
+                windows: Vec::new(),
+                events: VecDeque::new(),
+                redraws: Vec::new(),
+                clipboard: None,
+                primary: None,
+                woken: false,
+            }),
+            wakeup: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn add_window(&self, state: &Arc<Mutex<WindowState>>) -> WindowId {
+        let mut shared = self.state.lock().unwrap();
+        let id = WindowId(shared.next_window_id);
+        shared.next_window_id += 1;
+        shared.windows.push((id, Arc::downgrade(state)));
+        id
+    }
+
+    pub(crate) fn remove_window(&self, id: WindowId) {
+        let mut shared = self.state.lock().unwrap();
+        shared.windows.retain(|&(wid, _)| wid != id);
+        shared.redraws.retain(|&wid| wid != id);
+        shared.push_window_event(id, WindowEvent::Destroyed);
+        drop(shared);
+        self.wakeup.notify_one();
+    }
+
+    pub(crate) fn send_window_event(&self, id: WindowId, event: WindowEvent<'static>) {
+        self.state.lock().unwrap().push_window_event(id, event);
+        self.wakeup.notify_one();
+    }
+
+    pub(crate) fn request_redraw(&self, id: WindowId) {
+        let mut shared = self.state.lock().unwrap();
+        if !shared.redraws.contains(&id) {
+            shared.redraws.push(id);
+        }
+        drop(shared);
+        self.wakeup.notify_one();
+    }
+
+    pub(crate) fn set_clipboard_contents(&self, kind: ClipboardKind, contents: ClipboardContents) {
+        let mut shared = self.state.lock().unwrap();
+        match kind {
+            ClipboardKind::Clipboard => shared.clipboard = Some(contents),
+            ClipboardKind::Primary => shared.primary = Some(contents),
+        }
+        // Every window is told about the new contents, as they would be with XFixes.
+        let ids: Vec<_> = shared.windows.iter().map(|&(id, _)| id).collect();
+        for id in ids {
+            shared.push_window_event(id, WindowEvent::ClipboardChanged(kind));
+        }
+        drop(shared);
+        self.wakeup.notify_one();
+    }
+
+    pub(crate) fn clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        let shared = self.state.lock().unwrap();
+        let contents = match kind {
+            ClipboardKind::Clipboard => shared.clipboard.as_ref(),
+            ClipboardKind::Primary => shared.primary.as_ref(),
+        };
+        contents
+            .and_then(|contents| contents.get(mime_type))
+            .map(|data| data.to_vec())
+    }
+
+    fn wake_up(&self) {
+        self.state.lock().unwrap().woken = true;
+        self.wakeup.notify_one();
+    }
+}
+
+impl SharedState {
+    fn push_window_event(&mut self, id: WindowId, event: WindowEvent<'static>) {
+        self.events.push_back(Event::WindowEvent {
+            window_id: RootWindowId(PlatformWindowId::Headless(id)),
+            event,
+        });
+    }
+
+    fn has_pending_events(&self) -> bool {
+        self.woken || !self.events.is_empty() || !self.redraws.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunState {
+    NotStarted,
+    Running,
+    Exited,
+}
+
+pub struct EventLoop<T: 'static> {
+    user_channel: Receiver<T>,
+    user_sender: Sender<T>,
+    pending_user_events: VecDeque<T>,
+    control_flow: ControlFlow,
+    run_state: RunState,
+    window_target: RootELW<T>,
+}
+
+pub struct EventLoopProxy<T: 'static> {
+    user_sender: Sender<T>,
+    shared: Arc<Shared>,
+}
+
+pub struct EventLoopWindowTarget<T> {
+    pub(crate) shared: Arc<Shared>,
+    // The virtual clock, which only moves forward when the loop is stepped past a deadline or is
+    // explicitly advanced.
+    now: Cell<Instant>,
+    // The timers armed by the user, which follow the virtual clock
+    pub(crate) sources: RefCell<Sources>,
+    _marker: ::std::marker::PhantomData<T>,
+}
+
+impl<T: 'static> Clone for EventLoopProxy<T> {
+    fn clone(&self) -> Self {
+        EventLoopProxy {
+            user_sender: self.user_sender.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: 'static> EventLoopProxy<T> {
+    pub fn send_event(&self, event: T) -> Result<(), EventLoopClosed<T>> {
+        self.user_sender
+            .send(event)
+            .map_err(|mpsc::SendError(event)| EventLoopClosed(event))?;
+        self.shared.wake_up();
+        Ok(())
+    }
+}
+
+impl<T: 'static> EventLoop<T> {
+    pub fn new() -> EventLoop<T> {
+        let (user_sender, user_channel) = mpsc::channel();
+        EventLoop {
+            user_channel,
+            user_sender,
+            pending_user_events: VecDeque::new(),
+            control_flow: ControlFlow::default(),
+            run_state: RunState::NotStarted,
+            window_target: RootELW {
+                p: crate::platform_impl::EventLoopWindowTarget::Headless(EventLoopWindowTarget {
+                    shared: Arc::new(Shared::new()),
+                    now: Cell::new(Instant::now()),
+                    sources: Default::default(),
+                    _marker: ::std::marker::PhantomData,
+                }),
+                _marker: ::std::marker::PhantomData,
+            },
+        }
+    }
+
+    pub fn create_proxy(&self) -> EventLoopProxy<T> {
+        EventLoopProxy {
+            user_sender: self.user_sender.clone(),
+            shared: get_target(&self.window_target).shared.clone(),
+        }
+    }
+
+    pub fn run<F>(mut self, callback: F) -> !
+    where
+        F: 'static + FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        self.run_return(callback);
+        std::process::exit(0);
+    }
+
+    pub fn run_return<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        self.run_state = RunState::NotStarted;
+        self.control_flow = ControlFlow::default();
+
+        loop {
+            match self.step(&mut callback) {
+                StepStatus::Exited => break,
+                StepStatus::Idle => self.wait(),
+                StepStatus::Dispatched => (),
+            }
+        }
+    }
+
+    /// Runs a single iteration of the loop, starting with `NewEvents` and ending with
+    /// `RedrawEventsCleared`.
+    pub fn step<F>(&mut self, mut callback: F) -> StepStatus
+    where
+        F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        let cause = match self.run_state {
+            RunState::Exited => return StepStatus::Exited,
+            RunState::NotStarted => StartCause::Init,
+            RunState::Running => match self.start_cause() {
+                Some(cause) => cause,
+                None => return StepStatus::Idle,
+            },
+        };
+        self.run_state = RunState::Running;
+
+        let mut control_flow = self.control_flow;
+        callback(
+            Event::NewEvents(cause),
+            &self.window_target,
+            &mut control_flow,
+        );
+
+        let target = get_target(&self.window_target);
+        let timers = target.sources.borrow_mut().expire_timers(target.now.get());
+        for event in timers {
+            let event = event.map_nonuser_event().unwrap();
+            sticky_exit_callback(event, &self.window_target, &mut control_flow, &mut callback);
+        }
+
+        // Only dispatch the events which were queued when the iteration started, so that events
+        // emitted by the callback are handled on the next one.
+        let (events, redraws) = {
+            let mut shared = get_target(&self.window_target).shared.state.lock().unwrap();
+            shared.woken = false;
+            let events = std::mem::take(&mut shared.events);
+            let redraws = std::mem::take(&mut shared.redraws);
+            (events, redraws)
+        };
+
+        for event in events {
+            let event = event.map_nonuser_event().unwrap();
+            sticky_exit_callback(event, &self.window_target, &mut control_flow, &mut callback);
+        }
+
+        self.pending_user_events
+            .extend(self.user_channel.try_iter());
+        while let Some(event) = self.pending_user_events.pop_front() {
+            sticky_exit_callback(
+                Event::UserEvent(event),
+                &self.window_target,
+                &mut control_flow,
+                &mut callback,
+            );
+        }
+
+        sticky_exit_callback(
+            Event::MainEventsCleared,
+            &self.window_target,
+            &mut control_flow,
+            &mut callback,
+        );
+
+        for wid in redraws {
+            sticky_exit_callback(
+                Event::RedrawRequested(RootWindowId(PlatformWindowId::Headless(wid))),
+                &self.window_target,
+                &mut control_flow,
+                &mut callback,
+            );
+        }
+
+        sticky_exit_callback(
+            Event::RedrawEventsCleared,
+            &self.window_target,
+            &mut control_flow,
+            &mut callback,
+        );
+
+        self.control_flow = control_flow;
+        if control_flow == ControlFlow::Exit {
+            callback(Event::LoopDestroyed, &self.window_target, &mut control_flow);
+            self.run_state = RunState::Exited;
+            return StepStatus::Exited;
+        }
+        StepStatus::Dispatched
+    }
+
+    /// Moves the virtual clock forward.
+    pub fn advance_clock(&mut self, duration: Duration) {
+        let target = get_target(&self.window_target);
+        target.now.set(target.now.get() + duration);
+    }
+
+    // Returns why the next iteration would start, or `None` if the loop would keep waiting.
+    fn start_cause(&mut self) -> Option<StartCause> {
+        let target = get_target(&self.window_target);
+        self.pending_user_events
+            .extend(self.user_channel.try_iter());
+        let pending_events = !self.pending_user_events.is_empty()
+            || target.shared.state.lock().unwrap().has_pending_events();
+        let start = target.now.get();
+        let requested_resume = match self.control_flow {
+            ControlFlow::WaitUntil(deadline) => Some(deadline),
+            _ => None,
+        };
+        // A timer which fires after the deadline doesn't matter yet.
+        let timer = match (target.sources.borrow().next_timer(), requested_resume) {
+            (Some((timer, _)), Some(deadline)) if deadline <= timer => None,
+            (timer, _) => timer,
+        };
+
+        match (self.control_flow, timer) {
+            (ControlFlow::Poll, _) => Some(StartCause::Poll),
+            (ControlFlow::WaitUntil(deadline), _) if deadline <= start => {
+                Some(StartCause::ResumeTimeReached {
+                    start,
+                    requested_resume: deadline,
+                })
+            }
+            (_, Some((timer, id))) if timer <= start => Some(StartCause::TimerFired(id)),
+            _ if pending_events => Some(StartCause::WaitCancelled {
+                start,
+                requested_resume,
+            }),
+            // Nothing can happen before the next timer fires or the deadline is reached, so skip
+            // right to it.
+            (_, Some((timer, id))) => {
+                target.now.set(timer);
+                Some(StartCause::TimerFired(id))
+            }
+            (ControlFlow::WaitUntil(deadline), _) => {
+                target.now.set(deadline);
+                Some(StartCause::ResumeTimeReached {
+                    start,
+                    requested_resume: deadline,
+                })
+            }
+            (ControlFlow::Wait, _) => None,
+            (ControlFlow::Exit, _) => unreachable!(),
+        }
+    }
+
+    // Blocks until a window or a proxy from another thread wakes the loop up.
+    fn wait(&mut self) {
+        let shared = &get_target(&self.window_target).shared;
+        let mut state = shared.state.lock().unwrap();
+        while !state.has_pending_events() {
+            state = shared.wakeup.wait(state).unwrap();
+        }
+    }
+
+    pub fn primary_monitor(&self) -> MonitorHandle {
+        MonitorHandle
+    }
+
+    pub fn available_monitors(&self) -> VecDeque<MonitorHandle> {
+        vec![MonitorHandle].into()
+    }
+
+    pub fn window_target(&self) -> &RootELW<T> {
+        &self.window_target
+    }
+}
+
+impl<T> EventLoopWindowTarget<T> {
+    pub fn inject_window_event(&self, window_id: WindowId, event: WindowEvent<'static>) {
+        let window = {
+            let shared = self.shared.state.lock().unwrap();
+            shared
+                .windows
+                .iter()
+                .find(|&&(id, _)| id == window_id)
+                .and_then(|(_, window)| window.upgrade())
+        };
+        // Keep the window in sync with what the application is told.
+        if let Some(window) = window {
+            window.lock().unwrap().apply_event(&event);
+        }
+        self.shared.send_window_event(window_id, event);
+    }
+
+    pub fn inject_device_event(&self, event: DeviceEvent) {
+        self.shared
+            .state
+            .lock()
+            .unwrap()
+            .events
+            .push_back(Event::DeviceEvent {
+                device_id: RootDeviceId(PlatformDeviceId::Headless(DeviceId)),
+                event,
+            });
+        self.shared.wakeup.notify_one();
+    }
+
+    #[inline]
+    pub fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+fn get_target<T>(target: &RootELW<T>) -> &EventLoopWindowTarget<T> {
+    match target.p {
+        crate::platform_impl::EventLoopWindowTarget::Headless(ref wt) => wt,
+        _ => unreachable!(),
+    }
+}
+
+/*
+ * Monitor stuff
+ */
+
+/// The single virtual monitor of the headless backend.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonitorHandle;
+
+impl MonitorHandle {
+    #[inline]
+    pub fn name(&self) -> Option<String> {
+        Some("Headless".to_owned())
+    }
+
+    #[inline]
+    pub fn native_identifier(&self) -> u32 {
+        0
+    }
+
+    #[inline]
+    pub fn size(&self) -> PhysicalSize<u32> {
+        PhysicalSize::new(1920, 1080)
+    }
+
+    #[inline]
+    pub fn position(&self) -> PhysicalPosition<i32> {
+        PhysicalPosition::new(0, 0)
+    }
+
+    #[inline]
+    pub fn scale_factor(&self) -> f64 {
+        1.0
+    }
+
+    #[inline]
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        (self.position(), self.size())
+    }
+
+    #[inline]
+    pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
+        let video_mode = VideoMode {
+            size: self.size().into(),
+            bit_depth: 32,
+            refresh_rate: 60,
+        };
+        std::iter::once(RootVideoMode {
+            video_mode: PlatformVideoMode::Headless(video_mode),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VideoMode {
+    size: (u32, u32),
+    bit_depth: u16,
+    refresh_rate: u16,
+}
+
+impl VideoMode {
+    #[inline]
+    pub fn size(&self) -> PhysicalSize<u32> {
+        self.size.into()
+    }
+
+    #[inline]
+    pub fn bit_depth(&self) -> u16 {
+        self.bit_depth
+    }
+
+    #[inline]
+    pub fn refresh_rate(&self) -> u16 {
+        self.refresh_rate
+    }
+
+    #[inline]
+    pub fn monitor(&self) -> RootMonitorHandle {
+        RootMonitorHandle {
+            inner: PlatformMonitorHandle::Headless(MonitorHandle),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! A backend without any display server, whose windows only exist in memory.
+//!
+//! Events are injected through the `EventLoopWindowTarget` and the loop can be stepped one
+//! iteration at a time, which makes it possible to test input handling deterministically.
+
+pub use self::{
+    event_loop::{EventLoop, EventLoopProxy, EventLoopWindowTarget, MonitorHandle, VideoMode},
+    window::Window,
+};
+
+mod event_loop;
+mod window;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DeviceId;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowId(u64);
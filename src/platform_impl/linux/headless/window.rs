@@ -0,0 +1,359 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use raw_window_handle::unix::XlibHandle;
+
+use crate::{
+    dpi::{PhysicalPosition, PhysicalSize, Position, Size},
+    error::{ExternalError, NotSupportedError, OsError as RootOsError},
+    event::{self, DragAction, WindowEvent},
+    platform_impl::PlatformSpecificWindowBuilderAttributes as PlAttributes,
+    window::{
+        ClipboardContents, ClipboardKind, CursorIcon, Fullscreen, ResizeDirection, WindowAttributes,
+    },
+};
+
+use super::{
+    event_loop::{EventLoopWindowTarget, Shared},
+    MonitorHandle, WindowId,
+};
+
+/// The in-memory state of a headless window.
+pub(crate) struct WindowState {
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    min_size: Option<PhysicalSize<u32>>,
+    max_size: Option<PhysicalSize<u32>>,
+    fullscreen: Option<Fullscreen>,
+    title: String,
+    visible: bool,
+    resizable: bool,
+    maximized: bool,
+    minimized: bool,
+    decorated: bool,
+    focused: bool,
+}
+
+impl WindowState {
+    /// Updates the state to match an event injected for this window.
+    pub(crate) fn apply_event(&mut self, event: &WindowEvent<'static>) {
+        match *event {
+            WindowEvent::Resized(size) => self.size = size,
+            WindowEvent::Moved(position) => self.position = position,
+            WindowEvent::Focused(focused) => self.focused = focused,
+            WindowEvent::StateChanged(state) => {
+                self.maximized = state.contains(event::WindowState::MAXIMIZED);
+                self.minimized = state.contains(event::WindowState::MINIMIZED);
+            }
+            _ => (),
+        }
+    }
+
+    // The state reported by `WindowEvent::StateChanged`.
+    fn window_state(&self) -> event::WindowState {
+        let mut state = event::WindowState::empty();
+        state.set(event::WindowState::MAXIMIZED, self.maximized);
+        state.set(event::WindowState::MINIMIZED, self.minimized);
+        state.set(event::WindowState::FULLSCREEN, self.fullscreen.is_some());
+        state
+    }
+
+    // Whether the window is hidden from view, as reported by `WindowEvent::Occluded`.
+    fn is_occluded(&self) -> bool {
+        !self.visible || self.minimized
+    }
+
+    // Clamps `size` to the minimum and maximum sizes of the window.
+    fn clamp_size(&self, size: PhysicalSize<u32>) -> PhysicalSize<u32> {
+        let mut size = size;
+        if let Some(min) = self.min_size {
+            size.width = size.width.max(min.width);
+            size.height = size.height.max(min.height);
+        }
+        if let Some(max) = self.max_size {
+            size.width = size.width.min(max.width);
+            size.height = size.height.min(max.height);
+        }
+        size
+    }
+}
+
+pub struct Window {
+    id: WindowId,
+    state: Arc<Mutex<WindowState>>,
+    shared: Arc<Shared>,
+}
+
+impl Window {
+    pub fn new<T>(
+        evlp: &EventLoopWindowTarget<T>,
+        attributes: WindowAttributes,
+        _pl_attribs: PlAttributes,
+    ) -> Result<Window, RootOsError> {
+        let scale_factor = MonitorHandle.scale_factor();
+        let size = attributes
+            .inner_size
+            .map(|size| size.to_physical(scale_factor))
+            .unwrap_or_else(|| PhysicalSize::new(800, 600));
+
+        let state = WindowState {
+            position: PhysicalPosition::new(0, 0),
+            size,
+            min_size: attributes
+                .min_inner_size
+                .map(|size| size.to_physical(scale_factor)),
+            max_size: attributes
+                .max_inner_size
+                .map(|size| size.to_physical(scale_factor)),
+            fullscreen: attributes.fullscreen,
+            title: attributes.title,
+            visible: attributes.visible,
+            resizable: attributes.resizable,
+            maximized: attributes.maximized,
+            minimized: false,
+            decorated: attributes.decorations,
+            focused: false,
+        };
+        let state = Arc::new(Mutex::new(state));
+        let id = evlp.shared.add_window(&state);
+
+        Ok(Window {
+            id,
+            state,
+            shared: evlp.shared.clone(),
+        })
+    }
+
+    #[inline]
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    // Applies `change` to the state, reporting the resulting changes like a window manager would.
+    fn update_state<F>(&self, change: F)
+    where
+        F: FnOnce(&mut WindowState),
+    {
+        let mut state = self.state.lock().unwrap();
+        let (window_state, occluded) = (state.window_state(), state.is_occluded());
+        change(&mut state);
+        if state.window_state() != window_state {
+            self.shared
+                .send_window_event(self.id, WindowEvent::StateChanged(state.window_state()));
+        }
+        if state.is_occluded() != occluded {
+            self.shared
+                .send_window_event(self.id, WindowEvent::Occluded(state.is_occluded()));
+        }
+    }
+
+    pub fn set_title(&self, title: &str) {
+        self.state.lock().unwrap().title = title.into();
+    }
+
+    pub fn title(&self) -> String {
+        self.state.lock().unwrap().title.clone()
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.update_state(|state| state.visible = visible);
+    }
+
+    pub fn is_visible(&self) -> Option<bool> {
+        Some(self.state.lock().unwrap().visible)
+    }
+
+    #[inline]
+    pub fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        Ok(self.state.lock().unwrap().position)
+    }
+
+    #[inline]
+    pub fn inner_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        // Headless windows don't have decorations to account for.
+        Ok(self.state.lock().unwrap().position)
+    }
+
+    pub fn set_outer_position(&self, position: Position) {
+        let position = position.to_physical(self.scale_factor());
+        let mut state = self.state.lock().unwrap();
+        if state.position != position {
+            state.position = position;
+            self.shared
+                .send_window_event(self.id, WindowEvent::Moved(position));
+        }
+    }
+
+    #[inline]
+    pub fn inner_size(&self) -> PhysicalSize<u32> {
+        self.state.lock().unwrap().size
+    }
+
+    #[inline]
+    pub fn outer_size(&self) -> PhysicalSize<u32> {
+        self.state.lock().unwrap().size
+    }
+
+    pub fn set_inner_size(&self, size: Size) {
+        let mut state = self.state.lock().unwrap();
+        let size = state.clamp_size(size.to_physical(self.scale_factor()));
+        if state.size != size {
+            state.size = size;
+            self.shared
+                .send_window_event(self.id, WindowEvent::Resized(size));
+        }
+    }
+
+    pub fn set_min_inner_size(&self, dimensions: Option<Size>) {
+        self.state.lock().unwrap().min_size =
+            dimensions.map(|size| size.to_physical(self.scale_factor()));
+    }
+
+    pub fn set_max_inner_size(&self, dimensions: Option<Size>) {
+        self.state.lock().unwrap().max_size =
+            dimensions.map(|size| size.to_physical(self.scale_factor()));
+    }
+
+    pub fn set_resizable(&self, resizable: bool) {
+        self.state.lock().unwrap().resizable = resizable;
+    }
+
+    pub fn is_resizable(&self) -> bool {
+        self.state.lock().unwrap().resizable
+    }
+
+    #[inline]
+    pub fn set_cursor_icon(&self, _cursor: CursorIcon) {}
+
+    #[inline]
+    pub fn set_cursor_grab(&self, _grab: bool) -> Result<(), ExternalError> {
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_cursor_visible(&self, _visible: bool) {}
+
+    #[inline]
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn drag_resize_window(&self, _direction: ResizeDirection) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn set_clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        self.shared.set_clipboard_contents(kind, contents);
+        Ok(())
+    }
+
+    pub fn clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        Ok(self.shared.clipboard_contents(kind, mime_type))
+    }
+
+    #[inline]
+    pub fn set_drag_response(&self, _response: Option<(String, DragAction)>) {}
+
+    #[inline]
+    pub fn start_drag(
+        &self,
+        _contents: ClipboardContents,
+        _allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn scale_factor(&self) -> f64 {
+        MonitorHandle.scale_factor()
+    }
+
+    #[inline]
+    pub fn set_cursor_position(&self, _position: Position) -> Result<(), ExternalError> {
+        Ok(())
+    }
+
+    pub fn set_maximized(&self, maximized: bool) {
+        self.update_state(|state| state.maximized = maximized);
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.state.lock().unwrap().maximized
+    }
+
+    pub fn set_minimized(&self, minimized: bool) {
+        self.update_state(|state| state.minimized = minimized);
+    }
+
+    pub fn is_minimized(&self) -> Option<bool> {
+        Some(self.state.lock().unwrap().minimized)
+    }
+
+    pub fn fullscreen(&self) -> Option<Fullscreen> {
+        self.state.lock().unwrap().fullscreen.clone()
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.update_state(|state| state.fullscreen = fullscreen);
+    }
+
+    pub fn set_decorations(&self, decorations: bool) {
+        // No event reports decorations, which don't change the size of headless windows either.
+        self.state.lock().unwrap().decorated = decorations;
+    }
+
+    pub fn is_decorated(&self) -> bool {
+        self.state.lock().unwrap().decorated
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.state.lock().unwrap().focused
+    }
+
+    #[inline]
+    pub fn request_redraw(&self) {
+        self.shared.request_redraw(self.id);
+    }
+
+    #[inline]
+    pub fn request_frame_callback(&self) {
+        self.request_redraw();
+    }
+
+    #[inline]
+    pub fn current_monitor(&self) -> MonitorHandle {
+        MonitorHandle
+    }
+
+    #[inline]
+    pub fn available_monitors(&self) -> VecDeque<MonitorHandle> {
+        vec![MonitorHandle].into()
+    }
+
+    #[inline]
+    pub fn primary_monitor(&self) -> MonitorHandle {
+        MonitorHandle
+    }
+
+    /// Headless windows have no native window, so an empty handle is returned.
+    pub fn raw_window_handle(&self) -> XlibHandle {
+        XlibHandle::empty()
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        self.shared.remove_window(self.id);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::event::{Key, NamedKey, VirtualKeyCode};
+
+/// Maps an X11 keysym, as used by both Xlib and xkbcommon, to the logical key it stands for.
+///
+/// Keysyms of characters outside of Latin-1 and Unicode are `Key::Unidentified`, in which case
+/// the text produced by the key press should be used instead.
+pub fn keysym_to_key(keysym: u32) -> Key {
+    if let Some(c) = keysym_to_char(keysym) {
+        return Key::Character(c.to_string());
+    }
+    if let Some(accent) = dead_key_accent(keysym) {
+        return Key::Dead(accent);
+    }
+    let named = match keysym {
+        0xff08 => NamedKey::Backspace,
+        0xff09 | 0xfe20 | 0xff89 => NamedKey::Tab,
+        0xff0b | 0xff9d => NamedKey::Clear,
+        0xff0d | 0xff8d => NamedKey::Enter,
+        0xff13 | 0xff6b => NamedKey::Pause,
+        0xff14 => NamedKey::ScrollLock,
+        0xff1b => NamedKey::Escape,
+        0xffff | 0xff9f => NamedKey::Delete,
+        0xff20 => NamedKey::Compose,
+        0xff21 => NamedKey::KanjiMode,
+        0xff22 => NamedKey::NonConvert,
+        0xff23 => NamedKey::Convert,
+        0xff24 => NamedKey::Romaji,
+        0xff25 => NamedKey::Hiragana,
+        0xff26 => NamedKey::Katakana,
+        0xff27 => NamedKey::HiraganaKatakana,
+        0xff28 => NamedKey::Zenkaku,
+        0xff29 => NamedKey::Hankaku,
+        0xff2a => NamedKey::ZenkakuHankaku,
+        0xff30 => NamedKey::Eisu,
+        0xff31 => NamedKey::HangulMode,
+        0xff34 => NamedKey::HanjaMode,
+        0xff37 => NamedKey::CodeInput,
+        0xff3c => NamedKey::SingleCandidate,
+        0xff3d => NamedKey::AllCandidates,
+        0xff3e => NamedKey::PreviousCandidate,
+        0xff50 | 0xff95 => NamedKey::Home,
+        0xff51 | 0xff96 => NamedKey::ArrowLeft,
+        0xff52 | 0xff97 => NamedKey::ArrowUp,
+        0xff53 | 0xff98 => NamedKey::ArrowRight,
+        0xff54 | 0xff99 => NamedKey::ArrowDown,
+        0xff55 | 0xff9a => NamedKey::PageUp,
+        0xff56 | 0xff9b => NamedKey::PageDown,
+        0xff57 | 0xff9c => NamedKey::End,
+        0xff60 => NamedKey::Select,
+        0xff61 => NamedKey::PrintScreen,
+        0xff62 => NamedKey::Execute,
+        0xff63 | 0xff9e => NamedKey::Insert,
+        0xff65 => NamedKey::Undo,
+        0xff66 => NamedKey::Redo,
+        0xff67 => NamedKey::ContextMenu,
+        0xff68 => NamedKey::Find,
+        0xff69 => NamedKey::Cancel,
+        0xff6a => NamedKey::Help,
+        0xff7e => NamedKey::ModeChange,
+        0xff7f => NamedKey::NumLock,
+        0xff91 => NamedKey::F1,
+        0xff92 => NamedKey::F2,
+        0xff93 => NamedKey::F3,
+        0xff94 => NamedKey::F4,
+        0xffe1 | 0xffe2 => NamedKey::Shift,
+        0xffe3 | 0xffe4 => NamedKey::Control,
+        0xffe5 => NamedKey::CapsLock,
+        0xffe7 | 0xffe8 => NamedKey::Meta,
+        0xffe9 | 0xffea => NamedKey::Alt,
+        0xffeb | 0xffec => NamedKey::Super,
+        0xffed | 0xffee => NamedKey::Hyper,
+        0xfe03 => NamedKey::AltGraph,
+        0xfe08 => NamedKey::GroupNext,
+        0xfe0a => NamedKey::GroupPrevious,
+        0xfe0c => NamedKey::GroupFirst,
+        0xfe0e => NamedKey::GroupLast,
+        0x1008ff02 => NamedKey::BrightnessUp,
+        0x1008ff03 => NamedKey::BrightnessDown,
+        0x1008ff11 => NamedKey::AudioVolumeDown,
+        0x1008ff12 => NamedKey::AudioVolumeMute,
+        0x1008ff13 => NamedKey::AudioVolumeUp,
+        0x1008ff14 => NamedKey::MediaPlayPause,
+        0x1008ff15 => NamedKey::MediaStop,
+        0x1008ff16 => NamedKey::MediaTrackPrevious,
+        0x1008ff17 => NamedKey::MediaTrackNext,
+        0x1008ff18 => NamedKey::BrowserHome,
+        0x1008ff19 => NamedKey::LaunchMail,
+        0x1008ff1b => NamedKey::BrowserSearch,
+        0x1008ff1c => NamedKey::MediaRecord,
+        0x1008ff1d => NamedKey::LaunchApplication2,
+        0x1008ff26 => NamedKey::BrowserBack,
+        0x1008ff27 => NamedKey::BrowserForward,
+        0x1008ff28 => NamedKey::BrowserStop,
+        0x1008ff29 => NamedKey::BrowserRefresh,
+        0x1008ff2a => NamedKey::PowerOff,
+        0x1008ff2b => NamedKey::WakeUp,
+        0x1008ff2c => NamedKey::Eject,
+        0x1008ff2f => NamedKey::Standby,
+        0x1008ff30 => NamedKey::BrowserFavorites,
+        0x1008ff31 => NamedKey::MediaPause,
+        0x1008ff32 => NamedKey::LaunchMediaPlayer,
+        0x1008ff33 => NamedKey::LaunchApplication1,
+        0x1008ff56 => NamedKey::Close,
+        0x1008ff57 => NamedKey::Copy,
+        0x1008ff58 => NamedKey::Cut,
+        0x1008ff61 => NamedKey::LogOff,
+        0x1008ff68 => NamedKey::New,
+        0x1008ff6b => NamedKey::Open,
+        0x1008ff6d => NamedKey::Paste,
+        0x1008ff77 => NamedKey::Save,
+        0x1008ff8b => NamedKey::ZoomIn,
+        0x1008ff8c => NamedKey::ZoomOut,
+        0x1008ffa8 => NamedKey::Hibernate,
+        // F1 to F35
+        0xffbe..=0xffe0 => function_key(keysym - 0xffbe),
+        _ => return Key::Unidentified,
+    };
+    Key::Named(named)
+}
+
+/// Returns the logical key of a key event, falling back to the text it produced for keysyms that
+/// can't be mapped on their own.
+pub fn logical_key(keysym: u32, text: Option<&str>) -> Key {
+    match keysym_to_key(keysym) {
+        Key::Unidentified => match text {
+            Some(text) if !text.is_empty() && !text.chars().any(char::is_control) => {
+                Key::Character(text.to_owned())
+            }
+            _ => Key::Unidentified,
+        },
+        key => key,
+    }
+}
+
+// Handles the keypad keys producing characters, along with Latin-1 and Unicode keysyms.
+fn keysym_to_char(keysym: u32) -> Option<char> {
+    match keysym {
+        // KP_Space
+        0xff80 => Some(' '),
+        // KP_Multiply to KP_9, which follow ASCII
+        0xffaa..=0xffb9 => std::char::from_u32(keysym - 0xff80),
+        // KP_Equal
+        0xffbd => Some('='),
+        0x20..=0x7e | 0xa0..=0xff => std::char::from_u32(keysym),
+        0x0100_0100..=0x0110_ffff => std::char::from_u32(keysym - 0x0100_0000),
+        _ => None,
+    }
+}
+
+// Returns `Some` for dead keys, with the accent they add when it has a spacing form.
+fn dead_key_accent(keysym: u32) -> Option<Option<char>> {
+    let accent = match keysym {
+        0xfe50 => '`',
+        0xfe51 => '\u{b4}',
+        0xfe52 => '^',
+        0xfe53 => '~',
+        0xfe54 => '\u{af}',
+        0xfe55 => '\u{2d8}',
+        0xfe56 => '\u{2d9}',
+        0xfe57 => '\u{a8}',
+        0xfe58 => '\u{2da}',
+        0xfe59 => '\u{2dd}',
+        0xfe5a => '\u{2c7}',
+        0xfe5b => '\u{b8}',
+        0xfe5c => '\u{2db}',
+        0xfe5d..=0xfe8f => return Some(None),
+        _ => return None,
+    };
+    Some(Some(accent))
+}
+
+fn function_key(index: u32) -> NamedKey {
+    use NamedKey::*;
+    [
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16, F17, F18, F19, F20,
+        F21, F22, F23, F24, F25, F26, F27, F28, F29, F30, F31, F32, F33, F34, F35,
+    ][index as usize]
+}
+
+/// Maps a key to the `VirtualKeyCode` both Linux backends report for it, from its evdev scancode
+/// and its keysym in the current keyboard state.
+///
+/// The number row is identified by its scancode, so that it yields the same codes on layouts
+/// where those keys don't produce digits.
+pub fn key_to_vkey(rawkey: u32, keysym: u32) -> Option<VirtualKeyCode> {
+    match rawkey {
+        1 => Some(VirtualKeyCode::Escape),
+        2 => Some(VirtualKeyCode::Key1),
+        3 => Some(VirtualKeyCode::Key2),
+        4 => Some(VirtualKeyCode::Key3),
+        5 => Some(VirtualKeyCode::Key4),
+        6 => Some(VirtualKeyCode::Key5),
+        7 => Some(VirtualKeyCode::Key6),
+        8 => Some(VirtualKeyCode::Key7),
+        9 => Some(VirtualKeyCode::Key8),
+        10 => Some(VirtualKeyCode::Key9),
+        11 => Some(VirtualKeyCode::Key0),
+        _ => keysym_to_vkey(keysym),
+    }
+}
+
+fn keysym_to_vkey(keysym: u32) -> Option<VirtualKeyCode> {
+    use smithay_client_toolkit::keyboard::keysyms;
+    match keysym {
+        // letters
+        keysyms::XKB_KEY_A | keysyms::XKB_KEY_a => Some(VirtualKeyCode::A),
+        keysyms::XKB_KEY_B | keysyms::XKB_KEY_b => Some(VirtualKeyCode::B),
+        keysyms::XKB_KEY_C | keysyms::XKB_KEY_c => Some(VirtualKeyCode::C),
+        keysyms::XKB_KEY_D | keysyms::XKB_KEY_d => Some(VirtualKeyCode::D),
+        keysyms::XKB_KEY_E | keysyms::XKB_KEY_e => Some(VirtualKeyCode::E),
+        keysyms::XKB_KEY_F | keysyms::XKB_KEY_f => Some(VirtualKeyCode::F),
+        keysyms::XKB_KEY_G | keysyms::XKB_KEY_g => Some(VirtualKeyCode::G),
+        keysyms::XKB_KEY_H | keysyms::XKB_KEY_h => Some(VirtualKeyCode::H),
+        keysyms::XKB_KEY_I | keysyms::XKB_KEY_i => Some(VirtualKeyCode::I),
+        keysyms::XKB_KEY_J | keysyms::XKB_KEY_j => Some(VirtualKeyCode::J),
+        keysyms::XKB_KEY_K | keysyms::XKB_KEY_k => Some(VirtualKeyCode::K),
+        keysyms::XKB_KEY_L | keysyms::XKB_KEY_l => Some(VirtualKeyCode::L),
+        keysyms::XKB_KEY_M | keysyms::XKB_KEY_m => Some(VirtualKeyCode::M),
+        keysyms::XKB_KEY_N | keysyms::XKB_KEY_n => Some(VirtualKeyCode::N),
+        keysyms::XKB_KEY_O | keysyms::XKB_KEY_o => Some(VirtualKeyCode::O),
+        keysyms::XKB_KEY_P | keysyms::XKB_KEY_p => Some(VirtualKeyCode::P),
+        keysyms::XKB_KEY_Q | keysyms::XKB_KEY_q => Some(VirtualKeyCode::Q),
+        keysyms::XKB_KEY_R | keysyms::XKB_KEY_r => Some(VirtualKeyCode::R),
+        keysyms::XKB_KEY_S | keysyms::XKB_KEY_s => Some(VirtualKeyCode::S),
+        keysyms::XKB_KEY_T | keysyms::XKB_KEY_t => Some(VirtualKeyCode::T),
+        keysyms::XKB_KEY_U | keysyms::XKB_KEY_u => Some(VirtualKeyCode::U),
+        keysyms::XKB_KEY_V | keysyms::XKB_KEY_v => Some(VirtualKeyCode::V),
+        keysyms::XKB_KEY_W | keysyms::XKB_KEY_w => Some(VirtualKeyCode::W),
+        keysyms::XKB_KEY_X | keysyms::XKB_KEY_x => Some(VirtualKeyCode::X),
+        keysyms::XKB_KEY_Y | keysyms::XKB_KEY_y => Some(VirtualKeyCode::Y),
+        keysyms::XKB_KEY_Z | keysyms::XKB_KEY_z => Some(VirtualKeyCode::Z),
+        // digits
+        keysyms::XKB_KEY_0 => Some(VirtualKeyCode::Key0),
+        keysyms::XKB_KEY_1 => Some(VirtualKeyCode::Key1),
+        keysyms::XKB_KEY_2 => Some(VirtualKeyCode::Key2),
+        keysyms::XKB_KEY_3 => Some(VirtualKeyCode::Key3),
+        keysyms::XKB_KEY_4 => Some(VirtualKeyCode::Key4),
+        keysyms::XKB_KEY_5 => Some(VirtualKeyCode::Key5),
+        keysyms::XKB_KEY_6 => Some(VirtualKeyCode::Key6),
+        keysyms::XKB_KEY_7 => Some(VirtualKeyCode::Key7),
+        keysyms::XKB_KEY_8 => Some(VirtualKeyCode::Key8),
+        keysyms::XKB_KEY_9 => Some(VirtualKeyCode::Key9),
+        // F--
+        keysyms::XKB_KEY_F1 => Some(VirtualKeyCode::F1),
+        keysyms::XKB_KEY_F2 => Some(VirtualKeyCode::F2),
+        keysyms::XKB_KEY_F3 => Some(VirtualKeyCode::F3),
+        keysyms::XKB_KEY_F4 => Some(VirtualKeyCode::F4),
+        keysyms::XKB_KEY_F5 => Some(VirtualKeyCode::F5),
+        keysyms::XKB_KEY_F6 => Some(VirtualKeyCode::F6),
+        keysyms::XKB_KEY_F7 => Some(VirtualKeyCode::F7),
+        keysyms::XKB_KEY_F8 => Some(VirtualKeyCode::F8),
+        keysyms::XKB_KEY_F9 => Some(VirtualKeyCode::F9),
+        keysyms::XKB_KEY_F10 => Some(VirtualKeyCode::F10),
+        keysyms::XKB_KEY_F11 => Some(VirtualKeyCode::F11),
+        keysyms::XKB_KEY_F12 => Some(VirtualKeyCode::F12),
+        keysyms::XKB_KEY_F13 => Some(VirtualKeyCode::F13),
+        keysyms::XKB_KEY_F14 => Some(VirtualKeyCode::F14),
+        keysyms::XKB_KEY_F15 => Some(VirtualKeyCode::F15),
+        keysyms::XKB_KEY_F16 => Some(VirtualKeyCode::F16),
+        keysyms::XKB_KEY_F17 => Some(VirtualKeyCode::F17),
+        keysyms::XKB_KEY_F18 => Some(VirtualKeyCode::F18),
+        keysyms::XKB_KEY_F19 => Some(VirtualKeyCode::F19),
+        keysyms::XKB_KEY_F20 => Some(VirtualKeyCode::F20),
+        keysyms::XKB_KEY_F21 => Some(VirtualKeyCode::F21),
+        keysyms::XKB_KEY_F22 => Some(VirtualKeyCode::F22),
+        keysyms::XKB_KEY_F23 => Some(VirtualKeyCode::F23),
+        keysyms::XKB_KEY_F24 => Some(VirtualKeyCode::F24),
+        // flow control
+        keysyms::XKB_KEY_Print => Some(VirtualKeyCode::Snapshot),
+        keysyms::XKB_KEY_Scroll_Lock => Some(VirtualKeyCode::Scroll),
+        keysyms::XKB_KEY_Pause => Some(VirtualKeyCode::Pause),
+        keysyms::XKB_KEY_Insert => Some(VirtualKeyCode::Insert),
+        keysyms::XKB_KEY_Home => Some(VirtualKeyCode::Home),
+        keysyms::XKB_KEY_Delete => Some(VirtualKeyCode::Delete),
+        keysyms::XKB_KEY_End => Some(VirtualKeyCode::End),
+        keysyms::XKB_KEY_Page_Down => Some(VirtualKeyCode::PageDown),
+        keysyms::XKB_KEY_Page_Up => Some(VirtualKeyCode::PageUp),
+        // arrows
+        keysyms::XKB_KEY_Left => Some(VirtualKeyCode::Left),
+        keysyms::XKB_KEY_Up => Some(VirtualKeyCode::Up),
+        keysyms::XKB_KEY_Right => Some(VirtualKeyCode::Right),
+        keysyms::XKB_KEY_Down => Some(VirtualKeyCode::Down),
+        //
+        keysyms::XKB_KEY_BackSpace => Some(VirtualKeyCode::Back),
+        keysyms::XKB_KEY_Return => Some(VirtualKeyCode::Return),
+        keysyms::XKB_KEY_Escape => Some(VirtualKeyCode::Escape),
+        keysyms::XKB_KEY_Multi_key => Some(VirtualKeyCode::Compose),
+        keysyms::XKB_KEY_space => Some(VirtualKeyCode::Space),
+        // keypad
+        keysyms::XKB_KEY_Num_Lock => Some(VirtualKeyCode::Numlock),
+        keysyms::XKB_KEY_KP_0 => Some(VirtualKeyCode::Numpad0),
+        keysyms::XKB_KEY_KP_1 => Some(VirtualKeyCode::Numpad1),
+        keysyms::XKB_KEY_KP_2 => Some(VirtualKeyCode::Numpad2),
+        keysyms::XKB_KEY_KP_3 => Some(VirtualKeyCode::Numpad3),
+        keysyms::XKB_KEY_KP_4 => Some(VirtualKeyCode::Numpad4),
+        keysyms::XKB_KEY_KP_5 => Some(VirtualKeyCode::Numpad5),
+        keysyms::XKB_KEY_KP_6 => Some(VirtualKeyCode::Numpad6),
+        keysyms::XKB_KEY_KP_7 => Some(VirtualKeyCode::Numpad7),
+        keysyms::XKB_KEY_KP_8 => Some(VirtualKeyCode::Numpad8),
+        keysyms::XKB_KEY_KP_9 => Some(VirtualKeyCode::Numpad9),
+        // misc
+        // => Some(VirtualKeyCode::AbntC1),
+        // => Some(VirtualKeyCode::AbntC2),
+        keysyms::XKB_KEY_plus => Some(VirtualKeyCode::Add),
+        keysyms::XKB_KEY_apostrophe => Some(VirtualKeyCode::Apostrophe),
+        // => Some(VirtualKeyCode::Apps),
+        keysyms::XKB_KEY_at => Some(VirtualKeyCode::At),
+        // => Some(VirtualKeyCode::Ax),
+        keysyms::XKB_KEY_backslash => Some(VirtualKeyCode::Backslash),
+        // => Some(VirtualKeyCode::Calculator),
+        // => Some(VirtualKeyCode::Capital),
+        keysyms::XKB_KEY_colon => Some(VirtualKeyCode::Colon),
+        keysyms::XKB_KEY_comma => Some(VirtualKeyCode::Comma),
+        // => Some(VirtualKeyCode::Convert),
+        // => Some(VirtualKeyCode::Decimal),
+        // => Some(VirtualKeyCode::Divide),
+        keysyms::XKB_KEY_equal => Some(VirtualKeyCode::Equals),
+        keysyms::XKB_KEY_grave => Some(VirtualKeyCode::Grave),
+        // => Some(VirtualKeyCode::Kana),
+        // => Some(VirtualKeyCode::Kanji),
+        keysyms::XKB_KEY_Alt_L => Some(VirtualKeyCode::LAlt),
+        keysyms::XKB_KEY_bracketleft => Some(VirtualKeyCode::LBracket),
+        keysyms::XKB_KEY_Control_L => Some(VirtualKeyCode::LControl),
+        keysyms::XKB_KEY_Shift_L => Some(VirtualKeyCode::LShift),
+        // => Some(VirtualKeyCode::LWin),
+        // => Some(VirtualKeyCode::Mail),
+        // => Some(VirtualKeyCode::MediaSelect),
+        // => Some(VirtualKeyCode::MediaStop),
+        keysyms::XKB_KEY_minus => Some(VirtualKeyCode::Minus),
+        keysyms::XKB_KEY_asterisk => Some(VirtualKeyCode::Multiply),
+        // => Some(VirtualKeyCode::Mute),
+        // => Some(VirtualKeyCode::MyComputer),
+        // => Some(VirtualKeyCode::NextTrack),
+        // => Some(VirtualKeyCode::NoConvert),
+        keysyms::XKB_KEY_KP_Separator => Some(VirtualKeyCode::NumpadComma),
+        keysyms::XKB_KEY_KP_Enter => Some(VirtualKeyCode::NumpadEnter),
+        keysyms::XKB_KEY_KP_Equal => Some(VirtualKeyCode::NumpadEquals),
+        keysyms::XKB_KEY_KP_Add => Some(VirtualKeyCode::Add),
+        keysyms::XKB_KEY_KP_Subtract => Some(VirtualKeyCode::Subtract),
+        keysyms::XKB_KEY_KP_Divide => Some(VirtualKeyCode::Divide),
+        keysyms::XKB_KEY_KP_Page_Up => Some(VirtualKeyCode::PageUp),
+        keysyms::XKB_KEY_KP_Page_Down => Some(VirtualKeyCode::PageDown),
+        keysyms::XKB_KEY_KP_Home => Some(VirtualKeyCode::Home),
+        keysyms::XKB_KEY_KP_End => Some(VirtualKeyCode::End),
+        keysyms::XKB_KEY_KP_Insert => Some(VirtualKeyCode::Insert),
+        keysyms::XKB_KEY_KP_Delete => Some(VirtualKeyCode::Delete),
+        keysyms::XKB_KEY_KP_Left => Some(VirtualKeyCode::Left),
+        keysyms::XKB_KEY_KP_Up => Some(VirtualKeyCode::Up),
+        keysyms::XKB_KEY_KP_Right => Some(VirtualKeyCode::Right),
+        keysyms::XKB_KEY_KP_Down => Some(VirtualKeyCode::Down),
+        // => Some(VirtualKeyCode::OEM102),
+        keysyms::XKB_KEY_period => Some(VirtualKeyCode::Period),
+        // => Some(VirtualKeyCode::Playpause),
+        // => Some(VirtualKeyCode::Power),
+        // => Some(VirtualKeyCode::Prevtrack),
+        keysyms::XKB_KEY_Alt_R => Some(VirtualKeyCode::RAlt),
+        keysyms::XKB_KEY_bracketright => Some(VirtualKeyCode::RBracket),
+        keysyms::XKB_KEY_Control_R => Some(VirtualKeyCode::RControl),
+        keysyms::XKB_KEY_Shift_R => Some(VirtualKeyCode::RShift),
+        // => Some(VirtualKeyCode::RWin),
+        keysyms::XKB_KEY_semicolon => Some(VirtualKeyCode::Semicolon),
+        keysyms::XKB_KEY_slash => Some(VirtualKeyCode::Slash),
+        // => Some(VirtualKeyCode::Sleep),
+        // => Some(VirtualKeyCode::Stop),
+        // => Some(VirtualKeyCode::Subtract),
+        // => Some(VirtualKeyCode::Sysrq),
+        keysyms::XKB_KEY_Tab => Some(VirtualKeyCode::Tab),
+        keysyms::XKB_KEY_ISO_Left_Tab => Some(VirtualKeyCode::Tab),
+        // => Some(VirtualKeyCode::Underline),
+        // => Some(VirtualKeyCode::Unlabeled),
+        keysyms::XKB_KEY_XF86AudioLowerVolume => Some(VirtualKeyCode::VolumeDown),
+        keysyms::XKB_KEY_XF86AudioRaiseVolume => Some(VirtualKeyCode::VolumeUp),
+        // => Some(VirtualKeyCode::Wake),
+        keysyms::XKB_KEY_XF86Back => Some(VirtualKeyCode::NavigateBackward),
+        // => Some(VirtualKeyCode::WebFavorites),
+        keysyms::XKB_KEY_XF86Forward => Some(VirtualKeyCode::NavigateForward),
+        // => Some(VirtualKeyCode::WebHome),
+        // => Some(VirtualKeyCode::WebRefresh),
+        // => Some(VirtualKeyCode::WebSearch),
+        // => Some(VirtualKeyCode::WebStop),
+        // => Some(VirtualKeyCode::Yen),
+        keysyms::XKB_KEY_XF86Copy => Some(VirtualKeyCode::Copy),
+        keysyms::XKB_KEY_XF86Paste => Some(VirtualKeyCode::Paste),
+        keysyms::XKB_KEY_XF86Cut => Some(VirtualKeyCode::Cut),
+        // fallback
+        _ => None,
+    }
+}
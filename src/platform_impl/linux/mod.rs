@@ -1,31 +1,57 @@
 #![cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
 
-use std::{collections::VecDeque, env, ffi::CStr, fmt, mem::MaybeUninit, os::raw::*, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    env,
+    ffi::CStr,
+    fmt, io,
+    mem::MaybeUninit,
+    os::{raw::*, unix::io::RawFd},
+    sync::Arc,
+    time::Duration,
+};
 
+use instant::Instant;
 use parking_lot::Mutex;
 use raw_window_handle::RawWindowHandle;
 use smithay_client_toolkit::reexports::client::ConnectError;
 
 pub use self::x11::XNotSupported;
-use self::x11::{ffi::XVisualInfo, util::WindowType as XWindowType, XConnection, XError};
+use self::{
+    sources::Sources,
+    x11::{ffi::XVisualInfo, util::WindowType as XWindowType, XConnection, XError},
+};
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
     error::{ExternalError, NotSupportedError, OsError as RootOsError},
-    event::Event,
-    event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
+    event::{DragAction, Event},
+    event_loop::{
+        ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW, SourceId, Timer, TimerId,
+    },
     icon::Icon,
     monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
-    window::{CursorIcon, Fullscreen, WindowAttributes},
+    platform::unix::{FdInterest, PumpStatus, StepStatus},
+    window::{
+        ClipboardContents, ClipboardKind, CursorIcon, CustomCursor, Fullscreen, ResizeDirection,
+        WindowAttributes,
+    },
 };
 
 pub(crate) use crate::icon::RgbaIcon as PlatformIcon;
 
+mod dnd;
+pub mod headless;
+mod keycode;
+mod keysym;
+mod sources;
 pub mod wayland;
 pub mod x11;
+mod xkb;
 
 /// Environment variable specifying which backend should be used on unix platform.
 ///
-/// Legal values are x11 and wayland. If this variable is set only the named backend
+/// Legal values are x11, wayland and headless. If this variable is set only the named backend
 /// will be tried by winit. If it is not set, winit will try to connect to a wayland connection,
 /// and if it fails will fallback on x11.
 ///
@@ -70,6 +96,8 @@ lazy_static! {
 pub enum OsError {
     XError(XError),
     XMisc(&'static str),
+    WaylandMisc(&'static str),
+    IoError(Arc<io::Error>),
 }
 
 impl fmt::Display for OsError {
@@ -77,6 +105,8 @@ impl fmt::Display for OsError {
         match self {
             OsError::XError(e) => f.pad(&e.description),
             OsError::XMisc(e) => f.pad(e),
+            OsError::WaylandMisc(e) => f.pad(e),
+            OsError::IoError(e) => f.pad(&e.to_string()),
         }
     }
 }
@@ -84,12 +114,14 @@ impl fmt::Display for OsError {
 pub enum Window {
     X(x11::Window),
     Wayland(wayland::Window),
+    Headless(headless::Window),
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum WindowId {
     X(x11::WindowId),
     Wayland(wayland::WindowId),
+    Headless(headless::WindowId),
 }
 
 impl WindowId {
@@ -102,6 +134,7 @@ impl WindowId {
 pub enum DeviceId {
     X(x11::DeviceId),
     Wayland(wayland::DeviceId),
+    Headless(headless::DeviceId),
 }
 
 impl DeviceId {
@@ -114,6 +147,7 @@ impl DeviceId {
 pub enum MonitorHandle {
     X(x11::MonitorHandle),
     Wayland(wayland::MonitorHandle),
+    Headless(headless::MonitorHandle),
 }
 
 impl MonitorHandle {
@@ -122,6 +156,7 @@ impl MonitorHandle {
         match self {
             &MonitorHandle::X(ref m) => m.name(),
             &MonitorHandle::Wayland(ref m) => m.name(),
+            &MonitorHandle::Headless(ref m) => m.name(),
         }
     }
 
@@ -130,6 +165,7 @@ impl MonitorHandle {
         match self {
             &MonitorHandle::X(ref m) => m.native_identifier(),
             &MonitorHandle::Wayland(ref m) => m.native_identifier(),
+            &MonitorHandle::Headless(ref m) => m.native_identifier(),
         }
     }
 
@@ -138,6 +174,7 @@ impl MonitorHandle {
         match self {
             &MonitorHandle::X(ref m) => m.size(),
             &MonitorHandle::Wayland(ref m) => m.size(),
+            &MonitorHandle::Headless(ref m) => m.size(),
         }
     }
 
@@ -146,6 +183,7 @@ impl MonitorHandle {
         match self {
             &MonitorHandle::X(ref m) => m.position(),
             &MonitorHandle::Wayland(ref m) => m.position(),
+            &MonitorHandle::Headless(ref m) => m.position(),
         }
     }
 
@@ -154,6 +192,16 @@ impl MonitorHandle {
         match self {
             &MonitorHandle::X(ref m) => m.scale_factor(),
             &MonitorHandle::Wayland(ref m) => m.scale_factor() as f64,
+            &MonitorHandle::Headless(ref m) => m.scale_factor(),
+        }
+    }
+
+    #[inline]
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        match self {
+            &MonitorHandle::X(ref m) => m.work_area(),
+            &MonitorHandle::Wayland(ref m) => m.work_area(),
+            &MonitorHandle::Headless(ref m) => m.work_area(),
         }
     }
 
@@ -162,6 +210,7 @@ impl MonitorHandle {
         match self {
             MonitorHandle::X(m) => Box::new(m.video_modes()),
             MonitorHandle::Wayland(m) => Box::new(m.video_modes()),
+            MonitorHandle::Headless(m) => Box::new(m.video_modes()),
         }
     }
 }
@@ -170,6 +219,7 @@ impl MonitorHandle {
 pub enum VideoMode {
     X(x11::VideoMode),
     Wayland(wayland::VideoMode),
+    Headless(headless::VideoMode),
 }
 
 impl VideoMode {
@@ -178,6 +228,7 @@ impl VideoMode {
         match self {
             &VideoMode::X(ref m) => m.size(),
             &VideoMode::Wayland(ref m) => m.size(),
+            &VideoMode::Headless(ref m) => m.size(),
         }
     }
 
@@ -186,6 +237,7 @@ impl VideoMode {
         match self {
             &VideoMode::X(ref m) => m.bit_depth(),
             &VideoMode::Wayland(ref m) => m.bit_depth(),
+            &VideoMode::Headless(ref m) => m.bit_depth(),
         }
     }
 
@@ -194,6 +246,7 @@ impl VideoMode {
         match self {
             &VideoMode::X(ref m) => m.refresh_rate(),
             &VideoMode::Wayland(ref m) => m.refresh_rate(),
+            &VideoMode::Headless(ref m) => m.refresh_rate(),
         }
     }
 
@@ -202,6 +255,7 @@ impl VideoMode {
         match self {
             &VideoMode::X(ref m) => m.monitor(),
             &VideoMode::Wayland(ref m) => m.monitor(),
+            &VideoMode::Headless(ref m) => m.monitor(),
         }
     }
 }
@@ -220,6 +274,9 @@ impl Window {
             EventLoopWindowTarget::X(ref window_target) => {
                 x11::Window::new(window_target, attribs, pl_attribs).map(Window::X)
             }
+            EventLoopWindowTarget::Headless(ref window_target) => {
+                headless::Window::new(window_target, attribs, pl_attribs).map(Window::Headless)
+            }
         }
     }
 
@@ -228,6 +285,7 @@ impl Window {
         match self {
             &Window::X(ref w) => WindowId::X(w.id()),
             &Window::Wayland(ref w) => WindowId::Wayland(w.id()),
+            &Window::Headless(ref w) => WindowId::Headless(w.id()),
         }
     }
 
@@ -236,6 +294,16 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_title(title),
             &Window::Wayland(ref w) => w.set_title(title),
+            &Window::Headless(ref w) => w.set_title(title),
+        }
+    }
+
+    #[inline]
+    pub fn title(&self) -> String {
+        match self {
+            &Window::X(ref w) => w.title(),
+            &Window::Wayland(ref w) => w.title(),
+            &Window::Headless(ref w) => w.title(),
         }
     }
 
@@ -244,6 +312,16 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_visible(visible),
             &Window::Wayland(ref w) => w.set_visible(visible),
+            &Window::Headless(ref w) => w.set_visible(visible),
+        }
+    }
+
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        match self {
+            &Window::X(ref w) => w.is_visible(),
+            &Window::Wayland(ref w) => w.is_visible(),
+            &Window::Headless(ref w) => w.is_visible(),
         }
     }
 
@@ -252,6 +330,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.outer_position(),
             &Window::Wayland(ref w) => w.outer_position(),
+            &Window::Headless(ref w) => w.outer_position(),
         }
     }
 
@@ -260,6 +339,7 @@ impl Window {
         match self {
             &Window::X(ref m) => m.inner_position(),
             &Window::Wayland(ref m) => m.inner_position(),
+            &Window::Headless(ref m) => m.inner_position(),
         }
     }
 
@@ -268,6 +348,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_outer_position(position),
             &Window::Wayland(ref w) => w.set_outer_position(position),
+            &Window::Headless(ref w) => w.set_outer_position(position),
         }
     }
 
@@ -276,6 +357,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.inner_size(),
             &Window::Wayland(ref w) => w.inner_size(),
+            &Window::Headless(ref w) => w.inner_size(),
         }
     }
 
@@ -284,6 +366,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.outer_size(),
             &Window::Wayland(ref w) => w.outer_size(),
+            &Window::Headless(ref w) => w.outer_size(),
         }
     }
 
@@ -292,6 +375,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_inner_size(size),
             &Window::Wayland(ref w) => w.set_inner_size(size),
+            &Window::Headless(ref w) => w.set_inner_size(size),
         }
     }
 
@@ -300,6 +384,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_min_inner_size(dimensions),
             &Window::Wayland(ref w) => w.set_min_inner_size(dimensions),
+            &Window::Headless(ref w) => w.set_min_inner_size(dimensions),
         }
     }
 
@@ -308,6 +393,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_max_inner_size(dimensions),
             &Window::Wayland(ref w) => w.set_max_inner_size(dimensions),
+            &Window::Headless(ref w) => w.set_max_inner_size(dimensions),
         }
     }
 
@@ -316,6 +402,16 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_resizable(resizable),
             &Window::Wayland(ref w) => w.set_resizable(resizable),
+            &Window::Headless(ref w) => w.set_resizable(resizable),
+        }
+    }
+
+    #[inline]
+    pub fn is_resizable(&self) -> bool {
+        match self {
+            &Window::X(ref w) => w.is_resizable(),
+            &Window::Wayland(ref w) => w.is_resizable(),
+            &Window::Headless(ref w) => w.is_resizable(),
         }
     }
 
@@ -324,6 +420,16 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_cursor_icon(cursor),
             &Window::Wayland(ref w) => w.set_cursor_icon(cursor),
+            &Window::Headless(ref w) => w.set_cursor_icon(cursor),
+        }
+    }
+
+    #[inline]
+    pub fn set_custom_cursor(&self, cursor: CustomCursor) {
+        match self {
+            &Window::X(ref w) => w.set_custom_cursor(cursor),
+            &Window::Wayland(ref w) => w.set_custom_cursor(cursor),
+            &Window::Headless(_) => (),
         }
     }
 
@@ -332,6 +438,7 @@ impl Window {
         match self {
             &Window::X(ref window) => window.set_cursor_grab(grab),
             &Window::Wayland(ref window) => window.set_cursor_grab(grab),
+            &Window::Headless(ref window) => window.set_cursor_grab(grab),
         }
     }
 
@@ -340,6 +447,73 @@ impl Window {
         match self {
             &Window::X(ref window) => window.set_cursor_visible(visible),
             &Window::Wayland(ref window) => window.set_cursor_visible(visible),
+            &Window::Headless(ref window) => window.set_cursor_visible(visible),
+        }
+    }
+
+    #[inline]
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        match self {
+            &Window::X(ref window) => window.drag_window(),
+            &Window::Wayland(ref window) => window.drag_window(),
+            &Window::Headless(ref window) => window.drag_window(),
+        }
+    }
+
+    #[inline]
+    pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
+        match self {
+            &Window::X(ref window) => window.drag_resize_window(direction),
+            &Window::Wayland(ref window) => window.drag_resize_window(direction),
+            &Window::Headless(ref window) => window.drag_resize_window(direction),
+        }
+    }
+
+    #[inline]
+    pub fn set_clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        match self {
+            &Window::X(ref w) => w.set_clipboard_contents(kind, contents),
+            &Window::Wayland(ref w) => w.set_clipboard_contents(kind, contents),
+            &Window::Headless(ref w) => w.set_clipboard_contents(kind, contents),
+        }
+    }
+
+    #[inline]
+    pub fn clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        match self {
+            &Window::X(ref w) => w.clipboard_contents(kind, mime_type),
+            &Window::Wayland(ref w) => w.clipboard_contents(kind, mime_type),
+            &Window::Headless(ref w) => w.clipboard_contents(kind, mime_type),
+        }
+    }
+
+    #[inline]
+    pub fn set_drag_response(&self, response: Option<(String, DragAction)>) {
+        match self {
+            &Window::X(ref w) => w.set_drag_response(response),
+            &Window::Wayland(ref w) => w.set_drag_response(response),
+            &Window::Headless(ref w) => w.set_drag_response(response),
+        }
+    }
+
+    #[inline]
+    pub fn start_drag(
+        &self,
+        contents: ClipboardContents,
+        allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        match self {
+            &Window::X(ref w) => w.start_drag(contents, allowed_actions),
+            &Window::Wayland(ref w) => w.start_drag(contents, allowed_actions),
+            &Window::Headless(ref w) => w.start_drag(contents, allowed_actions),
         }
     }
 
@@ -348,6 +522,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.scale_factor(),
             &Window::Wayland(ref w) => w.scale_factor() as f64,
+            &Window::Headless(ref w) => w.scale_factor(),
         }
     }
 
@@ -356,6 +531,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_cursor_position(position),
             &Window::Wayland(ref w) => w.set_cursor_position(position),
+            &Window::Headless(ref w) => w.set_cursor_position(position),
         }
     }
 
@@ -364,6 +540,16 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_maximized(maximized),
             &Window::Wayland(ref w) => w.set_maximized(maximized),
+            &Window::Headless(ref w) => w.set_maximized(maximized),
+        }
+    }
+
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        match self {
+            &Window::X(ref w) => w.is_maximized(),
+            &Window::Wayland(ref w) => w.is_maximized(),
+            &Window::Headless(ref w) => w.is_maximized(),
         }
     }
 
@@ -372,6 +558,16 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_minimized(minimized),
             &Window::Wayland(ref w) => w.set_minimized(minimized),
+            &Window::Headless(ref w) => w.set_minimized(minimized),
+        }
+    }
+
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        match self {
+            &Window::X(ref w) => w.is_minimized(),
+            &Window::Wayland(ref w) => w.is_minimized(),
+            &Window::Headless(ref w) => w.is_minimized(),
         }
     }
 
@@ -380,6 +576,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.fullscreen(),
             &Window::Wayland(ref w) => w.fullscreen(),
+            &Window::Headless(ref w) => w.fullscreen(),
         }
     }
 
@@ -388,6 +585,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_fullscreen(monitor),
             &Window::Wayland(ref w) => w.set_fullscreen(monitor),
+            &Window::Headless(ref w) => w.set_fullscreen(monitor),
         }
     }
 
@@ -396,6 +594,25 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_decorations(decorations),
             &Window::Wayland(ref w) => w.set_decorations(decorations),
+            &Window::Headless(ref w) => w.set_decorations(decorations),
+        }
+    }
+
+    #[inline]
+    pub fn is_decorated(&self) -> bool {
+        match self {
+            &Window::X(ref w) => w.is_decorated(),
+            &Window::Wayland(ref w) => w.is_decorated(),
+            &Window::Headless(ref w) => w.is_decorated(),
+        }
+    }
+
+    #[inline]
+    pub fn has_focus(&self) -> bool {
+        match self {
+            &Window::X(ref w) => w.has_focus(),
+            &Window::Wayland(ref w) => w.has_focus(),
+            &Window::Headless(ref w) => w.has_focus(),
         }
     }
 
@@ -404,6 +621,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_always_on_top(always_on_top),
             &Window::Wayland(_) => (),
+            &Window::Headless(_) => (),
         }
     }
 
@@ -412,6 +630,7 @@ impl Window {
         match self {
             &Window::X(ref w) => w.set_window_icon(window_icon),
             &Window::Wayland(_) => (),
+            &Window::Headless(_) => (),
         }
     }
 
@@ -419,7 +638,17 @@ impl Window {
     pub fn set_ime_position(&self, position: Position) {
         match self {
             &Window::X(ref w) => w.set_ime_position(position),
-            &Window::Wayland(_) => (),
+            &Window::Wayland(ref w) => w.set_ime_position(position),
+            &Window::Headless(_) => (),
+        }
+    }
+
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        match self {
+            &Window::X(ref w) => w.set_ime_allowed(allowed),
+            &Window::Wayland(ref w) => w.set_ime_allowed(allowed),
+            &Window::Headless(_) => (),
         }
     }
 
@@ -428,6 +657,16 @@ impl Window {
         match self {
             &Window::X(ref w) => w.request_redraw(),
             &Window::Wayland(ref w) => w.request_redraw(),
+            &Window::Headless(ref w) => w.request_redraw(),
+        }
+    }
+
+    #[inline]
+    pub fn request_frame_callback(&self) {
+        match self {
+            &Window::X(ref w) => w.request_frame_callback(),
+            &Window::Wayland(ref w) => w.request_frame_callback(),
+            &Window::Headless(ref w) => w.request_frame_callback(),
         }
     }
 
@@ -440,6 +679,9 @@ impl Window {
             &Window::Wayland(ref window) => RootMonitorHandle {
                 inner: MonitorHandle::Wayland(window.current_monitor()),
             },
+            &Window::Headless(ref window) => RootMonitorHandle {
+                inner: MonitorHandle::Headless(window.current_monitor()),
+            },
         }
     }
 
@@ -456,6 +698,11 @@ impl Window {
                 .into_iter()
                 .map(MonitorHandle::Wayland)
                 .collect(),
+            &Window::Headless(ref window) => window
+                .available_monitors()
+                .into_iter()
+                .map(MonitorHandle::Headless)
+                .collect(),
         }
     }
 
@@ -464,6 +711,7 @@ impl Window {
         match self {
             &Window::X(ref window) => MonitorHandle::X(window.primary_monitor()),
             &Window::Wayland(ref window) => MonitorHandle::Wayland(window.primary_monitor()),
+            &Window::Headless(ref window) => MonitorHandle::Headless(window.primary_monitor()),
         }
     }
 
@@ -471,6 +719,7 @@ impl Window {
         match self {
             &Window::X(ref window) => RawWindowHandle::Xlib(window.raw_window_handle()),
             &Window::Wayland(ref window) => RawWindowHandle::Wayland(window.raw_window_handle()),
+            &Window::Headless(ref window) => RawWindowHandle::Xlib(window.raw_window_handle()),
         }
     }
 }
@@ -510,11 +759,13 @@ unsafe extern "C" fn x_error_callback(
 pub enum EventLoop<T: 'static> {
     Wayland(wayland::EventLoop<T>),
     X(x11::EventLoop<T>),
+    Headless(headless::EventLoop<T>),
 }
 
 pub enum EventLoopProxy<T: 'static> {
     X(x11::EventLoopProxy<T>),
     Wayland(wayland::EventLoopProxy<T>),
+    Headless(headless::EventLoopProxy<T>),
 }
 
 impl<T: 'static> Clone for EventLoopProxy<T> {
@@ -522,6 +773,7 @@ impl<T: 'static> Clone for EventLoopProxy<T> {
         match self {
             EventLoopProxy::X(proxy) => EventLoopProxy::X(proxy.clone()),
             EventLoopProxy::Wayland(proxy) => EventLoopProxy::Wayland(proxy.clone()),
+            EventLoopProxy::Headless(proxy) => EventLoopProxy::Headless(proxy.clone()),
         }
     }
 }
@@ -545,8 +797,9 @@ impl<T: 'static> EventLoop<T> {
                     return EventLoop::new_wayland_any_thread()
                         .expect("Failed to initialize Wayland backend");
                 }
+                "headless" => return EventLoop::new_headless(),
                 _ => panic!(
-                    "Unknown environment variable value for {}, try one of `x11`,`wayland`",
+                    "Unknown environment variable value for {}, try one of `x11`,`wayland`,`headless`",
                     BACKEND_PREFERENCE_ENV_VAR,
                 ),
             }
@@ -594,6 +847,10 @@ impl<T: 'static> EventLoop<T> {
         Ok(EventLoop::X(x11::EventLoop::new(xconn)))
     }
 
+    pub fn new_headless() -> EventLoop<T> {
+        EventLoop::Headless(headless::EventLoop::new())
+    }
+
     #[inline]
     pub fn available_monitors(&self) -> VecDeque<MonitorHandle> {
         match *self {
@@ -608,6 +865,11 @@ impl<T: 'static> EventLoop<T> {
                 .into_iter()
                 .map(MonitorHandle::X)
                 .collect(),
+            EventLoop::Headless(ref evlp) => evlp
+                .available_monitors()
+                .into_iter()
+                .map(MonitorHandle::Headless)
+                .collect(),
         }
     }
 
@@ -616,6 +878,7 @@ impl<T: 'static> EventLoop<T> {
         match *self {
             EventLoop::Wayland(ref evlp) => MonitorHandle::Wayland(evlp.primary_monitor()),
             EventLoop::X(ref evlp) => MonitorHandle::X(evlp.x_connection().primary_monitor()),
+            EventLoop::Headless(ref evlp) => MonitorHandle::Headless(evlp.primary_monitor()),
         }
     }
 
@@ -623,6 +886,7 @@ impl<T: 'static> EventLoop<T> {
         match *self {
             EventLoop::Wayland(ref evlp) => EventLoopProxy::Wayland(evlp.create_proxy()),
             EventLoop::X(ref evlp) => EventLoopProxy::X(evlp.create_proxy()),
+            EventLoop::Headless(ref evlp) => EventLoopProxy::Headless(evlp.create_proxy()),
         }
     }
 
@@ -633,6 +897,30 @@ impl<T: 'static> EventLoop<T> {
         match *self {
             EventLoop::Wayland(ref mut evlp) => evlp.run_return(callback),
             EventLoop::X(ref mut evlp) => evlp.run_return(callback),
+            EventLoop::Headless(ref mut evlp) => evlp.run_return(callback),
+        }
+    }
+
+    pub fn pump_events<F>(&mut self, timeout: Option<Duration>, callback: F) -> PumpStatus
+    where
+        F: FnMut(crate::event::Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        match *self {
+            EventLoop::Wayland(ref mut evlp) => evlp.pump_events(timeout, callback),
+            EventLoop::X(ref mut evlp) => evlp.pump_events(timeout, callback),
+            // The headless loop has nothing to wait for, its clock only moves when told to.
+            EventLoop::Headless(ref mut evlp) => match evlp.step(callback) {
+                StepStatus::Dispatched | StepStatus::Idle => PumpStatus::Continue,
+                StepStatus::Exited => PumpStatus::Exit,
+            },
+        }
+    }
+
+    pub fn as_raw_fd(&self) -> RawFd {
+        match *self {
+            EventLoop::Wayland(ref evlp) => evlp.as_raw_fd(),
+            EventLoop::X(ref evlp) => evlp.as_raw_fd(),
+            EventLoop::Headless(_) => panic!("the headless backend has no file descriptor"),
         }
     }
 
@@ -643,6 +931,7 @@ impl<T: 'static> EventLoop<T> {
         match self {
             EventLoop::Wayland(evlp) => evlp.run(callback),
             EventLoop::X(evlp) => evlp.run(callback),
+            EventLoop::Headless(evlp) => evlp.run(callback),
         }
     }
 
@@ -650,6 +939,7 @@ impl<T: 'static> EventLoop<T> {
         match *self {
             EventLoop::Wayland(ref evl) => evl.window_target(),
             EventLoop::X(ref evl) => evl.window_target(),
+            EventLoop::Headless(ref evl) => evl.window_target(),
         }
     }
 }
@@ -659,6 +949,7 @@ impl<T: 'static> EventLoopProxy<T> {
         match *self {
             EventLoopProxy::Wayland(ref proxy) => proxy.send_event(event),
             EventLoopProxy::X(ref proxy) => proxy.send_event(event),
+            EventLoopProxy::Headless(ref proxy) => proxy.send_event(event),
         }
     }
 }
@@ -666,6 +957,7 @@ impl<T: 'static> EventLoopProxy<T> {
 pub enum EventLoopWindowTarget<T> {
     Wayland(wayland::EventLoopWindowTarget<T>),
     X(x11::EventLoopWindowTarget<T>),
+    Headless(headless::EventLoopWindowTarget<T>),
 }
 
 impl<T> EventLoopWindowTarget<T> {
@@ -673,9 +965,74 @@ impl<T> EventLoopWindowTarget<T> {
     pub fn is_wayland(&self) -> bool {
         match *self {
             EventLoopWindowTarget::Wayland(_) => true,
-            EventLoopWindowTarget::X(_) => false,
+            EventLoopWindowTarget::X(_) | EventLoopWindowTarget::Headless(_) => false,
+        }
+    }
+
+    #[inline]
+    pub fn is_x11(&self) -> bool {
+        match *self {
+            EventLoopWindowTarget::X(_) => true,
+            EventLoopWindowTarget::Wayland(_) | EventLoopWindowTarget::Headless(_) => false,
+        }
+    }
+
+    #[inline]
+    pub fn is_headless(&self) -> bool {
+        match *self {
+            EventLoopWindowTarget::Headless(_) => true,
+            EventLoopWindowTarget::Wayland(_) | EventLoopWindowTarget::X(_) => false,
+        }
+    }
+
+    fn sources(&self) -> &RefCell<Sources> {
+        match *self {
+            EventLoopWindowTarget::Wayland(ref wt) => &wt.sources,
+            EventLoopWindowTarget::X(ref wt) => &wt.sources,
+            EventLoopWindowTarget::Headless(ref wt) => &wt.sources,
         }
     }
+
+    #[inline]
+    pub fn register_fd(&self, fd: RawFd, interest: FdInterest) -> Result<SourceId, ExternalError> {
+        self.sources().borrow_mut().register_fd(fd, interest)
+    }
+
+    #[inline]
+    pub fn unregister_fd(&self, source: SourceId) {
+        self.sources().borrow_mut().unregister_fd(source)
+    }
+
+    pub fn set_timer(&self, timer: Timer) -> Result<TimerId, ExternalError> {
+        let now = match *self {
+            EventLoopWindowTarget::Headless(ref wt) => wt.now(),
+            EventLoopWindowTarget::Wayland(_) | EventLoopWindowTarget::X(_) => Instant::now(),
+        };
+        self.sources().borrow_mut().set_timer(timer, now)
+    }
+
+    #[inline]
+    pub fn cancel_timer(&self, id: TimerId) {
+        self.sources().borrow_mut().cancel_timer(id)
+    }
+}
+
+/// How far `pump_events` got with the loop of a backend.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PumpState {
+    NotStarted,
+    /// The control flow set by the last iteration
+    Running(ControlFlow),
+    Exited,
+}
+
+/// The shortest of two timeouts, `None` standing for no timeout.
+fn min_timeout(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
 }
 
 fn sticky_exit_callback<T, F>(
@@ -0,0 +1,203 @@
+//! The file descriptors and timers the user registers with the event loop.
+
+use std::{io, os::unix::io::RawFd, rc::Rc, sync::Arc, time::Duration};
+
+use instant::Instant;
+use mio::{unix::EventedFd, Events, Poll, PollOpt, Ready, Token};
+
+use crate::{
+    error::{ExternalError, NotSupportedError},
+    event::Event,
+    event_loop::{SourceId, Timer, TimerId},
+    platform::unix::FdInterest,
+};
+
+use super::OsError;
+
+// The tokens below this one are left to the sources of the backends.
+const FIRST_SOURCE_TOKEN: usize = 16;
+
+#[derive(Default)]
+pub struct Sources {
+    // The poll of the backend, which the file descriptors are registered with. The headless
+    // backend has none, so it doesn't support them.
+    poll: Option<Rc<Poll>>,
+    next_id: u64,
+    fds: Vec<(SourceId, RawFd)>,
+    // Sorted by deadline, the timers with the same deadline keeping the order they were set in
+    timers: Vec<PendingTimer>,
+}
+
+struct PendingTimer {
+    deadline: Instant,
+    id: TimerId,
+    interval: Option<Duration>,
+}
+
+impl Sources {
+    pub fn new(poll: Rc<Poll>) -> Self {
+        Sources {
+            poll: Some(poll),
+            ..Default::default()
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    pub fn register_fd(
+        &mut self,
+        fd: RawFd,
+        interest: FdInterest,
+    ) -> Result<SourceId, ExternalError> {
+        let poll = match self.poll {
+            Some(ref poll) => Rc::clone(poll),
+            None => return Err(ExternalError::NotSupported(NotSupportedError::new())),
+        };
+
+        let mut ready = Ready::empty();
+        if interest.contains(FdInterest::READABLE) {
+            ready |= Ready::readable();
+        }
+        if interest.contains(FdInterest::WRITABLE) {
+            ready |= Ready::writable();
+        }
+
+        let id = SourceId(self.next_id());
+        poll.register(&EventedFd(&fd), token(id), ready, PollOpt::level())
+            .map_err(|e| ExternalError::Os(os_error!(OsError::IoError(Arc::new(e)))))?;
+        self.fds.push((id, fd));
+        Ok(id)
+    }
+
+    pub fn unregister_fd(&mut self, source: SourceId) {
+        let index = match self.fds.iter().position(|&(id, _)| id == source) {
+            Some(index) => index,
+            None => return,
+        };
+        let (_, fd) = self.fds.remove(index);
+        if let Some(ref poll) = self.poll {
+            // This fails if the file descriptor was closed already, which unregistered it anyway.
+            let _ = poll.deregister(&EventedFd(&fd));
+        }
+    }
+
+    /// Sets a timer, `now` being the time its interval starts from.
+    ///
+    /// Zero intervals are rejected, as the timer would fire on every iteration and keep the loop
+    /// from ever waiting, and so are intervals too long to compute the deadline of.
+    pub fn set_timer(&mut self, timer: Timer, now: Instant) -> Result<TimerId, ExternalError> {
+        let (deadline, interval) = match timer {
+            Timer::Deadline(deadline) => (deadline, None),
+            Timer::Interval(interval) if interval == Duration::from_secs(0) => {
+                return Err(invalid_timer("the interval of a timer can't be zero"))
+            }
+            Timer::Interval(interval) => match now.checked_add(interval) {
+                Some(deadline) => (deadline, Some(interval)),
+                None => return Err(invalid_timer("the interval of the timer is too long")),
+            },
+        };
+        let id = TimerId(self.next_id());
+        self.insert_timer(PendingTimer {
+            deadline,
+            id,
+            interval,
+        });
+        Ok(id)
+    }
+
+    fn insert_timer(&mut self, timer: PendingTimer) {
+        let index = self
+            .timers
+            .iter()
+            .position(|pending| pending.deadline > timer.deadline)
+            .unwrap_or(self.timers.len());
+        self.timers.insert(index, timer);
+    }
+
+    pub fn cancel_timer(&mut self, id: TimerId) {
+        self.timers.retain(|timer| timer.id != id);
+    }
+
+    /// The deadline of the timer which fires first, and its id.
+    pub fn next_timer(&self) -> Option<(Instant, TimerId)> {
+        self.timers.first().map(|timer| (timer.deadline, timer.id))
+    }
+
+    /// The deadline of the timer which fires first.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.next_timer().map(|(deadline, _)| deadline)
+    }
+
+    /// Takes the timers which fired by `now`, returning their events in the order of their
+    /// deadlines. Each timer fires once, even if it's an interval timer which missed several of
+    /// its periods, and is then set again for its first period after `now`, unless that's too far
+    /// in the future to be represented.
+    pub fn expire_timers(&mut self, now: Instant) -> Vec<Event<'static, ()>> {
+        let expired = self
+            .timers
+            .iter()
+            .take_while(|timer| timer.deadline <= now)
+            .count();
+        let expired: Vec<_> = self.timers.drain(..expired).collect();
+
+        let mut events = Vec::with_capacity(expired.len());
+        for mut timer in expired {
+            events.push(Event::TimerExpired(timer.id));
+            if let Some(interval) = timer.interval {
+                let missed_periods = (now - timer.deadline).as_nanos() / interval.as_nanos();
+                let deadline = timer.deadline.checked_add(Duration::from_nanos(
+                    (interval.as_nanos() * (missed_periods + 1)) as u64,
+                ));
+                if let Some(deadline) = deadline {
+                    timer.deadline = deadline;
+                    self.insert_timer(timer);
+                }
+            }
+        }
+        events
+    }
+
+    /// The events of the file descriptors which are ready according to `events`, followed by the
+    /// ones of the timers which fired by `now`.
+    pub fn ready(&mut self, events: &Events, now: Instant) -> Vec<Event<'static, ()>> {
+        let mut ready: Vec<_> = events
+            .iter()
+            .filter_map(|event| {
+                let source = self
+                    .fds
+                    .iter()
+                    .find(|&&(id, _)| token(id) == event.token())?
+                    .0;
+                Some(Event::FdReady {
+                    source,
+                    readable: event.readiness().is_readable(),
+                    writable: event.readiness().is_writable(),
+                })
+            })
+            .collect();
+        ready.extend(self.expire_timers(now));
+        ready
+    }
+}
+
+/// The timer which fired first among `events`, which is the one reported by
+/// `StartCause::TimerFired`.
+pub fn first_timer<T>(events: &[Event<'static, T>]) -> Option<TimerId> {
+    events.iter().find_map(|event| match *event {
+        Event::TimerExpired(id) => Some(id),
+        _ => None,
+    })
+}
+
+fn invalid_timer(message: &'static str) -> ExternalError {
+    let error = io::Error::new(io::ErrorKind::InvalidInput, message);
+    ExternalError::Os(os_error!(OsError::IoError(Arc::new(error))))
+}
+
+fn token(id: SourceId) -> Token {
+    Token(FIRST_SOURCE_TOKEN + id.0 as usize)
+}
@@ -0,0 +1,496 @@
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    os::unix::io::{AsRawFd, FromRawFd},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use smithay_client_toolkit::reexports::client::{
+    protocol::{
+        wl_data_device,
+        wl_data_device_manager::{DndAction, WlDataDeviceManager},
+        wl_data_offer, wl_data_source, wl_seat, wl_surface,
+    },
+    Display,
+};
+
+use crate::{
+    event::{DragAction, WindowEvent},
+    platform_impl::platform::dnd::DragResponse,
+    window::{ClipboardContents, ClipboardKind},
+};
+
+use super::{
+    dnd::{self, Dnd, DragSource},
+    event_loop::EventsSink,
+    make_wid, WindowId,
+};
+
+/// How long to wait for the owner of the selection to send its contents.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A selection or drag and drop offer advertised by the compositor, along with the mime types
+/// it was offered in.
+pub struct Offer {
+    pub offer: wl_data_offer::WlDataOffer,
+    pub mime_types: Vec<String>,
+    // The actions supported by the source of a drag and drop offer.
+    pub source_actions: DndAction,
+}
+
+/// Tracks the `wl_data_device` of every seat, the current selection and the last input event.
+///
+/// Setting the selection requires a serial from a recent input event, so keyboard and pointer
+/// handlers report theirs, along with their seat, through `update_serial`.
+pub struct Clipboard {
+    sink: EventsSink,
+    manager: Option<WlDataDeviceManager>,
+    devices: Vec<(u32, wl_seat::WlSeat, wl_data_device::WlDataDevice)>,
+    offers: Vec<Offer>,
+    selection: Option<Offer>,
+    dnd: Dnd,
+    source: Option<(wl_data_source::WlDataSource, ClipboardContents)>,
+    // The source of the drag started by one of our windows, along with its contents
+    drag_source: Option<(wl_data_source::WlDataSource, ClipboardContents)>,
+    // The seat and serial of the last input event
+    last_input: Option<(wl_seat::WlSeat, u32)>,
+    keyboard_focus: Option<WindowId>,
+    selection_changed: bool,
+}
+
+impl Clipboard {
+    pub fn new(sink: EventsSink, display: Arc<Display>) -> Clipboard {
+        Clipboard {
+            dnd: Dnd::new(sink.clone(), display),
+            sink,
+            manager: None,
+            devices: Vec::new(),
+            offers: Vec::new(),
+            selection: None,
+            source: None,
+            drag_source: None,
+            last_input: None,
+            keyboard_focus: None,
+            selection_changed: false,
+        }
+    }
+
+    /// Sets the data device manager, creating data devices for the seats that are already known.
+    pub fn set_manager(
+        this: &Arc<Mutex<Clipboard>>,
+        manager: WlDataDeviceManager,
+        seats: &[(u32, wl_seat::WlSeat)],
+    ) {
+        this.lock().unwrap().manager = Some(manager);
+        for &(id, ref seat) in seats {
+            Clipboard::new_seat(this, id, seat);
+        }
+    }
+
+    pub fn new_seat(this: &Arc<Mutex<Clipboard>>, id: u32, seat: &wl_seat::WlSeat) {
+        let mut clipboard = this.lock().unwrap();
+        let weak = Arc::downgrade(this);
+        let device = match clipboard.manager {
+            Some(ref manager) => manager.get_data_device(seat, move |device| {
+                device.implement_closure(
+                    move |event, _| {
+                        if let Some(clipboard) = weak.upgrade() {
+                            Clipboard::handle_device_event(&clipboard, event);
+                        }
+                    },
+                    (),
+                )
+            }),
+            None => return,
+        };
+        if let Ok(device) = device {
+            clipboard.devices.push((id, seat.clone(), device));
+        }
+    }
+
+    pub fn remove_seat(&mut self, id: u32) {
+        if let Some(idx) = self.devices.iter().position(|&(i, _, _)| i == id) {
+            let (_, seat, device) = self.devices.swap_remove(idx);
+            if let Some((ref last_seat, _)) = self.last_input {
+                if last_seat.as_ref().equals(seat.as_ref()) {
+                    self.last_input = None;
+                }
+            }
+            if device.as_ref().version() >= 2 {
+                device.release();
+            }
+        }
+    }
+
+    /// Records the seat and serial of an input event, used to set the selection.
+    pub fn update_serial(&mut self, seat: &wl_seat::WlSeat, serial: u32) {
+        self.last_input = Some((seat.clone(), serial));
+    }
+
+    /// The seat and serial of the last input event, also needed to start interactive moves and
+    /// resizes.
+    pub fn last_input(&self) -> Option<(wl_seat::WlSeat, u32)> {
+        self.last_input.clone()
+    }
+
+    fn last_serial(&self) -> u32 {
+        self.last_input
+            .as_ref()
+            .map(|&(_, serial)| serial)
+            .unwrap_or(0)
+    }
+
+    pub fn keyboard_enter(&mut self, wid: WindowId, seat: &wl_seat::WlSeat, serial: u32) {
+        self.update_serial(seat, serial);
+        self.keyboard_focus = Some(wid);
+        // The compositor sends the selection right before keyboard focus, so report it now that
+        // we know which window to report it to.
+        if self.selection_changed {
+            self.selection_changed = false;
+            self.sink
+                .send_window_event(WindowEvent::ClipboardChanged(ClipboardKind::Clipboard), wid);
+        }
+    }
+
+    pub fn keyboard_leave(&mut self) {
+        self.keyboard_focus = None;
+    }
+
+    fn handle_device_event(this: &Arc<Mutex<Clipboard>>, event: wl_data_device::Event) {
+        let mut clipboard = this.lock().unwrap();
+        match event {
+            wl_data_device::Event::DataOffer { id } => {
+                let weak = Arc::downgrade(this);
+                let offer = id.implement_closure(
+                    move |event, offer| {
+                        if let Some(clipboard) = weak.upgrade() {
+                            clipboard.lock().unwrap().handle_offer_event(&offer, event);
+                        }
+                    },
+                    (),
+                );
+                clipboard.offers.push(Offer {
+                    offer,
+                    mime_types: Vec::new(),
+                    source_actions: DndAction::empty(),
+                });
+            }
+            wl_data_device::Event::Selection { id } => {
+                let selection = id.and_then(|offer| {
+                    let idx = clipboard
+                        .offers
+                        .iter()
+                        .position(|o| o.offer.as_ref().equals(offer.as_ref()))?;
+                    Some(clipboard.offers.swap_remove(idx))
+                });
+                if let Some(old) = std::mem::replace(&mut clipboard.selection, selection) {
+                    old.offer.destroy();
+                }
+                match clipboard.keyboard_focus {
+                    Some(wid) => clipboard.sink.send_window_event(
+                        WindowEvent::ClipboardChanged(ClipboardKind::Clipboard),
+                        wid,
+                    ),
+                    None => clipboard.selection_changed = true,
+                }
+            }
+            wl_data_device::Event::Enter {
+                serial,
+                surface,
+                x,
+                y,
+                id,
+            } => {
+                let offer = id.and_then(|offer| {
+                    let idx = clipboard
+                        .offers
+                        .iter()
+                        .position(|o| o.offer.as_ref().equals(offer.as_ref()))?;
+                    Some(clipboard.offers.swap_remove(idx))
+                });
+                clipboard.dnd.enter(serial, &surface, x, y, offer);
+            }
+            wl_data_device::Event::Motion { x, y, .. } => clipboard.dnd.motion(x, y),
+            wl_data_device::Event::Leave => clipboard.dnd.leave(),
+            wl_data_device::Event::Drop => {
+                let Clipboard {
+                    ref mut dnd,
+                    ref drag_source,
+                    ..
+                } = *clipboard;
+                dnd.drop(drag_source.as_ref().map(|(_, contents)| contents));
+            }
+            _ => (),
+        }
+    }
+
+    fn handle_offer_event(
+        &mut self,
+        offer: &wl_data_offer::WlDataOffer,
+        event: wl_data_offer::Event,
+    ) {
+        match event {
+            wl_data_offer::Event::Offer { mime_type } => {
+                if let Some(o) = self.find_offer(offer) {
+                    o.mime_types.push(mime_type);
+                }
+            }
+            wl_data_offer::Event::SourceActions { source_actions } => {
+                if let Some(o) = self.find_offer(offer) {
+                    o.source_actions = DndAction::from_bits_truncate(source_actions);
+                }
+            }
+            wl_data_offer::Event::Action { dnd_action } => self
+                .dnd
+                .action(offer, DndAction::from_bits_truncate(dnd_action)),
+            _ => (),
+        }
+    }
+
+    fn find_offer(&mut self, offer: &wl_data_offer::WlDataOffer) -> Option<&mut Offer> {
+        let dnd_offer = self.dnd.offer_mut(offer);
+        self.offers
+            .iter_mut()
+            .chain(self.selection.as_mut())
+            .find(|o| o.offer.as_ref().equals(offer.as_ref()))
+            .or(dnd_offer)
+    }
+
+    /// Records the answer of a window to the drag and drop operation over it.
+    pub fn respond_to_drag(&mut self, window: WindowId, response: DragResponse) {
+        self.dnd.respond(window, response);
+    }
+
+    pub fn set_contents(
+        this: &Arc<Mutex<Clipboard>>,
+        contents: ClipboardContents,
+    ) -> Result<(), &'static str> {
+        let mut clipboard = this.lock().unwrap();
+        let manager = match clipboard.manager {
+            Some(ref manager) => manager.clone(),
+            None => return Err("`wl_data_device_manager` is not available"),
+        };
+
+        let weak = Arc::downgrade(this);
+        let source_contents = contents.clone();
+        let source = manager
+            .create_data_source(move |source| {
+                // The window may live on another thread than the event queue.
+                source.implement_closure_threadsafe(
+                    move |event, source| match event {
+                        wl_data_source::Event::Send { mime_type, fd } => {
+                            let file = unsafe { File::from_raw_fd(fd) };
+                            if let Some(data) = source_contents.get(&mime_type) {
+                                write_pipe(file, data.to_vec());
+                            }
+                        }
+                        wl_data_source::Event::Cancelled => {
+                            source.destroy();
+                            if let Some(clipboard) = weak.upgrade() {
+                                clipboard.lock().unwrap().source_cancelled(&source);
+                            }
+                        }
+                        _ => (),
+                    },
+                    (),
+                )
+            })
+            .map_err(|()| "failed to create a data source")?;
+
+        for mime_type in contents.mime_types() {
+            source.offer(mime_type.into());
+        }
+        let serial = clipboard.last_serial();
+        for (_, _, device) in &clipboard.devices {
+            device.set_selection(Some(&source), serial);
+        }
+        if let Some((old, _)) = clipboard.source.replace((source, contents)) {
+            old.destroy();
+        }
+        Ok(())
+    }
+
+    pub fn start_drag(
+        this: &Arc<Mutex<Clipboard>>,
+        surface: &wl_surface::WlSurface,
+        contents: ClipboardContents,
+        allowed_actions: &[DragAction],
+    ) -> Result<(), &'static str> {
+        let mut clipboard = this.lock().unwrap();
+        if clipboard.drag_source.is_some() {
+            return Err("a drag is already in progress");
+        }
+        let manager = match clipboard.manager {
+            Some(ref manager) => manager.clone(),
+            None => return Err("`wl_data_device_manager` is not available"),
+        };
+        // The drag has to start from the seat whose input event the serial belongs to.
+        let (seat, serial) = match clipboard.last_input {
+            Some((ref seat, serial)) => (seat.clone(), serial),
+            None => return Err("no input event to start the drag from"),
+        };
+        let device = match clipboard
+            .devices
+            .iter()
+            .find(|&(_, device_seat, _)| device_seat.as_ref().equals(seat.as_ref()))
+        {
+            Some((_, _, device)) => device.clone(),
+            None => return Err("no data device for the seat"),
+        };
+
+        let weak = Arc::downgrade(this);
+        let mut drag_source =
+            DragSource::new(clipboard.sink.clone(), make_wid(surface), contents.clone());
+        let source = manager
+            .create_data_source(move |source| {
+                // The window may live on another thread than the event queue.
+                source.implement_closure_threadsafe(
+                    move |event, source| {
+                        if drag_source.handle_event(event, &source) {
+                            if let Some(clipboard) = weak.upgrade() {
+                                clipboard.lock().unwrap().drag_source_ended(&source);
+                            }
+                        }
+                    },
+                    (),
+                )
+            })
+            .map_err(|()| "failed to create a data source")?;
+
+        for mime_type in contents.mime_types() {
+            source.offer(mime_type.into());
+        }
+        if source.as_ref().version() >= 3 {
+            let actions = allowed_actions
+                .iter()
+                .fold(DndAction::empty(), |actions, &action| {
+                    actions | dnd::dnd_action(action)
+                });
+            if actions.is_empty() {
+                source.set_actions(DndAction::Copy.to_raw());
+            } else {
+                source.set_actions(actions.to_raw());
+            }
+        }
+        device.start_drag(Some(&source), surface, None, serial);
+        clipboard.drag_source = Some((source, contents));
+        Ok(())
+    }
+
+    fn drag_source_ended(&mut self, source: &wl_data_source::WlDataSource) {
+        let ours = match self.drag_source {
+            Some((ref s, _)) => s.as_ref().equals(source.as_ref()),
+            None => false,
+        };
+        if ours {
+            self.drag_source = None;
+        }
+    }
+
+    fn source_cancelled(&mut self, source: &wl_data_source::WlDataSource) {
+        let ours = match self.source {
+            Some((ref s, _)) => s.as_ref().equals(source.as_ref()),
+            None => false,
+        };
+        if ours {
+            self.source = None;
+        }
+    }
+
+    /// Returns the contents we own, if we currently own the selection.
+    pub fn owned_contents(&self) -> Option<&ClipboardContents> {
+        self.source.as_ref().map(|(_, contents)| contents)
+    }
+
+    /// Asks the selection owner to write its contents in `mime_type` to a pipe, and returns the
+    /// read end of that pipe. Returns `None` if there is no selection or it isn't available in
+    /// the requested format.
+    pub fn receive(&self, mime_type: &str) -> Result<Option<File>, &'static str> {
+        let selection = match self.selection {
+            Some(ref selection) => selection,
+            None => return Ok(None),
+        };
+        let mime_type = match selection
+            .mime_types
+            .iter()
+            .find(|m| *m == mime_type)
+            .or_else(|| {
+                if crate::window::is_text_mime_type(mime_type) {
+                    selection
+                        .mime_types
+                        .iter()
+                        .find(|m| crate::window::is_text_mime_type(m))
+                } else {
+                    None
+                }
+            }) {
+            Some(mime_type) => mime_type.clone(),
+            None => return Ok(None),
+        };
+
+        receive_pipe(&selection.offer, mime_type).map(Some)
+    }
+}
+
+/// Asks the source of an offer to write its contents in `mime_type` to a pipe, and returns the
+/// read end of that pipe. The request must be flushed before reading from it.
+pub fn receive_pipe(
+    offer: &wl_data_offer::WlDataOffer,
+    mime_type: String,
+) -> Result<File, &'static str> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err("failed to create a pipe");
+    }
+    let (read, write) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    offer.receive(mime_type, fds[1]);
+    // libwayland duplicates the fd when marshalling the request, so our end can be closed right
+    // away.
+    drop(write);
+    Ok(read)
+}
+
+/// Writes `data` to the pipe of a client which asked for our contents, from a thread of its own so
+/// that a client reading slowly doesn't block the event loop.
+pub fn write_pipe(mut pipe: File, data: Vec<u8>) {
+    thread::spawn(move || {
+        let _ = pipe.write_all(&data);
+    });
+}
+
+/// Reads the whole contents of a pipe returned by `receive_pipe`, giving up if the owner of the
+/// offer takes longer than `READ_TIMEOUT` to send them.
+pub fn read_pipe(mut pipe: File) -> Result<Vec<u8>, &'static str> {
+    let deadline = Instant::now() + READ_TIMEOUT;
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err("timed out waiting for the selection owner");
+        }
+        let timeout = (deadline - now).as_millis() as libc::c_int;
+        let mut fd = libc::pollfd {
+            fd: pipe.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if unsafe { libc::poll(&mut fd, 1, timeout) } < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err("failed to poll the selection pipe");
+        }
+        if fd.revents == 0 {
+            continue;
+        }
+        match pipe.read(&mut buf) {
+            Ok(0) => return Ok(data),
+            Ok(n) => data.extend_from_slice(&buf[..n]),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(_) => return Err("failed to read the selection pipe"),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    os::unix::io::AsRawFd,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::Instant,
+};
+
+use smithay_client_toolkit::reexports::client::{
+    protocol::{
+        wl_buffer::WlBuffer, wl_callback, wl_compositor::WlCompositor, wl_shm,
+        wl_surface::WlSurface,
+    },
+    NewProxy,
+};
+
+use crate::window::CustomCursor;
+
+/// A surface showing the image of a custom cursor, which can be attached to pointers.
+///
+/// Animated cursors are driven by frame callbacks on the surface, so they only advance while the
+/// compositor actually shows the cursor. Every proxy is created with a thread-safe
+/// implementation, so this can be built from any thread.
+pub struct CustomCursorSurface {
+    pub cursor: CustomCursor,
+    pub surface: WlSurface,
+    animation: Arc<Mutex<Animation>>,
+    // The memory backing the buffers, which the compositor may read from at any time.
+    _file: File,
+}
+
+struct Animation {
+    cursor: CustomCursor,
+    surface: WlSurface,
+    // One buffer per frame of `cursor`.
+    buffers: Vec<WlBuffer>,
+    current: usize,
+    shown_at: Instant,
+}
+
+impl CustomCursorSurface {
+    pub fn new(
+        compositor: &WlCompositor,
+        shm: &wl_shm::WlShm,
+        cursor: CustomCursor,
+    ) -> io::Result<CustomCursorSurface> {
+        let (file, buffers) = create_buffers(shm, &cursor)?;
+        let surface = compositor
+            .create_surface(NewProxy::implement_dummy)
+            .map_err(|_| compositor_dead())?;
+        let image = &cursor.frames[0].image;
+        surface.attach(Some(&buffers[0]), 0, 0);
+        surface.damage(0, 0, image.width as i32, image.height as i32);
+
+        let animation = Arc::new(Mutex::new(Animation {
+            cursor: cursor.clone(),
+            surface: surface.clone(),
+            buffers,
+            current: 0,
+            shown_at: Instant::now(),
+        }));
+        if cursor.is_animated() {
+            request_frame(&surface, Arc::downgrade(&animation));
+        }
+        surface.commit();
+
+        Ok(CustomCursorSurface {
+            cursor,
+            surface,
+            animation,
+            _file: file,
+        })
+    }
+
+    /// Returns the hotspot of the frame currently shown.
+    pub fn hotspot(&self) -> (i32, i32) {
+        let animation = self.animation.lock().unwrap();
+        let image = &animation.cursor.frames[animation.current].image;
+        (image.hotspot_x as i32, image.hotspot_y as i32)
+    }
+}
+
+impl Drop for CustomCursorSurface {
+    fn drop(&mut self) {
+        self.surface.destroy();
+        for buffer in self.animation.lock().unwrap().buffers.drain(..) {
+            buffer.destroy();
+        }
+    }
+}
+
+impl Animation {
+    // Moves on to the frame that should be shown now, if it isn't the current one already.
+    fn advance(&mut self) {
+        let now = Instant::now();
+        let previous = self.current;
+        let frames = &self.cursor.frames;
+        // Frames with a zero duration would loop forever, so at most one loop is skipped.
+        for _ in 0..frames.len() {
+            let deadline = self.shown_at + frames[self.current].duration;
+            if deadline > now {
+                break;
+            }
+            self.current = (self.current + 1) % frames.len();
+            self.shown_at = deadline;
+        }
+        if self.current == previous {
+            return;
+        }
+        // Catching up after the cursor was hidden for a while.
+        if now > self.shown_at + frames[self.current].duration {
+            self.shown_at = now;
+        }
+
+        // The offset of the attached buffer moves the hotspot, which differs between frames.
+        let old = &frames[previous].image;
+        let new = &frames[self.current].image;
+        self.surface.attach(
+            Some(&self.buffers[self.current]),
+            old.hotspot_x as i32 - new.hotspot_x as i32,
+            old.hotspot_y as i32 - new.hotspot_y as i32,
+        );
+        self.surface
+            .damage(0, 0, new.width as i32, new.height as i32);
+    }
+}
+
+// Asks the compositor for the next frame of the animation, which is committed along with the
+// surface.
+fn request_frame(surface: &WlSurface, weak: Weak<Mutex<Animation>>) {
+    let _ = surface.frame(move |callback| {
+        callback.implement_closure_threadsafe(
+            move |event, _| {
+                if let wl_callback::Event::Done { .. } = event {
+                    if let Some(animation) = weak.upgrade() {
+                        let mut animation = animation.lock().unwrap();
+                        animation.advance();
+                        request_frame(&animation.surface, weak.clone());
+                        animation.surface.commit();
+                    }
+                }
+            },
+            (),
+        )
+    });
+}
+
+fn create_buffers(shm: &wl_shm::WlShm, cursor: &CustomCursor) -> io::Result<(File, Vec<WlBuffer>)> {
+    let mut file = create_shm_file()?;
+    let mut offsets = Vec::with_capacity(cursor.frames.len());
+    let mut len = 0;
+    for frame in cursor.frames.iter() {
+        let bytes: Vec<u8> = frame
+            .image
+            .argb_pixels()
+            .into_iter()
+            .flat_map(|pixel| pixel.to_ne_bytes().to_vec())
+            .collect();
+        file.write_all(&bytes)?;
+        offsets.push(len);
+        len += bytes.len();
+    }
+    file.flush()?;
+
+    let pool = shm
+        .create_pool(file.as_raw_fd(), len as i32, NewProxy::implement_dummy)
+        .map_err(|_| compositor_dead())?;
+    let buffers: Result<Vec<_>, _> = cursor
+        .frames
+        .iter()
+        .zip(offsets)
+        .map(|(frame, offset)| {
+            let image = &frame.image;
+            pool.create_buffer(
+                offset as i32,
+                image.width as i32,
+                image.height as i32,
+                image.width as i32 * 4,
+                wl_shm::Format::Argb8888,
+                NewProxy::implement_dummy,
+            )
+        })
+        .collect();
+    // The buffers keep the memory of the pool alive.
+    pool.destroy();
+    Ok((file, buffers.map_err(|_| compositor_dead())?))
+}
+
+// Creates an anonymous file to share memory with the compositor.
+fn create_shm_file() -> io::Result<File> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = env::var_os("XDG_RUNTIME_DIR")
+        .map(Into::into)
+        .unwrap_or_else(env::temp_dir);
+    loop {
+        let path = dir.join(format!(
+            "winit-cursor-{}-{}",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => {
+                let _ = fs::remove_file(&path);
+                return Ok(file);
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// Creating a proxy only fails once the connection to the compositor is lost.
+fn compositor_dead() -> io::Error {
+    io::ErrorKind::BrokenPipe.into()
+}
@@ -0,0 +1,388 @@
+use std::{fs::File, os::unix::io::FromRawFd, sync::Arc, thread};
+
+use smithay_client_toolkit::reexports::client::{
+    protocol::{
+        wl_data_device_manager::DndAction, wl_data_offer::WlDataOffer, wl_data_source,
+        wl_surface::WlSurface,
+    },
+    Display,
+};
+use smithay_client_toolkit::surface;
+
+use crate::{
+    dpi::{LogicalPosition, PhysicalPosition},
+    event::{DragAction, WindowEvent},
+    platform_impl::platform::dnd::{default_response, parse_uri_list, DragResponse, URI_LIST},
+    window::ClipboardContents,
+};
+
+use super::{
+    clipboard::{self, Offer},
+    event_loop::EventsSink,
+    make_wid, WindowId,
+};
+
+/// A drag and drop operation over one of our windows.
+struct Drag {
+    offer: Offer,
+    surface: WlSurface,
+    window: WindowId,
+    serial: u32,
+    position: PhysicalPosition<f64>,
+    action: DragAction,
+    response: DragResponse,
+}
+
+impl Drag {
+    /// Tells the source of the drag whether we accept it.
+    fn send_response(&self) {
+        let offer = &self.offer.offer;
+        match self.response {
+            Some((ref mime_type, action)) => {
+                offer.accept(self.serial, Some(mime_type.clone()));
+                // Since version 3, drops are cancelled unless the destination picks an action.
+                if offer.as_ref().version() >= 3 {
+                    offer.set_actions(
+                        (DndAction::Copy | DndAction::Move).to_raw(),
+                        dnd_action(action).to_raw(),
+                    );
+                }
+            }
+            None => {
+                offer.accept(self.serial, None);
+                if offer.as_ref().version() >= 3 {
+                    offer.set_actions(DndAction::None.to_raw(), DndAction::None.to_raw());
+                }
+            }
+        }
+    }
+}
+
+/// Receives the drags over our windows, which are reported with `DragEntered`, `DragMoved`, and
+/// either `DragDropped` or `DragLeft`.
+///
+/// The data is only read once dropped, from a thread of its own since the source may take a
+/// while to send it. Files are reported with `DroppedFile` then, but not hovered, as that would
+/// mean reading them as soon as the drag enters.
+pub struct Dnd {
+    sink: EventsSink,
+    display: Arc<Display>,
+    drag: Option<Drag>,
+}
+
+impl Dnd {
+    pub fn new(sink: EventsSink, display: Arc<Display>) -> Dnd {
+        Dnd {
+            sink,
+            display,
+            drag: None,
+        }
+    }
+
+    /// Returns the offer of the current drag, if it is `offer`.
+    pub fn offer_mut(&mut self, offer: &WlDataOffer) -> Option<&mut Offer> {
+        self.drag
+            .as_mut()
+            .map(|drag| &mut drag.offer)
+            .filter(|o| o.offer.as_ref().equals(offer.as_ref()))
+    }
+
+    /// Handles a drag entering one of our surfaces. The offer is `None` for drags within another
+    /// client, which never hold any data for us.
+    pub fn enter(
+        &mut self,
+        serial: u32,
+        surface: &WlSurface,
+        x: f64,
+        y: f64,
+        offer: Option<Offer>,
+    ) {
+        if let Some(old) = self.drag.take() {
+            old.offer.offer.destroy();
+        }
+        let offer = match offer {
+            Some(offer) => offer,
+            None => return,
+        };
+
+        let window = make_wid(surface);
+        let position = physical_position(surface, x, y);
+        // Sources only list the actions they support, so propose a copy whenever possible.
+        let action = if offer.offer.as_ref().version() < 3
+            || offer.source_actions.contains(DndAction::Copy)
+        {
+            DragAction::Copy
+        } else {
+            DragAction::Move
+        };
+        let drag = Drag {
+            response: default_response(&offer.mime_types),
+            offer,
+            surface: surface.clone(),
+            window,
+            serial,
+            position,
+            action,
+        };
+        drag.send_response();
+
+        self.sink.send_window_event(
+            WindowEvent::DragEntered {
+                mime_types: drag.offer.mime_types.clone(),
+                position,
+                action,
+            },
+            window,
+        );
+        self.drag = Some(drag);
+    }
+
+    pub fn motion(&mut self, x: f64, y: f64) {
+        if let Some(ref mut drag) = self.drag {
+            drag.position = physical_position(&drag.surface, x, y);
+            self.sink.send_window_event(
+                WindowEvent::DragMoved {
+                    position: drag.position,
+                    action: drag.action,
+                },
+                drag.window,
+            );
+        }
+    }
+
+    /// Handles the compositor picking another action for the drag, usually because the user
+    /// pressed a modifier.
+    pub fn action(&mut self, offer: &WlDataOffer, dnd_action: DndAction) {
+        let action = if dnd_action.contains(DndAction::Move) {
+            DragAction::Move
+        } else if dnd_action.contains(DndAction::Copy) {
+            DragAction::Copy
+        } else {
+            return;
+        };
+        if let Some(ref mut drag) = self.drag {
+            if drag.offer.offer.as_ref().equals(offer.as_ref()) && drag.action != action {
+                drag.action = action;
+                self.sink.send_window_event(
+                    WindowEvent::DragMoved {
+                        position: drag.position,
+                        action,
+                    },
+                    drag.window,
+                );
+            }
+        }
+    }
+
+    /// Records the answer of a window to the drag over it.
+    pub fn respond(&mut self, window: WindowId, response: DragResponse) {
+        if let Some(ref mut drag) = self.drag {
+            if drag.window == window {
+                drag.response = response;
+                drag.send_response();
+            }
+        }
+    }
+
+    pub fn leave(&mut self) {
+        if let Some(drag) = self.drag.take() {
+            self.sink
+                .send_window_event(WindowEvent::DragLeft, drag.window);
+            drag.offer.offer.destroy();
+        }
+    }
+
+    pub fn drop(&mut self, own: Option<&ClipboardContents>) {
+        let drag = match self.drag.take() {
+            Some(drag) => drag,
+            None => return,
+        };
+        let (mime_type, action) = match drag.response {
+            Some(ref response) => response.clone(),
+            None => {
+                self.sink
+                    .send_window_event(WindowEvent::DragLeft, drag.window);
+                drag.offer.offer.destroy();
+                return;
+            }
+        };
+        let dropped = Dropped {
+            offer: drag.offer.offer,
+            window: drag.window,
+            position: drag.position,
+            mime_type,
+            action,
+        };
+
+        // The contents of a drag started by one of our windows are read directly, since the
+        // source would have to be answered by the event loop.
+        if let Some(contents) = own {
+            let data = contents.get(&dropped.mime_type).map(|data| data.to_vec());
+            let uri_list = contents.get(URI_LIST).map(|data| data.to_vec());
+            dropped.finish(&self.sink, &self.display, data, uri_list);
+            return;
+        }
+
+        let pipe = clipboard::receive_pipe(&dropped.offer, dropped.mime_type.clone()).ok();
+        // The files are reported along with the data in any other MIME type.
+        let uri_list_pipe = if dropped.mime_type != URI_LIST
+            && drag.offer.mime_types.iter().any(|m| m == URI_LIST)
+        {
+            clipboard::receive_pipe(&dropped.offer, URI_LIST.into()).ok()
+        } else {
+            None
+        };
+        let _ = self.display.flush();
+
+        let sink = self.sink.clone();
+        let display = self.display.clone();
+        thread::spawn(move || {
+            let data = pipe.and_then(|pipe| clipboard::read_pipe(pipe).ok());
+            let uri_list = match uri_list_pipe {
+                Some(pipe) => clipboard::read_pipe(pipe).ok(),
+                None if dropped.mime_type == URI_LIST => data.clone(),
+                None => None,
+            };
+            dropped.finish(&sink, &display, data, uri_list);
+        });
+    }
+}
+
+/// A drag dropped on one of our windows, whose data is being read.
+struct Dropped {
+    offer: WlDataOffer,
+    window: WindowId,
+    position: PhysicalPosition<f64>,
+    mime_type: String,
+    action: DragAction,
+}
+
+impl Dropped {
+    /// Reports the drop once its data was read, or the drag leaving if it couldn't be.
+    fn finish(
+        self,
+        sink: &EventsSink,
+        display: &Display,
+        data: Option<Vec<u8>>,
+        uri_list: Option<Vec<u8>>,
+    ) {
+        match data {
+            Some(data) => {
+                let paths = uri_list
+                    .and_then(|uri_list| parse_uri_list(&uri_list).ok())
+                    .unwrap_or_default();
+                for path in paths {
+                    sink.send_window_event(WindowEvent::DroppedFile(path), self.window);
+                }
+                sink.send_window_event(
+                    WindowEvent::DragDropped {
+                        contents: ClipboardContents::new().with_data(self.mime_type, data),
+                        position: self.position,
+                        action: self.action,
+                    },
+                    self.window,
+                );
+                if self.offer.as_ref().version() >= 3 {
+                    self.offer.finish();
+                }
+            }
+            None => sink.send_window_event(WindowEvent::DragLeft, self.window),
+        }
+        self.offer.destroy();
+        let _ = display.flush();
+    }
+}
+
+/// Follows a drag started by one of our windows, reporting the action the target under it would
+/// perform and how it ends.
+pub struct DragSource {
+    sink: EventsSink,
+    window: WindowId,
+    contents: ClipboardContents,
+    // Whether the target under the drag accepts one of the MIME types
+    target_accepts: bool,
+    // The action picked by the compositor, only reported since version 3
+    action: DndAction,
+    reported: Option<DragAction>,
+}
+
+impl DragSource {
+    pub fn new(sink: EventsSink, window: WindowId, contents: ClipboardContents) -> DragSource {
+        DragSource {
+            sink,
+            window,
+            contents,
+            target_accepts: false,
+            action: DndAction::empty(),
+            reported: None,
+        }
+    }
+
+    /// Handles an event of the data source, returning whether the drag is over.
+    pub fn handle_event(
+        &mut self,
+        event: wl_data_source::Event,
+        source: &wl_data_source::WlDataSource,
+    ) -> bool {
+        match event {
+            wl_data_source::Event::Send { mime_type, fd } => {
+                let file = unsafe { File::from_raw_fd(fd) };
+                if let Some(data) = self.contents.get(&mime_type) {
+                    clipboard::write_pipe(file, data.to_vec());
+                }
+            }
+            wl_data_source::Event::Target { mime_type } => {
+                self.target_accepts = mime_type.is_some();
+                self.report_action(source);
+            }
+            wl_data_source::Event::Action { dnd_action } => {
+                self.action = DndAction::from_bits_truncate(dnd_action);
+                self.report_action(source);
+            }
+            wl_data_source::Event::DndFinished => {
+                let action = self.reported.unwrap_or(DragAction::Copy);
+                self.sink
+                    .send_window_event(WindowEvent::DragSourceFinished(action), self.window);
+                source.destroy();
+                return true;
+            }
+            wl_data_source::Event::Cancelled => {
+                self.sink
+                    .send_window_event(WindowEvent::DragSourceCancelled, self.window);
+                source.destroy();
+                return true;
+            }
+            _ => (),
+        }
+        false
+    }
+
+    fn report_action(&mut self, source: &wl_data_source::WlDataSource) {
+        let accepted = if !self.target_accepts {
+            None
+        } else if source.as_ref().version() < 3 || self.action.contains(DndAction::Copy) {
+            Some(DragAction::Copy)
+        } else if self.action.contains(DndAction::Move) {
+            Some(DragAction::Move)
+        } else {
+            None
+        };
+        if accepted != self.reported {
+            self.reported = accepted;
+            self.sink
+                .send_window_event(WindowEvent::DragSourceAction(accepted), self.window);
+        }
+    }
+}
+
+fn physical_position(surface: &WlSurface, x: f64, y: f64) -> PhysicalPosition<f64> {
+    LogicalPosition::new(x, y).to_physical(surface::get_dpi_factor(surface) as f64)
+}
+
+pub fn dnd_action(action: DragAction) -> DndAction {
+    match action {
+        DragAction::Move => DndAction::Move,
+        // Wayland has no link action.
+        DragAction::Copy | DragAction::Link => DndAction::Copy,
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     collections::VecDeque,
     fmt,
     io::ErrorKind,
+    os::unix::io::{AsRawFd, RawFd},
     rc::Rc,
     sync::{Arc, Mutex},
     time::{Duration, Instant},
@@ -15,10 +16,17 @@ use mio_extras::channel::{channel, Receiver, SendError, Sender};
 use smithay_client_toolkit::reexports::protocols::unstable::pointer_constraints::v1::client::{
     zwp_locked_pointer_v1::ZwpLockedPointerV1, zwp_pointer_constraints_v1::ZwpPointerConstraintsV1,
 };
+use smithay_client_toolkit::reexports::protocols::unstable::pointer_gestures::v1::client::{
+    zwp_pointer_gesture_pinch_v1::ZwpPointerGesturePinchV1,
+    zwp_pointer_gesture_swipe_v1::ZwpPointerGestureSwipeV1,
+    zwp_pointer_gestures_v1::ZwpPointerGesturesV1,
+};
 use smithay_client_toolkit::reexports::protocols::unstable::relative_pointer::v1::client::{
     zwp_relative_pointer_manager_v1::ZwpRelativePointerManagerV1,
     zwp_relative_pointer_v1::ZwpRelativePointerV1,
 };
+use smithay_client_toolkit::reexports::protocols::unstable::tablet::v2::client::zwp_tablet_manager_v2::ZwpTabletManagerV2;
+use smithay_client_toolkit::reexports::protocols::unstable::text_input::v3::client::zwp_text_input_manager_v3::ZwpTextInputManagerV3;
 
 use smithay_client_toolkit::pointer::{AutoPointer, AutoThemer};
 use smithay_client_toolkit::reexports::client::protocol::{
@@ -32,14 +40,21 @@ use crate::{
     },
     event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
     monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+    platform::unix::PumpStatus,
     platform_impl::platform::{
+        min_timeout,
+        sources::{first_timer, Sources},
         sticky_exit_callback, DeviceId as PlatformDeviceId, MonitorHandle as PlatformMonitorHandle,
-        VideoMode as PlatformVideoMode, WindowId as PlatformWindowId,
+        PumpState, VideoMode as PlatformVideoMode, WindowId as PlatformWindowId,
     },
-    window::{CursorIcon, WindowId as RootWindowId},
+    window::{CursorIcon, CustomCursor, WindowId as RootWindowId},
 };
 
 use super::{
+    clipboard::Clipboard,
+    cursor::CustomCursorSurface,
+    tablet::Tablet,
+    text_input::TextInput,
     window::{DecorationsAction, WindowStore},
     DeviceId, WindowId,
 };
@@ -92,8 +107,15 @@ pub struct CursorManager {
     pointers: Vec<AutoPointer>,
     locked_pointers: Vec<ZwpLockedPointerV1>,
     cursor_visible: bool,
-    current_cursor: CursorIcon,
+    current_cursor: CurrentCursor,
     scale_factor: u32,
+    compositor: Option<WlCompositor>,
+    shm: Option<WlShm>,
+}
+
+enum CurrentCursor {
+    Icon(CursorIcon),
+    Custom(CustomCursorSurface),
 }
 
 impl CursorManager {
@@ -104,8 +126,10 @@ impl CursorManager {
             pointers: Vec::new(),
             locked_pointers: Vec::new(),
             cursor_visible: true,
-            current_cursor: CursorIcon::default(),
+            current_cursor: CurrentCursor::Icon(CursorIcon::default()),
             scale_factor: 1,
+            compositor: None,
+            shm: None,
         }
     }
 
@@ -117,8 +141,10 @@ impl CursorManager {
         self.pointers.push(auto_themer.theme_pointer(pointer));
     }
 
-    fn set_auto_themer(&mut self, auto_themer: AutoThemer) {
+    fn set_globals(&mut self, auto_themer: AutoThemer, compositor: WlCompositor, shm: WlShm) {
         self.auto_themer = Some(auto_themer);
+        self.compositor = Some(compositor);
+        self.shm = Some(shm);
     }
 
     pub fn set_cursor_visible(&mut self, visible: bool) {
@@ -127,7 +153,7 @@ impl CursorManager {
                 (**pointer).set_cursor(0, None, 0, 0);
             }
         } else {
-            self.set_cursor_icon_impl(self.current_cursor);
+            self.apply_current_cursor();
         }
         self.cursor_visible = visible;
     }
@@ -137,15 +163,49 @@ impl CursorManager {
         if !self.cursor_visible {
             self.set_cursor_visible(false);
         } else {
-            self.set_cursor_icon_impl(self.current_cursor);
+            self.apply_current_cursor();
         }
     }
 
     pub fn set_cursor_icon(&mut self, cursor: CursorIcon) {
-        if cursor != self.current_cursor {
-            self.current_cursor = cursor;
-            if self.cursor_visible {
-                self.set_cursor_icon_impl(cursor);
+        match self.current_cursor {
+            CurrentCursor::Icon(current) if current == cursor => return,
+            _ => (),
+        }
+        self.current_cursor = CurrentCursor::Icon(cursor);
+        if self.cursor_visible {
+            self.set_cursor_icon_impl(cursor);
+        }
+    }
+
+    pub fn set_custom_cursor(&mut self, cursor: CustomCursor) {
+        match self.current_cursor {
+            CurrentCursor::Custom(ref current) if current.cursor == cursor => return,
+            _ => (),
+        }
+        let surface = match (&self.compositor, &self.shm) {
+            (Some(compositor), Some(shm)) => CustomCursorSurface::new(compositor, shm, cursor),
+            _ => return,
+        };
+        match surface {
+            Ok(surface) => {
+                self.current_cursor = CurrentCursor::Custom(surface);
+                if self.cursor_visible {
+                    self.apply_current_cursor();
+                }
+            }
+            Err(err) => warn!("Failed to create a custom cursor: {}", err),
+        }
+    }
+
+    fn apply_current_cursor(&mut self) {
+        match self.current_cursor {
+            CurrentCursor::Icon(cursor) => self.set_cursor_icon_impl(cursor),
+            CurrentCursor::Custom(ref custom) => {
+                let (hotspot_x, hotspot_y) = custom.hotspot();
+                for pointer in self.pointers.iter() {
+                    (**pointer).set_cursor(0, Some(&custom.surface), hotspot_x, hotspot_y);
+                }
             }
         }
     }
@@ -242,17 +302,22 @@ impl CursorManager {
 
 pub struct EventLoop<T: 'static> {
     // Poll instance
-    poll: Poll,
+    poll: Rc<Poll>,
     // The wayland display
     pub display: Arc<Display>,
     // The output manager
     pub outputs: OutputMgr,
+    // The configuration of the outputs, as of the last dispatch
+    known_outputs: Vec<OutputConfiguration>,
     // The cursor manager
     cursor_manager: Arc<Mutex<CursorManager>>,
     kbd_channel: Receiver<Event<'static, ()>>,
     user_channel: Receiver<T>,
     user_sender: Sender<T>,
     window_target: RootELW<T>,
+    pump_state: PumpState,
+    // The events of the user's sources found ready by the last wait
+    ready_sources: Vec<Event<'static, ()>>,
 }
 
 // A handle that can be sent across threads and used to wake up the `EventLoop`.
@@ -277,6 +342,12 @@ pub struct EventLoopWindowTarget<T> {
     pub display: Arc<Display>,
     // The list of seats
     pub seats: Arc<Mutex<Vec<(u32, wl_seat::WlSeat)>>>,
+    // The clipboard, shared by all seats
+    pub clipboard: Arc<Mutex<Clipboard>>,
+    // The IME state, shared by all seats
+    pub text_input: Arc<Mutex<TextInput>>,
+    // The file descriptors and timers registered by the user
+    pub(crate) sources: RefCell<Sources>,
     _marker: ::std::marker::PhantomData<T>,
 }
 
@@ -308,7 +379,7 @@ impl<T: 'static> EventLoop<T> {
         let store = Arc::new(Mutex::new(WindowStore::new()));
         let seats = Arc::new(Mutex::new(Vec::new()));
 
-        let poll = Poll::new().unwrap();
+        let poll = Rc::new(Poll::new().unwrap());
 
         let (kbd_sender, kbd_channel) = channel();
 
@@ -319,11 +390,18 @@ impl<T: 'static> EventLoop<T> {
 
         let pointer_constraints_proxy = Arc::new(Mutex::new(None));
 
+        let clipboard = Arc::new(Mutex::new(Clipboard::new(sink.clone(), display.clone())));
+        let text_input = Arc::new(Mutex::new(TextInput::new(sink.clone())));
+
         let mut seat_manager = SeatManager {
+            tablet: Arc::new(Mutex::new(Tablet::new(sink.clone()))),
             sink,
+            clipboard: clipboard.clone(),
+            text_input: text_input.clone(),
             store: store.clone(),
             seats: seats.clone(),
             relative_pointer_manager_proxy: Rc::new(RefCell::new(None)),
+            pointer_gestures_proxy: Rc::new(RefCell::new(None)),
             pointer_constraints_proxy: pointer_constraints_proxy.clone(),
             cursor_manager: Arc::new(Mutex::new(CursorManager::new(pointer_constraints_proxy))),
         };
@@ -355,6 +433,18 @@ impl<T: 'static> EventLoop<T> {
                             .try_borrow_mut()
                             .unwrap() = Some(relative_pointer_manager_proxy);
                     }
+                    if interface == "zwp_pointer_gestures_v1" {
+                        let pointer_gestures_proxy = registry
+                            .bind(version, id, move |pointer_gestures| {
+                                pointer_gestures.implement_closure(|_, _| (), ())
+                            })
+                            .unwrap();
+
+                        *seat_manager
+                            .pointer_gestures_proxy
+                            .try_borrow_mut()
+                            .unwrap() = Some(pointer_gestures_proxy);
+                    }
                     if interface == "zwp_pointer_constraints_v1" {
                         let pointer_constraints_proxy = registry
                             .bind(version, id, move |pointer_constraints| {
@@ -365,6 +455,32 @@ impl<T: 'static> EventLoop<T> {
                         *seat_manager.pointer_constraints_proxy.lock().unwrap() =
                             Some(pointer_constraints_proxy);
                     }
+                    if interface == "zwp_text_input_manager_v3" {
+                        let text_input_manager: ZwpTextInputManagerV3 = registry
+                            .bind(version, id, move |text_input_manager| {
+                                text_input_manager.implement_closure(|_, _| (), ())
+                            })
+                            .unwrap();
+
+                        TextInput::set_manager(
+                            &seat_manager.text_input,
+                            text_input_manager,
+                            &seat_manager.seats.lock().unwrap(),
+                        );
+                    }
+                    if interface == "zwp_tablet_manager_v2" {
+                        let tablet_manager: ZwpTabletManagerV2 = registry
+                            .bind(version, id, move |tablet_manager| {
+                                tablet_manager.implement_closure(|_, _| (), ())
+                            })
+                            .unwrap();
+
+                        Tablet::set_manager(
+                            &seat_manager.tablet,
+                            tablet_manager,
+                            &seat_manager.seats.lock().unwrap(),
+                        );
+                    }
                     if interface == "wl_shm" {
                         let shm: WlShm = registry
                             .bind(version, id, move |shm| shm.implement_closure(|_, _| (), ()))
@@ -384,11 +500,12 @@ impl<T: 'static> EventLoop<T> {
                     if compositor_cell.borrow().is_some() && shm_cell.borrow().is_some() {
                         let compositor = compositor_cell.borrow_mut().take().unwrap();
                         let shm = shm_cell.borrow_mut().take().unwrap();
-                        let auto_themer = AutoThemer::init(None, compositor, &shm);
-                        cursor_manager_clone
-                            .lock()
-                            .unwrap()
-                            .set_auto_themer(auto_themer);
+                        let auto_themer = AutoThemer::init(None, compositor.clone(), &shm);
+                        cursor_manager_clone.lock().unwrap().set_globals(
+                            auto_themer,
+                            compositor,
+                            shm,
+                        );
                     }
 
                     if interface == "wl_seat" {
@@ -404,6 +521,12 @@ impl<T: 'static> EventLoop<T> {
         )
         .unwrap();
 
+        Clipboard::set_manager(
+            &clipboard,
+            env.data_device_manager.clone(),
+            &seats.lock().unwrap(),
+        );
+
         poll.register(&event_queue, EVQ_TOKEN, Ready::readable(), PollOpt::level())
             .unwrap();
 
@@ -418,14 +541,18 @@ impl<T: 'static> EventLoop<T> {
         .unwrap();
 
         let cursor_manager_clone = cursor_manager.clone();
+        let sources = RefCell::new(Sources::new(Rc::clone(&poll)));
         Ok(EventLoop {
             poll,
             display: display.clone(),
             outputs: env.outputs.clone(),
+            known_outputs: output_configurations(&env.outputs),
             user_sender,
             user_channel,
             kbd_channel,
             cursor_manager,
+            pump_state: PumpState::NotStarted,
+            ready_sources: Vec::new(),
             window_target: RootELW {
                 p: crate::platform_impl::EventLoopWindowTarget::Wayland(EventLoopWindowTarget {
                     evq: RefCell::new(event_queue),
@@ -434,7 +561,10 @@ impl<T: 'static> EventLoop<T> {
                     cursor_manager: cursor_manager_clone,
                     cleanup_needed: Arc::new(Mutex::new(false)),
                     seats,
+                    clipboard,
+                    text_input,
                     display,
+                    sources,
                     _marker: ::std::marker::PhantomData,
                 }),
                 _marker: ::std::marker::PhantomData,
@@ -464,172 +594,240 @@ impl<T: 'static> EventLoop<T> {
         self.display.flush().expect("Wayland connection lost.");
 
         let mut control_flow = ControlFlow::default();
-        let mut events = Events::with_capacity(8);
-
-        callback(
-            Event::NewEvents(StartCause::Init),
-            &self.window_target,
-            &mut control_flow,
-        );
+        let mut cause = StartCause::Init;
 
         loop {
-            // Read events from the event queue
-            {
-                let mut evq = get_target(&self.window_target).evq.borrow_mut();
+            self.dispatch_iteration(cause, &mut callback, &mut control_flow);
 
-                evq.dispatch_pending()
-                    .expect("failed to dispatch wayland events");
+            if control_flow == ControlFlow::Exit {
+                break;
+            }
+            cause = self.wait_events(control_flow, None);
+        }
 
-                if let Some(read) = evq.prepare_read() {
-                    if let Err(e) = read.read_events() {
-                        if e.kind() != ErrorKind::WouldBlock {
-                            panic!("failed to read wayland events: {}", e);
-                        }
-                    }
+        callback(Event::LoopDestroyed, &self.window_target, &mut control_flow);
+    }
 
-                    evq.dispatch_pending()
-                        .expect("failed to dispatch wayland events");
-                }
+    pub fn pump_events<F>(&mut self, timeout: Option<Duration>, mut callback: F) -> PumpStatus
+    where
+        F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        let (mut cause, mut control_flow) = match self.pump_state {
+            PumpState::NotStarted => {
+                // send pending events to the server
+                self.display.flush().expect("Wayland connection lost.");
+                (StartCause::Init, ControlFlow::default())
+            }
+            PumpState::Running(control_flow) => {
+                (self.wait_events(control_flow, timeout), control_flow)
             }
+            PumpState::Exited => return PumpStatus::Exit,
+        };
 
-            self.post_dispatch_triggers(&mut callback, &mut control_flow);
+        loop {
+            self.dispatch_iteration(cause, &mut callback, &mut control_flow);
 
-            while let Ok(event) = self.kbd_channel.try_recv() {
-                let event = event.map_nonuser_event().unwrap();
-                sticky_exit_callback(event, &self.window_target, &mut control_flow, &mut callback);
+            // Other code reading the socket while the callback ran, such as mesa, may have queued
+            // events, which leaves the file descriptor unreadable, so they're dispatched right
+            // away.
+            if control_flow == ControlFlow::Exit || !self.dispatch_queued_events() {
+                break;
             }
+            cause = self.wait_events(control_flow, Some(Duration::from_millis(0)));
+        }
 
-            while let Ok(event) = self.user_channel.try_recv() {
-                sticky_exit_callback(
-                    Event::UserEvent(event),
-                    &self.window_target,
-                    &mut control_flow,
-                    &mut callback,
-                );
+        if control_flow == ControlFlow::Exit {
+            callback(Event::LoopDestroyed, &self.window_target, &mut control_flow);
+            self.pump_state = PumpState::Exited;
+            PumpStatus::Exit
+        } else {
+            // The requests made by the callback have to reach the server before the caller
+            // waits on the file descriptor.
+            self.display.flush().expect("Wayland connection lost.");
+            self.pump_state = PumpState::Running(control_flow);
+            if control_flow == ControlFlow::Poll || self.redraws_pending() {
+                PumpStatus::Pending
+            } else {
+                PumpStatus::Continue
             }
+        }
+    }
 
-            // send Events cleared
-            {
-                sticky_exit_callback(
-                    Event::MainEventsCleared,
-                    &self.window_target,
-                    &mut control_flow,
-                    &mut callback,
-                );
-            }
+    // Dispatches the events already in the event queue, returning whether there were any.
+    fn dispatch_queued_events(&self) -> bool {
+        let dispatched = get_target(&self.window_target)
+            .evq
+            .borrow_mut()
+            .dispatch_pending()
+            .expect("Wayland connection lost.");
+        dispatched > 0
+    }
 
-            // handle request-redraw
-            {
-                self.redraw_triggers(|wid, window_target| {
-                    sticky_exit_callback(
-                        Event::RedrawRequested(crate::window::WindowId(
-                            crate::platform_impl::WindowId::Wayland(wid),
-                        )),
-                        window_target,
-                        &mut control_flow,
-                        &mut callback,
-                    );
-                });
-            }
+    // Whether redraws were requested too late to be served by the last iteration.
+    fn redraws_pending(&self) -> bool {
+        get_target(&self.window_target)
+            .store
+            .lock()
+            .unwrap()
+            .redraws_pending()
+    }
 
-            // send RedrawEventsCleared
-            {
-                sticky_exit_callback(
-                    Event::RedrawEventsCleared,
-                    &self.window_target,
-                    &mut control_flow,
-                    &mut callback,
-                );
-            }
+    #[inline]
+    pub fn as_raw_fd(&self) -> RawFd {
+        self.poll.as_raw_fd()
+    }
 
-            // send pending events to the server
-            self.display.flush().expect("Wayland connection lost.");
+    // Dispatches one iteration of the loop, from `NewEvents` to `RedrawEventsCleared`.
+    fn dispatch_iteration<F>(
+        &mut self,
+        cause: StartCause,
+        callback: &mut F,
+        control_flow: &mut ControlFlow,
+    ) where
+        F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        callback(Event::NewEvents(cause), &self.window_target, control_flow);
 
-            // During the run of the user callback, some other code monitoring and reading the
-            // wayland socket may have been run (mesa for example does this with vsync), if that
-            // is the case, some events may have been enqueued in our event queue.
-            //
-            // If some messages are there, the event loop needs to behave as if it was instantly
-            // woken up by messages arriving from the wayland socket, to avoid getting stuck.
-            let instant_wakeup = {
-                let window_target = match self.window_target.p {
-                    crate::platform_impl::EventLoopWindowTarget::Wayland(ref wt) => wt,
-                    _ => unreachable!(),
-                };
-                let dispatched = window_target
-                    .evq
-                    .borrow_mut()
-                    .dispatch_pending()
-                    .expect("Wayland connection lost.");
-                dispatched > 0
-            };
+        for event in self.ready_sources.drain(..) {
+            let event = event.map_nonuser_event().unwrap();
+            sticky_exit_callback(event, &self.window_target, control_flow, callback);
+        }
 
-            match control_flow {
-                ControlFlow::Exit => break,
-                ControlFlow::Poll => {
-                    // non-blocking dispatch
-                    self.poll
-                        .poll(&mut events, Some(Duration::from_millis(0)))
-                        .unwrap();
-                    events.clear();
-
-                    callback(
-                        Event::NewEvents(StartCause::Poll),
-                        &self.window_target,
-                        &mut control_flow,
-                    );
-                }
-                ControlFlow::Wait => {
-                    if !instant_wakeup {
-                        self.poll.poll(&mut events, None).unwrap();
-                        events.clear();
-                    }
+        // Read events from the event queue
+        {
+            let mut evq = get_target(&self.window_target).evq.borrow_mut();
 
-                    callback(
-                        Event::NewEvents(StartCause::WaitCancelled {
-                            start: Instant::now(),
-                            requested_resume: None,
-                        }),
-                        &self.window_target,
-                        &mut control_flow,
-                    );
-                }
-                ControlFlow::WaitUntil(deadline) => {
-                    let start = Instant::now();
-                    // compute the blocking duration
-                    let duration = if deadline > start && !instant_wakeup {
-                        deadline - start
-                    } else {
-                        Duration::from_millis(0)
-                    };
-                    self.poll.poll(&mut events, Some(duration)).unwrap();
-                    events.clear();
-
-                    let now = Instant::now();
-                    if now < deadline {
-                        callback(
-                            Event::NewEvents(StartCause::WaitCancelled {
-                                start,
-                                requested_resume: Some(deadline),
-                            }),
-                            &self.window_target,
-                            &mut control_flow,
-                        );
-                    } else {
-                        callback(
-                            Event::NewEvents(StartCause::ResumeTimeReached {
-                                start,
-                                requested_resume: deadline,
-                            }),
-                            &self.window_target,
-                            &mut control_flow,
-                        );
+            evq.dispatch_pending()
+                .expect("failed to dispatch wayland events");
+
+            if let Some(read) = evq.prepare_read() {
+                if let Err(e) = read.read_events() {
+                    if e.kind() != ErrorKind::WouldBlock {
+                        panic!("failed to read wayland events: {}", e);
                     }
                 }
+
+                evq.dispatch_pending()
+                    .expect("failed to dispatch wayland events");
             }
         }
 
-        callback(Event::LoopDestroyed, &self.window_target, &mut control_flow);
+        self.post_dispatch_triggers(&mut *callback, control_flow);
+
+        while let Ok(event) = self.kbd_channel.try_recv() {
+            let event = event.map_nonuser_event().unwrap();
+            sticky_exit_callback(event, &self.window_target, control_flow, callback);
+        }
+
+        while let Ok(event) = self.user_channel.try_recv() {
+            sticky_exit_callback(
+                Event::UserEvent(event),
+                &self.window_target,
+                control_flow,
+                callback,
+            );
+        }
+
+        // send Events cleared
+        {
+            sticky_exit_callback(
+                Event::MainEventsCleared,
+                &self.window_target,
+                control_flow,
+                callback,
+            );
+        }
+
+        // handle request-redraw
+        {
+            self.redraw_triggers(|wid, window_target| {
+                sticky_exit_callback(
+                    Event::RedrawRequested(crate::window::WindowId(
+                        crate::platform_impl::WindowId::Wayland(wid),
+                    )),
+                    window_target,
+                    control_flow,
+                    callback,
+                );
+            });
+        }
+
+        // send RedrawEventsCleared
+        {
+            sticky_exit_callback(
+                Event::RedrawEventsCleared,
+                &self.window_target,
+                control_flow,
+                callback,
+            );
+        }
+    }
+
+    // Waits for events as long as `control_flow` asks for, but no longer than `timeout`, and
+    // returns the cause of the next iteration.
+    fn wait_events(&mut self, control_flow: ControlFlow, timeout: Option<Duration>) -> StartCause {
+        let mut events = Events::with_capacity(8);
+
+        // send pending events to the server
+        self.display.flush().expect("Wayland connection lost.");
+
+        // During the run of the user callback, some other code monitoring and reading the
+        // wayland socket may have been run (mesa for example does this with vsync), if that
+        // is the case, some events may have been enqueued in our event queue.
+        //
+        // If some messages are there, the event loop needs to behave as if it was instantly
+        // woken up by messages arriving from the wayland socket, to avoid getting stuck. The same
+        // goes for redraws which are already due.
+        let instant_wakeup = self.dispatch_queued_events() || self.redraws_pending();
+
+        let start = Instant::now();
+        let (deadline, wait) = match control_flow {
+            ControlFlow::Exit | ControlFlow::Poll => (None, Some(Duration::from_millis(0))),
+            ControlFlow::Wait => (None, None),
+            ControlFlow::WaitUntil(deadline) => (
+                Some(deadline),
+                Some(deadline.saturating_duration_since(start)),
+            ),
+        };
+        let wait = if instant_wakeup {
+            Some(Duration::from_millis(0))
+        } else {
+            let window_target = get_target(&self.window_target);
+            let timer = window_target
+                .sources
+                .borrow()
+                .next_deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+            // Wake up to report the windows whose frame callbacks stopped as occluded.
+            let occlusion = window_target
+                .store
+                .lock()
+                .unwrap()
+                .next_occlusion_deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+            min_timeout(min_timeout(min_timeout(wait, timeout), timer), occlusion)
+        };
+        self.poll.poll(&mut events, wait).unwrap();
+        let now = Instant::now();
+        self.ready_sources = get_target(&self.window_target)
+            .sources
+            .borrow_mut()
+            .ready(&events, now);
+
+        match (control_flow, deadline) {
+            (ControlFlow::Poll, _) => StartCause::Poll,
+            (_, Some(deadline)) if now >= deadline => StartCause::ResumeTimeReached {
+                start,
+                requested_resume: deadline,
+            },
+            _ => match first_timer(&self.ready_sources) {
+                Some(id) => StartCause::TimerFired(id),
+                None => StartCause::WaitCancelled {
+                    start,
+                    requested_resume: deadline,
+                },
+            },
+        }
     }
 
     pub fn primary_monitor(&self) -> MonitorHandle {
@@ -681,10 +879,43 @@ impl<T> EventLoop<T> {
         )
     }
 
+    // SCTK's output manager does not report its changes, so they are found by comparing the
+    // configuration of the outputs with the one from the last dispatch.
+    fn monitor_events(&mut self) -> Vec<Event<'static, T>> {
+        let current = output_configurations(&self.outputs);
+        let mut events = Vec::new();
+        let handle = |proxy: &wl_output::WlOutput| RootMonitorHandle {
+            inner: PlatformMonitorHandle::Wayland(MonitorHandle {
+                proxy: proxy.clone(),
+                mgr: self.outputs.clone(),
+            }),
+        };
+
+        for output in &self.known_outputs {
+            if !current.iter().any(|o| o.id == output.id) {
+                events.push(Event::MonitorRemoved(handle(&output.proxy)));
+            }
+        }
+        for output in &current {
+            match self.known_outputs.iter().find(|o| o.id == output.id) {
+                None => events.push(Event::MonitorAdded(handle(&output.proxy))),
+                Some(known) if !known.same_configuration(output) => {
+                    events.push(Event::MonitorChanged(handle(&output.proxy)))
+                }
+                Some(_) => (),
+            }
+        }
+
+        self.known_outputs = current;
+        events
+    }
+
     fn post_dispatch_triggers<F>(&mut self, mut callback: F, control_flow: &mut ControlFlow)
     where
         F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
     {
+        let monitor_events = self.monitor_events();
+
         let window_target = match self.window_target.p {
             crate::platform_impl::EventLoopWindowTarget::Wayland(ref wt) => wt,
             _ => unreachable!(),
@@ -694,6 +925,11 @@ impl<T> EventLoop<T> {
             sticky_exit_callback(event, &self.window_target, control_flow, &mut callback);
         };
 
+        // report the outputs that were added, removed or reconfigured
+        for event in monitor_events {
+            callback(event);
+        }
+
         // prune possible dead windows
         {
             let mut cleanup_needed = window_target.cleanup_needed.lock().unwrap();
@@ -701,6 +937,7 @@ impl<T> EventLoop<T> {
                 let pruned = window_target.store.lock().unwrap().cleanup();
                 *cleanup_needed = false;
                 for wid in pruned {
+                    window_target.text_input.lock().unwrap().remove_window(wid);
                     callback(Event::WindowEvent {
                         window_id: crate::window::WindowId(
                             crate::platform_impl::WindowId::Wayland(wid),
@@ -778,6 +1015,29 @@ impl<T> EventLoop<T> {
                 }
             }
 
+            if let Some(window_state) = window.new_window_state {
+                callback(Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::StateChanged(window_state),
+                });
+            }
+
+            if let Some(occluded) = window.occluded {
+                callback(Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::Occluded(occluded),
+                });
+            }
+
+            if let Some(time) = window.frame_callback {
+                callback(Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::FrameCallback {
+                        presentation_time: Duration::from_millis(time.into()),
+                    },
+                });
+            }
+
             if window.closed {
                 callback(Event::WindowEvent {
                     window_id,
@@ -810,9 +1070,13 @@ fn get_target<T>(target: &RootELW<T>) -> &EventLoopWindowTarget<T> {
 
 struct SeatManager {
     sink: EventsSink,
+    clipboard: Arc<Mutex<Clipboard>>,
+    text_input: Arc<Mutex<TextInput>>,
+    tablet: Arc<Mutex<Tablet>>,
     store: Arc<Mutex<WindowStore>>,
     seats: Arc<Mutex<Vec<(u32, wl_seat::WlSeat)>>>,
     relative_pointer_manager_proxy: Rc<RefCell<Option<ZwpRelativePointerManagerV1>>>,
+    pointer_gestures_proxy: Rc<RefCell<Option<ZwpPointerGesturesV1>>>,
     pointer_constraints_proxy: Arc<Mutex<Option<ZwpPointerConstraintsV1>>>,
     cursor_manager: Arc<Mutex<CursorManager>>,
 }
@@ -827,10 +1091,13 @@ impl SeatManager {
             pointer: None,
             relative_pointer: None,
             relative_pointer_manager_proxy: self.relative_pointer_manager_proxy.clone(),
+            pointer_gestures: None,
+            pointer_gestures_proxy: self.pointer_gestures_proxy.clone(),
             keyboard: None,
             touch: None,
             modifiers_tracker: Arc::new(Mutex::new(ModifiersState::default())),
             cursor_manager: self.cursor_manager.clone(),
+            clipboard: self.clipboard.clone(),
         };
         let seat = registry
             .bind(min(version, 5), id, move |seat| {
@@ -838,10 +1105,16 @@ impl SeatManager {
             })
             .unwrap();
         self.store.lock().unwrap().new_seat(&seat);
+        Clipboard::new_seat(&self.clipboard, id, &seat);
+        TextInput::new_seat(&self.text_input, id, &seat);
+        Tablet::new_seat(&self.tablet, id, &seat);
         self.seats.lock().unwrap().push((id, seat));
     }
 
     fn remove_seat(&mut self, id: u32) {
+        self.clipboard.lock().unwrap().remove_seat(id);
+        self.text_input.lock().unwrap().remove_seat(id);
+        self.tablet.lock().unwrap().remove_seat(id);
         let mut seats = self.seats.lock().unwrap();
         if let Some(idx) = seats.iter().position(|&(i, _)| i == id) {
             let (_, seat) = seats.swap_remove(idx);
@@ -858,10 +1131,13 @@ struct SeatData {
     pointer: Option<wl_pointer::WlPointer>,
     relative_pointer: Option<ZwpRelativePointerV1>,
     relative_pointer_manager_proxy: Rc<RefCell<Option<ZwpRelativePointerManagerV1>>>,
+    pointer_gestures: Option<(ZwpPointerGestureSwipeV1, ZwpPointerGesturePinchV1)>,
+    pointer_gestures_proxy: Rc<RefCell<Option<ZwpPointerGesturesV1>>>,
     keyboard: Option<wl_keyboard::WlKeyboard>,
     touch: Option<wl_touch::WlTouch>,
     modifiers_tracker: Arc<Mutex<ModifiersState>>,
     cursor_manager: Arc<Mutex<CursorManager>>,
+    clipboard: Arc<Mutex<Clipboard>>,
 }
 
 impl SeatData {
@@ -877,6 +1153,7 @@ impl SeatData {
                         self.store.clone(),
                         self.modifiers_tracker.clone(),
                         self.cursor_manager.clone(),
+                        self.clipboard.clone(),
                     ));
 
                     self.cursor_manager
@@ -896,10 +1173,28 @@ impl SeatData {
                                 manager,
                             )
                             .ok()
-                        })
+                        });
+
+                    self.pointer_gestures = self
+                        .pointer_gestures_proxy
+                        .try_borrow()
+                        .unwrap()
+                        .as_ref()
+                        .and_then(|manager| {
+                            super::pointer::implement_pointer_gestures(
+                                self.sink.clone(),
+                                self.pointer.as_ref().unwrap(),
+                                manager,
+                            )
+                            .ok()
+                        });
                 }
                 // destroy pointer if applicable
                 if !capabilities.contains(wl_seat::Capability::Pointer) {
+                    if let Some((swipe, pinch)) = self.pointer_gestures.take() {
+                        swipe.destroy();
+                        pinch.destroy();
+                    }
                     if let Some(pointer) = self.pointer.take() {
                         if pointer.as_ref().version() >= 3 {
                             pointer.release();
@@ -912,6 +1207,7 @@ impl SeatData {
                         &seat,
                         self.sink.clone(),
                         self.modifiers_tracker.clone(),
+                        self.clipboard.clone(),
                     ))
                 }
                 // destroy keyboard if applicable
@@ -1094,6 +1390,12 @@ impl MonitorHandle {
             .unwrap_or(1)
     }
 
+    // `xdg_toplevel.configure_bounds` is newer than the version of xdg-shell we speak.
+    #[inline]
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        (self.position(), self.size())
+    }
+
     #[inline]
     pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
         let monitor = self.clone();
@@ -1113,6 +1415,46 @@ impl MonitorHandle {
     }
 }
 
+// The state of an output used to tell whether it was reconfigured.
+struct OutputConfiguration {
+    id: u32,
+    proxy: wl_output::WlOutput,
+    location: (i32, i32),
+    transform: wl_output::Transform,
+    scale_factor: i32,
+    modes: Vec<((i32, i32), i32, bool)>,
+}
+
+impl OutputConfiguration {
+    fn same_configuration(&self, other: &OutputConfiguration) -> bool {
+        self.location == other.location
+            && self.transform == other.transform
+            && self.scale_factor == other.scale_factor
+            && self.modes == other.modes
+    }
+}
+
+fn output_configurations(outputs: &OutputMgr) -> Vec<OutputConfiguration> {
+    outputs.with_all(|list| {
+        list.iter()
+            // An output has no modes until the compositor sent its first configuration.
+            .filter(|(_, _, info)| !info.modes.is_empty())
+            .map(|(id, proxy, info)| OutputConfiguration {
+                id: *id,
+                proxy: proxy.clone(),
+                location: info.location,
+                transform: info.transform,
+                scale_factor: info.scale_factor,
+                modes: info
+                    .modes
+                    .iter()
+                    .map(|m| (m.dimensions, m.refresh_rate, m.is_current))
+                    .collect(),
+            })
+            .collect()
+    })
+}
+
 pub fn primary_monitor(outputs: &OutputMgr) -> MonitorHandle {
     outputs.with_all(|list| {
         if let Some(&(_, ref proxy, _)) = list.first() {
@@ -0,0 +1,131 @@
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use smithay_client_toolkit::reexports::client::protocol::{
+    wl_compositor, wl_seat, wl_shm, wl_subcompositor, wl_surface,
+};
+use smithay_client_toolkit::reexports::protocols::xdg_shell::client::xdg_toplevel::ResizeEdge;
+use smithay_client_toolkit::window::{ConceptFrame, Frame, FrameRequest, Theme};
+
+use crate::window::ResizeDirection;
+
+type RequestHandler = Arc<Mutex<Box<dyn FnMut(FrameRequest, u32) + Send>>>;
+
+thread_local! {
+    // SCTK keeps the shell surface of a window to itself, so the only way to start a move or a
+    // resize is the handler it gives to the decorations. The handler of the frame created last is
+    // left here for `FrameRequests::take_last`.
+    static LAST_HANDLER: RefCell<Option<RequestHandler>> = RefCell::new(None);
+}
+
+/// The decorations of our windows: SCTK's `ConceptFrame`, which also lets the window send the
+/// requests the decorations would send when clicked.
+pub struct WinitFrame {
+    inner: ConceptFrame,
+}
+
+/// Sends requests to the shell surface of a window, as if they came from its decorations.
+#[derive(Clone)]
+pub struct FrameRequests(RequestHandler);
+
+impl FrameRequests {
+    /// Takes the handler of the last frame created on this thread, which belongs to the window
+    /// that was just created.
+    pub fn take_last() -> Option<FrameRequests> {
+        LAST_HANDLER.with(|last| last.borrow_mut().take().map(FrameRequests))
+    }
+
+    /// Starts an interactive move, `serial` being the one of the input event that triggered it.
+    pub fn start_move(&self, seat: &wl_seat::WlSeat, serial: u32) {
+        (self.0.lock().unwrap())(FrameRequest::Move(seat.clone()), serial);
+    }
+
+    /// Starts an interactive resize, `serial` being the one of the input event that triggered it.
+    pub fn start_resize(&self, seat: &wl_seat::WlSeat, serial: u32, direction: ResizeDirection) {
+        let edge = match direction {
+            ResizeDirection::East => ResizeEdge::Right,
+            ResizeDirection::North => ResizeEdge::Top,
+            ResizeDirection::NorthEast => ResizeEdge::TopRight,
+            ResizeDirection::NorthWest => ResizeEdge::TopLeft,
+            ResizeDirection::South => ResizeEdge::Bottom,
+            ResizeDirection::SouthEast => ResizeEdge::BottomRight,
+            ResizeDirection::SouthWest => ResizeEdge::BottomLeft,
+            ResizeDirection::West => ResizeEdge::Left,
+        };
+        (self.0.lock().unwrap())(FrameRequest::Resize(seat.clone(), edge), serial);
+    }
+}
+
+impl Frame for WinitFrame {
+    type Error = <ConceptFrame as Frame>::Error;
+
+    fn init(
+        base_surface: &wl_surface::WlSurface,
+        compositor: &wl_compositor::WlCompositor,
+        subcompositor: &wl_subcompositor::WlSubcompositor,
+        shm: &wl_shm::WlShm,
+        implementation: Box<dyn FnMut(FrameRequest, u32) + Send>,
+    ) -> Result<WinitFrame, Self::Error> {
+        let handler: RequestHandler = Arc::new(Mutex::new(implementation));
+        let frame_handler = handler.clone();
+        let inner = ConceptFrame::init(
+            base_surface,
+            compositor,
+            subcompositor,
+            shm,
+            Box::new(move |request, serial| (frame_handler.lock().unwrap())(request, serial)),
+        )?;
+        LAST_HANDLER.with(|last| *last.borrow_mut() = Some(handler));
+        Ok(WinitFrame { inner })
+    }
+
+    fn set_active(&mut self, active: bool) -> bool {
+        self.inner.set_active(active)
+    }
+
+    fn set_maximized(&mut self, maximized: bool) -> bool {
+        self.inner.set_maximized(maximized)
+    }
+
+    fn set_hidden(&mut self, hidden: bool) {
+        self.inner.set_hidden(hidden)
+    }
+
+    fn set_resizable(&mut self, resizable: bool) {
+        self.inner.set_resizable(resizable)
+    }
+
+    fn new_seat(&mut self, seat: &wl_seat::WlSeat) {
+        self.inner.new_seat(seat)
+    }
+
+    fn resize(&mut self, newsize: (u32, u32)) {
+        self.inner.resize(newsize)
+    }
+
+    fn redraw(&mut self) {
+        self.inner.redraw()
+    }
+
+    fn subtract_borders(&self, width: i32, height: i32) -> (i32, i32) {
+        self.inner.subtract_borders(width, height)
+    }
+
+    fn add_borders(&self, width: i32, height: i32) -> (i32, i32) {
+        self.inner.add_borders(width, height)
+    }
+
+    fn location(&self) -> (i32, i32) {
+        self.inner.location()
+    }
+
+    fn set_theme<T: Theme>(&mut self, theme: T) {
+        self.inner.set_theme(theme)
+    }
+
+    fn set_title(&mut self, title: String) {
+        self.inner.set_title(title)
+    }
+}
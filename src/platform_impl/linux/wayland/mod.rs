@@ -8,9 +8,15 @@ pub use self::{
 
 use smithay_client_toolkit::reexports::client::protocol::wl_surface;
 
+mod clipboard;
+mod cursor;
+mod dnd;
 mod event_loop;
+mod frame;
 mod keyboard;
 mod pointer;
+mod tablet;
+mod text_input;
 mod touch;
 mod window;
 
@@ -7,6 +7,7 @@ use crate::event::{
 };
 
 use super::{
+    clipboard::Clipboard,
     event_loop::{CursorManager, EventsSink},
     make_wid,
     window::WindowStore,
@@ -25,6 +26,12 @@ use smithay_client_toolkit::reexports::protocols::unstable::relative_pointer::v1
     zwp_relative_pointer_v1::ZwpRelativePointerV1,
 };
 
+use smithay_client_toolkit::reexports::protocols::unstable::pointer_gestures::v1::client::{
+    zwp_pointer_gesture_pinch_v1::{self, ZwpPointerGesturePinchV1},
+    zwp_pointer_gesture_swipe_v1::{self, ZwpPointerGestureSwipeV1},
+    zwp_pointer_gestures_v1::ZwpPointerGesturesV1,
+};
+
 use smithay_client_toolkit::reexports::protocols::unstable::pointer_constraints::v1::client::{
     zwp_locked_pointer_v1::ZwpLockedPointerV1, zwp_pointer_constraints_v1::Lifetime,
     zwp_pointer_constraints_v1::ZwpPointerConstraintsV1,
@@ -38,6 +45,7 @@ pub fn implement_pointer(
     store: Arc<Mutex<WindowStore>>,
     modifiers_tracker: Arc<Mutex<ModifiersState>>,
     cursor_manager: Arc<Mutex<CursorManager>>,
+    clipboard: Arc<Mutex<Clipboard>>,
 ) -> WlPointer {
     seat.get_pointer(|pointer| {
         // Currently focused winit surface
@@ -45,6 +53,7 @@ pub fn implement_pointer(
         let mut axis_buffer = None;
         let mut axis_discrete_buffer = None;
         let mut axis_state = TouchPhase::Ended;
+        let seat = seat.clone();
 
         pointer.implement_closure(
             move |evt, pointer| {
@@ -131,7 +140,13 @@ pub fn implement_pointer(
                             );
                         }
                     }
-                    PtrEvent::Button { button, state, .. } => {
+                    PtrEvent::Button {
+                        button,
+                        state,
+                        serial,
+                        ..
+                    } => {
+                        clipboard.lock().unwrap().update_serial(&seat, serial);
                         if let Some(surface) = mouse_focus.as_ref() {
                             let state = match state {
                                 wl_pointer::ButtonState::Pressed => ElementState::Pressed,
@@ -278,6 +293,107 @@ pub fn implement_relative_pointer(
     })
 }
 
+pub fn implement_pointer_gestures(
+    sink: EventsSink,
+    pointer: &WlPointer,
+    manager: &ZwpPointerGesturesV1,
+) -> Result<(ZwpPointerGestureSwipeV1, ZwpPointerGesturePinchV1), ()> {
+    let swipe_sink = sink.clone();
+    // The surface and the number of fingers of the gesture in progress.
+    let mut swipe_focus = None;
+    let swipe = manager.get_swipe_gesture(pointer, |swipe| {
+        swipe.implement_closure(
+            move |evt, _swipe| {
+                use self::zwp_pointer_gesture_swipe_v1::Event;
+
+                let (phase, delta) = match evt {
+                    Event::Begin {
+                        surface, fingers, ..
+                    } => {
+                        swipe_focus = Some((surface, fingers));
+                        (TouchPhase::Started, (0.0, 0.0))
+                    }
+                    Event::Update { dx, dy, .. } => (TouchPhase::Moved, (dx, dy)),
+                    Event::End { cancelled, .. } if cancelled != 0 => {
+                        (TouchPhase::Cancelled, (0.0, 0.0))
+                    }
+                    Event::End { .. } => (TouchPhase::Ended, (0.0, 0.0)),
+                    _ => unreachable!(),
+                };
+                if let Some((ref surface, fingers)) = swipe_focus {
+                    let scale_factor = surface::get_dpi_factor(surface) as f64;
+                    swipe_sink.send_window_event(
+                        WindowEvent::SwipeGesture {
+                            device_id: crate::event::DeviceId(
+                                crate::platform_impl::DeviceId::Wayland(DeviceId),
+                            ),
+                            phase,
+                            fingers,
+                            delta: LogicalPosition::new(delta.0, delta.1).to_physical(scale_factor),
+                        },
+                        make_wid(surface),
+                    );
+                }
+                if phase != TouchPhase::Started && phase != TouchPhase::Moved {
+                    swipe_focus = None;
+                }
+            },
+            (),
+        )
+    })?;
+
+    // The surface and the scale of the gesture in progress.
+    let mut pinch_focus = None;
+    let pinch = manager.get_pinch_gesture(pointer, |pinch| {
+        pinch.implement_closure(
+            move |evt, _pinch| {
+                use self::zwp_pointer_gesture_pinch_v1::Event;
+
+                let (phase, scale, rotation) = match evt {
+                    Event::Begin { surface, .. } => {
+                        pinch_focus = Some((surface, 1.0));
+                        (TouchPhase::Started, 1.0, 0.0)
+                    }
+                    Event::Update {
+                        scale, rotation, ..
+                    } => (TouchPhase::Moved, scale, rotation),
+                    Event::End { cancelled, .. } if cancelled != 0 => {
+                        (TouchPhase::Cancelled, 0.0, 0.0)
+                    }
+                    Event::End { .. } => (TouchPhase::Ended, 0.0, 0.0),
+                    _ => unreachable!(),
+                };
+                if let Some((ref surface, ref mut previous_scale)) = pinch_focus {
+                    // The compositor reports the scale relative to the start of the gesture,
+                    // and doesn't send one at its end.
+                    let scale_delta = match phase {
+                        TouchPhase::Started | TouchPhase::Moved => scale - *previous_scale,
+                        _ => 0.0,
+                    };
+                    *previous_scale = scale;
+                    sink.send_window_event(
+                        WindowEvent::PinchGesture {
+                            device_id: crate::event::DeviceId(
+                                crate::platform_impl::DeviceId::Wayland(DeviceId),
+                            ),
+                            phase,
+                            scale_delta,
+                            rotation_delta: rotation,
+                        },
+                        make_wid(surface),
+                    );
+                }
+                if phase != TouchPhase::Started && phase != TouchPhase::Moved {
+                    pinch_focus = None;
+                }
+            },
+            (),
+        )
+    })?;
+
+    Ok((swipe, pinch))
+}
+
 pub fn implement_locked_pointer(
     surface: &WlSurface,
     pointer: &WlPointer,
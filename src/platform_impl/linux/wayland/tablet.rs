@@ -0,0 +1,275 @@
+use std::sync::{Arc, Mutex};
+
+use smithay_client_toolkit::reexports::client::{
+    protocol::{wl_seat, wl_surface::WlSurface},
+    NewProxy,
+};
+use smithay_client_toolkit::reexports::protocols::unstable::tablet::v2::client::{
+    zwp_tablet_manager_v2::ZwpTabletManagerV2,
+    zwp_tablet_pad_v2::{self, ZwpTabletPadV2},
+    zwp_tablet_seat_v2::{self, ZwpTabletSeatV2},
+    zwp_tablet_tool_v2::{self, ButtonState, Capability, Type, ZwpTabletToolV2},
+    zwp_tablet_v2::{self, ZwpTabletV2},
+};
+use smithay_client_toolkit::surface;
+
+use crate::{
+    dpi::LogicalPosition,
+    event::{PenButtons, PenInput, PenPhase, PenTool, WindowEvent},
+};
+
+use super::{event_loop::EventsSink, make_wid, DeviceId};
+
+// Linux input event codes of the barrel buttons.
+const BTN_STYLUS: u32 = 0x14b;
+const BTN_STYLUS2: u32 = 0x14c;
+const BTN_STYLUS3: u32 = 0x149;
+
+/// Tracks the `zwp_tablet_seat_v2` of every seat, whose tools are reported as
+/// `WindowEvent::PenInput`.
+pub struct Tablet {
+    sink: EventsSink,
+    manager: Option<ZwpTabletManagerV2>,
+    seats: Vec<(u32, ZwpTabletSeatV2)>,
+}
+
+impl Tablet {
+    pub fn new(sink: EventsSink) -> Tablet {
+        Tablet {
+            sink,
+            manager: None,
+            seats: Vec::new(),
+        }
+    }
+
+    /// Sets the tablet manager, creating tablet seats for the seats that are already known.
+    pub fn set_manager(
+        this: &Arc<Mutex<Tablet>>,
+        manager: ZwpTabletManagerV2,
+        seats: &[(u32, wl_seat::WlSeat)],
+    ) {
+        this.lock().unwrap().manager = Some(manager);
+        for &(id, ref seat) in seats {
+            Tablet::new_seat(this, id, seat);
+        }
+    }
+
+    pub fn new_seat(this: &Arc<Mutex<Tablet>>, id: u32, seat: &wl_seat::WlSeat) {
+        let mut tablet = this.lock().unwrap();
+        let sink = tablet.sink.clone();
+        let proxy = match tablet.manager {
+            Some(ref manager) => manager.get_tablet_seat(seat, move |proxy| {
+                proxy.implement_closure(
+                    move |event, _| match event {
+                        zwp_tablet_seat_v2::Event::TabletAdded { id } => implement_tablet(id),
+                        zwp_tablet_seat_v2::Event::ToolAdded { id } => {
+                            implement_tool(id, sink.clone())
+                        }
+                        zwp_tablet_seat_v2::Event::PadAdded { id } => implement_pad(id),
+                        _ => (),
+                    },
+                    (),
+                )
+            }),
+            None => return,
+        };
+        if let Ok(proxy) = proxy {
+            tablet.seats.push((id, proxy));
+        }
+    }
+
+    pub fn remove_seat(&mut self, id: u32) {
+        if let Some(idx) = self.seats.iter().position(|&(i, _)| i == id) {
+            let (_, tablet_seat) = self.seats.swap_remove(idx);
+            tablet_seat.destroy();
+        }
+    }
+}
+
+// Tablets and pads are only implemented so they can be destroyed along with the device.
+fn implement_tablet(tablet: NewProxy<ZwpTabletV2>) {
+    tablet.implement_closure(
+        |event, tablet| {
+            if let zwp_tablet_v2::Event::Removed = event {
+                tablet.destroy();
+            }
+        },
+        (),
+    );
+}
+
+fn implement_pad(pad: NewProxy<ZwpTabletPadV2>) {
+    pad.implement_closure(
+        |event, pad| {
+            if let zwp_tablet_pad_v2::Event::Removed = event {
+                pad.destroy();
+            }
+        },
+        (),
+    );
+}
+
+/// The changes to the proximity and the contact of a tool, applied on `frame`.
+#[derive(Default)]
+struct PendingPhases {
+    proximity_in: bool,
+    down: bool,
+    moved: bool,
+    up: bool,
+    proximity_out: bool,
+}
+
+struct ToolState {
+    sink: EventsSink,
+    tool: PenTool,
+    has_pressure: bool,
+    has_tilt: bool,
+    has_rotation: bool,
+    surface: Option<WlSurface>,
+    // Surface-local logical coordinates.
+    position: LogicalPosition<f64>,
+    pressure: f64,
+    tilt: (f64, f64),
+    rotation: f64,
+    buttons: PenButtons,
+    pending: PendingPhases,
+}
+
+fn implement_tool(tool: NewProxy<ZwpTabletToolV2>, sink: EventsSink) {
+    let mut state = ToolState {
+        sink,
+        tool: PenTool::Unknown,
+        has_pressure: false,
+        has_tilt: false,
+        has_rotation: false,
+        surface: None,
+        position: LogicalPosition::new(0.0, 0.0),
+        pressure: 0.0,
+        tilt: (0.0, 0.0),
+        rotation: 0.0,
+        buttons: PenButtons::empty(),
+        pending: PendingPhases::default(),
+    };
+    tool.implement_closure(move |event, tool| state.handle_event(event, &tool), ());
+}
+
+impl ToolState {
+    fn handle_event(&mut self, event: zwp_tablet_tool_v2::Event, tool: &ZwpTabletToolV2) {
+        use self::zwp_tablet_tool_v2::Event;
+
+        match event {
+            Event::Type { tool_type } => {
+                self.tool = match tool_type {
+                    Type::Pen => PenTool::Pen,
+                    Type::Eraser => PenTool::Eraser,
+                    Type::Brush => PenTool::Brush,
+                    Type::Pencil => PenTool::Pencil,
+                    Type::Airbrush => PenTool::Airbrush,
+                    _ => PenTool::Unknown,
+                };
+            }
+            Event::Capability { capability } => match capability {
+                Capability::Pressure => self.has_pressure = true,
+                Capability::Tilt => self.has_tilt = true,
+                Capability::Rotation => self.has_rotation = true,
+                _ => (),
+            },
+            Event::ProximityIn { surface, .. } => {
+                self.surface = Some(surface);
+                self.pending.proximity_in = true;
+            }
+            Event::ProximityOut => self.pending.proximity_out = true,
+            Event::Down { .. } => self.pending.down = true,
+            Event::Up => self.pending.up = true,
+            Event::Motion { x, y } => {
+                self.position = LogicalPosition::new(x, y);
+                self.pending.moved = true;
+            }
+            Event::Pressure { pressure } => {
+                self.pressure = pressure as f64 / 65535.0;
+                self.pending.moved = true;
+            }
+            Event::Tilt { tilt_x, tilt_y } => {
+                self.tilt = (tilt_x, tilt_y);
+                self.pending.moved = true;
+            }
+            Event::Rotation { degrees } => {
+                self.rotation = degrees;
+                self.pending.moved = true;
+            }
+            Event::Button { button, state, .. } => {
+                let button = match button {
+                    BTN_STYLUS => PenButtons::PRIMARY,
+                    BTN_STYLUS2 => PenButtons::SECONDARY,
+                    BTN_STYLUS3 => PenButtons::TERTIARY,
+                    _ => return,
+                };
+                self.buttons.set(button, state == ButtonState::Pressed);
+                self.pending.moved = true;
+            }
+            Event::Frame { .. } => self.frame(),
+            Event::Removed => tool.destroy(),
+            _ => (),
+        }
+    }
+
+    fn frame(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        let surface = match self.surface {
+            Some(ref surface) => surface,
+            None => return,
+        };
+
+        let mut phases = Vec::new();
+        if pending.proximity_in {
+            phases.push(PenPhase::ProximityIn);
+        }
+        if pending.down {
+            phases.push(PenPhase::Down);
+        }
+        if pending.up {
+            phases.push(PenPhase::Up);
+        }
+        if pending.proximity_out {
+            phases.push(PenPhase::ProximityOut);
+        }
+        // The other phases already carry the new state of the tool.
+        if pending.moved && phases.is_empty() {
+            phases.push(PenPhase::Moved);
+        }
+
+        let wid = make_wid(surface);
+        let location = self
+            .position
+            .to_physical(surface::get_dpi_factor(surface) as f64);
+        for phase in phases {
+            self.sink.send_window_event(
+                WindowEvent::PenInput(PenInput {
+                    device_id: crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(
+                        DeviceId,
+                    )),
+                    phase,
+                    tool: self.tool,
+                    location,
+                    pressure: if self.has_pressure {
+                        Some(self.pressure)
+                    } else {
+                        None
+                    },
+                    tilt: if self.has_tilt { Some(self.tilt) } else { None },
+                    rotation: if self.has_rotation {
+                        Some(self.rotation)
+                    } else {
+                        None
+                    },
+                    buttons: self.buttons,
+                }),
+                wid,
+            );
+        }
+
+        if pending.proximity_out {
+            self.surface = None;
+        }
+    }
+}
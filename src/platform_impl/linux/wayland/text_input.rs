@@ -0,0 +1,237 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use smithay_client_toolkit::reexports::client::protocol::wl_seat;
+use smithay_client_toolkit::reexports::protocols::unstable::text_input::v3::client::{
+    zwp_text_input_manager_v3::ZwpTextInputManagerV3,
+    zwp_text_input_v3::{self, ContentHint, ContentPurpose, ZwpTextInputV3},
+};
+
+use crate::event::WindowEvent;
+
+use super::{event_loop::EventsSink, make_wid, WindowId};
+
+/// The IME settings of a window, applied whenever it gains text input focus.
+#[derive(Clone, Copy, Default)]
+struct WindowSettings {
+    allowed: bool,
+    // Surface-local logical coordinates of the candidate box.
+    position: (i32, i32),
+}
+
+/// The state of a text input changed by the compositor, applied on `done`.
+#[derive(Default)]
+struct PendingState {
+    preedit: Option<(String, Option<(usize, usize)>)>,
+    commit: Option<String>,
+}
+
+struct SeatTextInput {
+    seat_id: u32,
+    text_input: ZwpTextInputV3,
+    focus: Option<WindowId>,
+    enabled: bool,
+    preedit_shown: bool,
+    pending: PendingState,
+}
+
+/// Tracks the `zwp_text_input_v3` of every seat, along with the IME settings of every window.
+///
+/// The text input of a seat follows its keyboard focus, and is only enabled while the focused
+/// window allows IME input.
+pub struct TextInput {
+    sink: EventsSink,
+    manager: Option<ZwpTextInputManagerV3>,
+    inputs: Vec<SeatTextInput>,
+    windows: HashMap<WindowId, WindowSettings>,
+}
+
+impl TextInput {
+    pub fn new(sink: EventsSink) -> TextInput {
+        TextInput {
+            sink,
+            manager: None,
+            inputs: Vec::new(),
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Sets the text input manager, creating text inputs for the seats that are already known.
+    pub fn set_manager(
+        this: &Arc<Mutex<TextInput>>,
+        manager: ZwpTextInputManagerV3,
+        seats: &[(u32, wl_seat::WlSeat)],
+    ) {
+        this.lock().unwrap().manager = Some(manager);
+        for &(id, ref seat) in seats {
+            TextInput::new_seat(this, id, seat);
+        }
+    }
+
+    pub fn new_seat(this: &Arc<Mutex<TextInput>>, id: u32, seat: &wl_seat::WlSeat) {
+        let mut text_input = this.lock().unwrap();
+        let weak = Arc::downgrade(this);
+        let proxy = match text_input.manager {
+            Some(ref manager) => manager.get_text_input(seat, move |proxy| {
+                proxy.implement_closure(
+                    move |event, proxy| {
+                        if let Some(text_input) = weak.upgrade() {
+                            text_input.lock().unwrap().handle_event(&proxy, event);
+                        }
+                    },
+                    (),
+                )
+            }),
+            None => return,
+        };
+        if let Ok(proxy) = proxy {
+            text_input.inputs.push(SeatTextInput {
+                seat_id: id,
+                text_input: proxy,
+                focus: None,
+                enabled: false,
+                preedit_shown: false,
+                pending: PendingState::default(),
+            });
+        }
+    }
+
+    pub fn remove_seat(&mut self, id: u32) {
+        if let Some(idx) = self.inputs.iter().position(|input| input.seat_id == id) {
+            let input = self.inputs.swap_remove(idx);
+            input.text_input.destroy();
+        }
+    }
+
+    pub fn remove_window(&mut self, wid: WindowId) {
+        self.windows.remove(&wid);
+    }
+
+    pub fn set_ime_allowed(&mut self, wid: WindowId, allowed: bool) {
+        self.windows.entry(wid).or_default().allowed = allowed;
+        self.update_window(wid);
+    }
+
+    pub fn set_ime_position(&mut self, wid: WindowId, position: (i32, i32)) {
+        self.windows.entry(wid).or_default().position = position;
+        self.update_window(wid);
+    }
+
+    // Applies the settings of a window to the text inputs it has the focus of.
+    fn update_window(&mut self, wid: WindowId) {
+        let settings = self.windows.get(&wid).cloned().unwrap_or_default();
+        for input in &mut self.inputs {
+            if input.focus == Some(wid) {
+                input.apply(&self.sink, settings);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, proxy: &ZwpTextInputV3, event: zwp_text_input_v3::Event) {
+        let input = match self
+            .inputs
+            .iter_mut()
+            .find(|input| input.text_input.as_ref().equals(proxy.as_ref()))
+        {
+            Some(input) => input,
+            None => return,
+        };
+        match event {
+            zwp_text_input_v3::Event::Enter { surface } => {
+                let wid = make_wid(&surface);
+                input.focus = Some(wid);
+                // The text input starts disabled on every surface it enters.
+                input.enabled = false;
+                let settings = self.windows.get(&wid).cloned().unwrap_or_default();
+                input.apply(&self.sink, settings);
+            }
+            zwp_text_input_v3::Event::Leave { .. } => {
+                input.apply(&self.sink, WindowSettings::default());
+                input.focus = None;
+            }
+            zwp_text_input_v3::Event::PreeditString {
+                text,
+                cursor_begin,
+                cursor_end,
+            } => {
+                // A negative cursor position means that the cursor should be hidden.
+                let cursor_range = if cursor_begin < 0 || cursor_end < 0 {
+                    None
+                } else {
+                    Some((cursor_begin as usize, cursor_end as usize))
+                };
+                input.pending.preedit = Some((text.unwrap_or_default(), cursor_range));
+            }
+            zwp_text_input_v3::Event::CommitString { text } => {
+                input.pending.commit = text;
+            }
+            zwp_text_input_v3::Event::Done { .. } => input.done(&self.sink),
+            // No surrounding text is ever sent, so there's nothing to delete.
+            _ => (),
+        }
+    }
+}
+
+impl SeatTextInput {
+    fn apply(&mut self, sink: &EventsSink, settings: WindowSettings) {
+        if settings.allowed {
+            let (x, y) = settings.position;
+            // Enabling the text input again resets the input method, which would drop the text
+            // being composed, so moving the cursor only updates its rectangle.
+            if !self.enabled {
+                self.text_input.enable();
+                self.text_input
+                    .set_content_type(ContentHint::None, ContentPurpose::Normal);
+                self.enabled = true;
+            }
+            self.text_input.set_cursor_rectangle(x, y, 0, 0);
+            self.text_input.commit();
+        } else if self.enabled {
+            self.text_input.disable();
+            self.text_input.commit();
+            self.enabled = false;
+            self.clear_preedit(sink);
+        }
+    }
+
+    // Applies the pending state, in the order mandated by the protocol: the preedit string is
+    // removed, the committed string inserted and the new preedit string shown.
+    fn done(&mut self, sink: &EventsSink) {
+        let PendingState { preedit, commit } = std::mem::take(&mut self.pending);
+        let wid = match self.focus {
+            Some(wid) => wid,
+            None => return,
+        };
+
+        if let Some(text) = commit {
+            self.clear_preedit(sink);
+            sink.send_window_event(WindowEvent::ImeCommit(text), wid);
+        }
+
+        match preedit {
+            Some((text, cursor_range)) if !text.is_empty() => {
+                self.preedit_shown = true;
+                sink.send_window_event(WindowEvent::ImePreedit { text, cursor_range }, wid);
+            }
+            _ => self.clear_preedit(sink),
+        }
+    }
+
+    fn clear_preedit(&mut self, sink: &EventsSink) {
+        if !self.preedit_shown {
+            return;
+        }
+        self.preedit_shown = false;
+        if let Some(wid) = self.focus {
+            sink.send_window_event(
+                WindowEvent::ImePreedit {
+                    text: String::new(),
+                    cursor_range: None,
+                },
+                wid,
+            );
+        }
+    }
+}
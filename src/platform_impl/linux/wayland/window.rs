@@ -3,36 +3,54 @@ use std::{
     collections::VecDeque,
     mem::replace,
     sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
 };
 
 use crate::{
     dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
     error::{ExternalError, NotSupportedError, OsError as RootOsError},
+    event::{DragAction, WindowState},
     monitor::MonitorHandle as RootMonitorHandle,
     platform_impl::{
-        platform::wayland::event_loop::{available_monitors, primary_monitor},
-        MonitorHandle as PlatformMonitorHandle,
+        platform::{
+            dnd::DragResponse,
+            wayland::event_loop::{available_monitors, primary_monitor},
+        },
+        MonitorHandle as PlatformMonitorHandle, OsError,
         PlatformSpecificWindowBuilderAttributes as PlAttributes,
     },
-    window::{CursorIcon, Fullscreen, WindowAttributes},
+    window::{
+        ClipboardContents, ClipboardKind, CursorIcon, CustomCursor, Fullscreen, ResizeDirection,
+        WindowAttributes,
+    },
 };
 
 use smithay_client_toolkit::{
     output::OutputMgr,
     reexports::client::{
-        protocol::{wl_seat, wl_surface},
+        protocol::{wl_callback, wl_seat, wl_surface},
         Display,
     },
     surface::{get_dpi_factor, get_outputs},
-    window::{ConceptFrame, Event as WEvent, State as WState, Theme, Window as SWindow},
+    window::{Event as WEvent, State as WState, Theme, Window as SWindow},
 };
 
-use super::{event_loop::CursorManager, make_wid, EventLoopWindowTarget, MonitorHandle, WindowId};
+use super::{
+    clipboard::{self, Clipboard},
+    event_loop::CursorManager,
+    frame::{FrameRequests, WinitFrame},
+    make_wid,
+    text_input::TextInput,
+    EventLoopWindowTarget, MonitorHandle, WindowId,
+};
 
 pub struct Window {
     surface: wl_surface::WlSurface,
-    frame: Arc<Mutex<SWindow<ConceptFrame>>>,
+    frame: Arc<Mutex<SWindow<WinitFrame>>>,
+    frame_requests: FrameRequests,
     cursor_manager: Arc<Mutex<CursorManager>>,
+    clipboard: Arc<Mutex<Clipboard>>,
+    text_input: Arc<Mutex<TextInput>>,
     outputs: OutputMgr, // Access to info for all monitors
     size: Arc<Mutex<(u32, u32)>>,
     kill_switch: (Arc<Mutex<bool>>, Arc<Mutex<bool>>),
@@ -40,8 +58,13 @@ pub struct Window {
     need_frame_refresh: Arc<Mutex<bool>>,
     need_refresh: Arc<Mutex<bool>>,
     fullscreen: Arc<Mutex<bool>>,
+    maximized: Arc<Mutex<bool>>,
+    activated: Arc<Mutex<bool>>,
     cursor_grab_changed: Arc<Mutex<Option<bool>>>, // Update grab state
     decorated: Arc<Mutex<bool>>,
+    resizable: Mutex<bool>,
+    title: Mutex<String>,
+    frame_callback: Arc<Mutex<FrameCallbackState>>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -78,6 +101,8 @@ impl Window {
         // Create the window
         let size = Arc::new(Mutex::new((width, height)));
         let fullscreen = Arc::new(Mutex::new(false));
+        let maximized = Arc::new(Mutex::new(false));
+        let activated = Arc::new(Mutex::new(false));
 
         let window_store = evlp.store.clone();
 
@@ -85,7 +110,7 @@ impl Window {
         let pending_decorations_action = Arc::new(Mutex::new(None));
 
         let my_surface = surface.clone();
-        let mut frame = SWindow::<ConceptFrame>::init_from_env(
+        let mut frame = SWindow::<WinitFrame>::init_from_env(
             &evlp.env,
             surface.clone(),
             (width, height),
@@ -93,11 +118,20 @@ impl Window {
                 WEvent::Configure { new_size, states } => {
                     let mut store = window_store.lock().unwrap();
                     let is_fullscreen = states.contains(&WState::Fullscreen);
+                    let is_maximized = states.contains(&WState::Maximized);
+                    let is_activated = states.contains(&WState::Activated);
+                    let window_state = window_state_from(&states);
 
                     for window in &mut store.windows {
                         if window.surface.as_ref().equals(&my_surface.as_ref()) {
                             window.new_size = new_size;
                             *(window.need_refresh.lock().unwrap()) = true;
+                            *(window.maximized.lock().unwrap()) = is_maximized;
+                            *(window.activated.lock().unwrap()) = is_activated;
+                            if window.window_state != window_state {
+                                window.window_state = window_state;
+                                window.new_window_state = Some(window_state);
+                            }
                             {
                                 // Get whether we're in fullscreen
                                 let mut fullscreen = window.fullscreen.lock().unwrap();
@@ -141,6 +175,7 @@ impl Window {
             },
         )
         .unwrap();
+        let frame_requests = FrameRequests::take_last().unwrap();
 
         if let Some(app_id) = pl_attribs.app_id {
             frame.set_app_id(app_id);
@@ -172,7 +207,7 @@ impl Window {
         frame.set_decorate(attributes.decorations);
 
         // set title
-        frame.set_title(attributes.title);
+        frame.set_title(attributes.title.clone());
 
         // min-max dimensions
         frame.set_min_size(
@@ -191,6 +226,7 @@ impl Window {
         let frame = Arc::new(Mutex::new(frame));
         let need_refresh = Arc::new(Mutex::new(true));
         let cursor_grab_changed = Arc::new(Mutex::new(None));
+        let frame_callback = Arc::new(Mutex::new(FrameCallbackState::default()));
 
         evlp.store.lock().unwrap().windows.push(InternalWindow {
             closed: false,
@@ -198,6 +234,8 @@ impl Window {
             size: size.clone(),
             need_refresh: need_refresh.clone(),
             fullscreen: fullscreen.clone(),
+            maximized: maximized.clone(),
+            activated: activated.clone(),
             cursor_grab_changed: cursor_grab_changed.clone(),
             need_frame_refresh: need_frame_refresh.clone(),
             surface: surface.clone(),
@@ -207,6 +245,9 @@ impl Window {
             new_scale_factor: None,
             decorated: decorated.clone(),
             pending_decorations_action: pending_decorations_action.clone(),
+            window_state: WindowState::empty(),
+            new_window_state: None,
+            frame_callback: frame_callback.clone(),
         });
         evlp.evq.borrow_mut().sync_roundtrip().unwrap();
 
@@ -214,15 +255,23 @@ impl Window {
             display: evlp.display.clone(),
             surface,
             frame,
+            frame_requests,
             outputs: evlp.env.outputs.clone(),
             size,
             kill_switch: (kill_switch, evlp.cleanup_needed.clone()),
             need_frame_refresh,
             need_refresh,
             cursor_manager,
+            clipboard: evlp.clipboard.clone(),
+            text_input: evlp.text_input.clone(),
             fullscreen,
+            maximized,
+            activated,
             cursor_grab_changed,
             decorated,
+            resizable: Mutex::new(attributes.resizable),
+            title: Mutex::new(attributes.title),
+            frame_callback,
         })
     }
 
@@ -232,13 +281,23 @@ impl Window {
     }
 
     pub fn set_title(&self, title: &str) {
+        *self.title.lock().unwrap() = title.into();
         self.frame.lock().unwrap().set_title(title.into());
     }
 
+    pub fn title(&self) -> String {
+        self.title.lock().unwrap().clone()
+    }
+
     pub fn set_visible(&self, _visible: bool) {
         // TODO
     }
 
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        None
+    }
+
     #[inline]
     pub fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
         Err(NotSupportedError::new())
@@ -264,6 +323,16 @@ impl Window {
         *self.need_refresh.lock().unwrap() = true;
     }
 
+    pub fn request_frame_callback(&self) {
+        self.frame_callback.lock().unwrap().wanted = true;
+        if request_frame_callback(&self.surface, &self.frame_callback) {
+            // The request only takes effect with the next commit, which the application may not
+            // make until the callback is done, so commit the surface along with its current frame.
+            self.surface.commit();
+            let _ = self.display.flush();
+        }
+    }
+
     #[inline]
     pub fn outer_size(&self) -> PhysicalSize<u32> {
         let scale_factor = self.scale_factor() as f64;
@@ -302,9 +371,14 @@ impl Window {
 
     #[inline]
     pub fn set_resizable(&self, resizable: bool) {
+        *self.resizable.lock().unwrap() = resizable;
         self.frame.lock().unwrap().set_resizable(resizable);
     }
 
+    pub fn is_resizable(&self) -> bool {
+        *self.resizable.lock().unwrap()
+    }
+
     #[inline]
     pub fn scale_factor(&self) -> i32 {
         get_dpi_factor(&self.surface)
@@ -316,6 +390,14 @@ impl Window {
         *(self.need_frame_refresh.lock().unwrap()) = true;
     }
 
+    pub fn is_decorated(&self) -> bool {
+        *self.decorated.lock().unwrap()
+    }
+
+    pub fn has_focus(&self) -> bool {
+        *self.activated.lock().unwrap()
+    }
+
     pub fn set_minimized(&self, minimized: bool) {
         // An app cannot un-minimize itself on Wayland
         if minimized {
@@ -323,6 +405,12 @@ impl Window {
         }
     }
 
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        // The compositor doesn't tell whether the window is minimized.
+        None
+    }
+
     pub fn set_maximized(&self, maximized: bool) {
         if maximized {
             self.frame.lock().unwrap().set_maximized();
@@ -331,6 +419,10 @@ impl Window {
         }
     }
 
+    pub fn is_maximized(&self) -> bool {
+        *self.maximized.lock().unwrap()
+    }
+
     pub fn fullscreen(&self) -> Option<Fullscreen> {
         if *(self.fullscreen.lock().unwrap()) {
             Some(Fullscreen::Borderless(RootMonitorHandle {
@@ -369,6 +461,13 @@ impl Window {
         cursor_manager.set_cursor_icon(cursor);
     }
 
+    #[inline]
+    pub fn set_custom_cursor(&self, cursor: CustomCursor) {
+        let mut cursor_manager = self.cursor_manager.lock().unwrap();
+        cursor_manager.set_custom_cursor(cursor);
+        let _ = self.display.flush();
+    }
+
     #[inline]
     pub fn set_cursor_visible(&self, visible: bool) {
         let mut cursor_manager = self.cursor_manager.lock().unwrap();
@@ -386,6 +485,107 @@ impl Window {
         Err(ExternalError::NotSupported(NotSupportedError::new()))
     }
 
+    pub fn set_clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        if kind != ClipboardKind::Clipboard {
+            return Err(ExternalError::NotSupported(NotSupportedError::new()));
+        }
+        Clipboard::set_contents(&self.clipboard, contents)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::WaylandMisc(err))))?;
+        let _ = self.display.flush();
+        Ok(())
+    }
+
+    pub fn clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        if kind != ClipboardKind::Clipboard {
+            return Err(ExternalError::NotSupported(NotSupportedError::new()));
+        }
+        let pipe = {
+            let clipboard = self.clipboard.lock().unwrap();
+            // Reading from our own source would wait on the event loop, so answer directly.
+            if let Some(contents) = clipboard.owned_contents() {
+                return Ok(contents.get(mime_type).map(|data| data.to_vec()));
+            }
+            clipboard.receive(mime_type)
+        };
+        let pipe = match pipe {
+            Ok(Some(pipe)) => pipe,
+            Ok(None) => return Ok(None),
+            Err(err) => return Err(ExternalError::Os(os_error!(OsError::WaylandMisc(err)))),
+        };
+        let _ = self.display.flush();
+        clipboard::read_pipe(pipe)
+            .map(Some)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::WaylandMisc(err))))
+    }
+
+    pub fn set_drag_response(&self, response: DragResponse) {
+        self.clipboard
+            .lock()
+            .unwrap()
+            .respond_to_drag(make_wid(&self.surface), response);
+        let _ = self.display.flush();
+    }
+
+    pub fn start_drag(
+        &self,
+        contents: ClipboardContents,
+        allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        Clipboard::start_drag(&self.clipboard, &self.surface, contents, allowed_actions)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::WaylandMisc(err))))?;
+        let _ = self.display.flush();
+        Ok(())
+    }
+
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        let (seat, serial) = self.last_input()?;
+        self.frame_requests.start_move(&seat, serial);
+        let _ = self.display.flush();
+        Ok(())
+    }
+
+    pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
+        let (seat, serial) = self.last_input()?;
+        self.frame_requests.start_resize(&seat, serial, direction);
+        let _ = self.display.flush();
+        Ok(())
+    }
+
+    /// Returns the seat and serial of the last input event, which the compositor checks before
+    /// starting an interactive move or resize.
+    fn last_input(&self) -> Result<(wl_seat::WlSeat, u32), ExternalError> {
+        self.clipboard.lock().unwrap().last_input().ok_or_else(|| {
+            ExternalError::Os(os_error!(OsError::WaylandMisc(
+                "no input event to start the operation from"
+            )))
+        })
+    }
+
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.text_input
+            .lock()
+            .unwrap()
+            .set_ime_allowed(self.id(), allowed);
+        let _ = self.display.flush();
+    }
+
+    pub fn set_ime_position(&self, position: Position) {
+        let position = position.to_logical::<i32>(self.scale_factor() as f64);
+        self.text_input
+            .lock()
+            .unwrap()
+            .set_ime_position(self.id(), (position.x, position.y));
+        let _ = self.display.flush();
+    }
+
     pub fn display(&self) -> &Display {
         &*self.display
     }
@@ -437,15 +637,94 @@ struct InternalWindow {
     size: Arc<Mutex<(u32, u32)>>,
     need_refresh: Arc<Mutex<bool>>,
     fullscreen: Arc<Mutex<bool>>,
+    maximized: Arc<Mutex<bool>>,
+    activated: Arc<Mutex<bool>>,
     need_frame_refresh: Arc<Mutex<bool>>,
     cursor_grab_changed: Arc<Mutex<Option<bool>>>,
     closed: bool,
     kill_switch: Arc<Mutex<bool>>,
-    frame: Weak<Mutex<SWindow<ConceptFrame>>>,
+    frame: Weak<Mutex<SWindow<WinitFrame>>>,
     current_scale_factor: i32,
     new_scale_factor: Option<i32>,
     decorated: Arc<Mutex<bool>>,
     pending_decorations_action: Arc<Mutex<Option<DecorationsAction>>>,
+    // The state last reported with `StateChanged`
+    window_state: WindowState,
+    new_window_state: Option<WindowState>,
+    frame_callback: Arc<Mutex<FrameCallbackState>>,
+}
+
+// How long a frame callback may stay pending before the window is considered occluded.
+const OCCLUSION_DELAY: Duration = Duration::from_secs(1);
+
+/// Tracks the frame callbacks of a window, which compositors stop sending while it's hidden.
+#[derive(Default)]
+struct FrameCallbackState {
+    // When the pending frame callback was requested
+    requested: Option<Instant>,
+    // The state last reported with `Occluded`
+    occluded: bool,
+    // Whether the application asked for the next frame callback
+    wanted: bool,
+    // The time of the frame callback the application asked for, once it's done
+    done: Option<u32>,
+}
+
+impl FrameCallbackState {
+    /// When the window is to be considered occluded if the pending frame callback isn't done by
+    /// then.
+    fn occlusion_deadline(&self) -> Option<Instant> {
+        match self.requested {
+            Some(requested) if !self.occluded => Some(requested + OCCLUSION_DELAY),
+            _ => None,
+        }
+    }
+
+    /// Returns the occlusion state of the window if it changed since it was last reported.
+    fn update_occluded(&mut self) -> Option<bool> {
+        let occluded = match self.requested {
+            Some(requested) => self.occluded || requested.elapsed() >= OCCLUSION_DELAY,
+            None => false,
+        };
+        if occluded != self.occluded {
+            self.occluded = occluded;
+            Some(occluded)
+        } else {
+            None
+        }
+    }
+}
+
+// Asks for a frame callback, committed by the application along with its next frame. Returns
+// whether a new one was requested, rather than one being pending already.
+fn request_frame_callback(
+    surface: &wl_surface::WlSurface,
+    state: &Arc<Mutex<FrameCallbackState>>,
+) -> bool {
+    let mut state_lock = state.lock().unwrap();
+    if state_lock.requested.is_some() {
+        return false;
+    }
+    let state = state.clone();
+    let callback = surface.frame(move |callback| {
+        callback.implement_closure(
+            move |event, _| {
+                if let wl_callback::Event::Done { callback_data } = event {
+                    let mut state = state.lock().unwrap();
+                    state.requested = None;
+                    if state.wanted {
+                        state.wanted = false;
+                        state.done = Some(callback_data);
+                    }
+                }
+            },
+            (),
+        )
+    });
+    if callback.is_ok() {
+        state_lock.requested = Some(Instant::now());
+    }
+    callback.is_ok()
 }
 
 pub struct WindowStore {
@@ -461,8 +740,11 @@ pub struct WindowStoreForEach<'a> {
     pub grab_cursor: Option<bool>,
     pub surface: &'a wl_surface::WlSurface,
     pub wid: WindowId,
-    pub frame: Option<&'a mut SWindow<ConceptFrame>>,
+    pub frame: Option<&'a mut SWindow<WinitFrame>>,
     pub decorations_action: Option<DecorationsAction>,
+    pub new_window_state: Option<WindowState>,
+    pub occluded: Option<bool>,
+    pub frame_callback: Option<u32>,
 }
 
 impl WindowStore {
@@ -496,6 +778,24 @@ impl WindowStore {
         pruned
     }
 
+    /// Whether a window is to be redrawn by the next iteration of the event loop.
+    pub fn redraws_pending(&self) -> bool {
+        self.windows.iter().any(|window| {
+            *window.need_refresh.lock().unwrap()
+                || *window.need_frame_refresh.lock().unwrap()
+                || window.frame_callback.lock().unwrap().done.is_some()
+        })
+    }
+
+    /// The earliest time a window is to be considered occluded, which the event loop has to wake
+    /// up for to report it.
+    pub fn next_occlusion_deadline(&self) -> Option<Instant> {
+        self.windows
+            .iter()
+            .filter_map(|window| window.frame_callback.lock().unwrap().occlusion_deadline())
+            .min()
+    }
+
     pub fn new_seat(&self, seat: &wl_seat::WlSeat) {
         for window in &self.windows {
             if let Some(w) = window.frame.upgrade() {
@@ -525,6 +825,13 @@ impl WindowStore {
             let opt_arc = window.frame.upgrade();
             let mut opt_mutex_lock = opt_arc.as_ref().map(|m| m.lock().unwrap());
             let decorations_action = { window.pending_decorations_action.lock().unwrap().take() };
+            let (occluded, frame_callback) = {
+                let mut state = window.frame_callback.lock().unwrap();
+                (state.update_occluded(), state.done.take())
+            };
+            if frame_callback.is_some() {
+                *window.need_refresh.lock().unwrap() = true;
+            }
             f(WindowStoreForEach {
                 new_size: window.new_size.take(),
                 size: &window.size,
@@ -536,6 +843,9 @@ impl WindowStore {
                 wid: make_wid(&window.surface),
                 frame: opt_mutex_lock.as_mut().map(|m| &mut **m),
                 decorations_action,
+                new_window_state: window.new_window_state.take(),
+                occluded,
+                frame_callback,
             });
             // avoid re-spamming the event
             window.closed = false;
@@ -544,13 +854,17 @@ impl WindowStore {
 
     pub fn for_each_redraw_trigger<F>(&mut self, mut f: F)
     where
-        F: FnMut(bool, bool, WindowId, Option<&mut SWindow<ConceptFrame>>),
+        F: FnMut(bool, bool, WindowId, Option<&mut SWindow<WinitFrame>>),
     {
         for window in &mut self.windows {
             let opt_arc = window.frame.upgrade();
             let mut opt_mutex_lock = opt_arc.as_ref().map(|m| m.lock().unwrap());
+            let refresh = replace(&mut *window.need_refresh.lock().unwrap(), false);
+            if refresh {
+                request_frame_callback(&window.surface, &window.frame_callback);
+            }
             f(
-                replace(&mut *window.need_refresh.lock().unwrap(), false),
+                refresh,
                 replace(&mut *window.need_frame_refresh.lock().unwrap(), false),
                 make_wid(&window.surface),
                 opt_mutex_lock.as_mut().map(|m| &mut **m),
@@ -558,3 +872,19 @@ impl WindowStore {
         }
     }
 }
+
+fn window_state_from(states: &[WState]) -> WindowState {
+    let mut window_state = WindowState::empty();
+    for state in states {
+        window_state |= match *state {
+            WState::Maximized => WindowState::MAXIMIZED,
+            WState::Fullscreen => WindowState::FULLSCREEN,
+            WState::TiledLeft => WindowState::TILED_LEFT,
+            WState::TiledRight => WindowState::TILED_RIGHT,
+            WState::TiledTop => WindowState::TILED_TOP,
+            WState::TiledBottom => WindowState::TILED_BOTTOM,
+            _ => WindowState::empty(),
+        };
+    }
+    window_state
+}
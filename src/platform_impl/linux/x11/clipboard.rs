@@ -0,0 +1,762 @@
+use std::{
+    cmp,
+    ffi::CString,
+    mem::MaybeUninit,
+    os::raw::*,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::{ffi, util, XConnection, XError};
+use crate::window::{is_text_mime_type, ClipboardContents, ClipboardKind};
+
+// From <X11/extensions/xfixeswire.h>; these aren't exposed by x11-dl.
+const XFIXES_SELECTION_NOTIFY: c_int = 0;
+const XFIXES_SET_SELECTION_OWNER_NOTIFY_MASK: c_ulong = 1 << 0;
+const XFIXES_SELECTION_WINDOW_DESTROY_NOTIFY_MASK: c_ulong = 1 << 1;
+const XFIXES_SELECTION_CLIENT_CLOSE_NOTIFY_MASK: c_ulong = 1 << 2;
+
+// How long we're willing to wait for the selection owner to answer a conversion request, or to
+// send the next chunk of an incremental transfer.
+const CONVERSION_TIMEOUT: Duration = Duration::from_millis(1000);
+
+// How long a requestor may take to ask for the next chunk of an incremental transfer we serve
+// before it's abandoned.
+const INCR_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClipboardAtoms {
+    pub clipboard: ffi::Atom,
+    pub targets: ffi::Atom,
+    pub utf8_string: ffi::Atom,
+    pub text: ffi::Atom,
+    pub text_plain: ffi::Atom,
+    pub incr: ffi::Atom,
+    pub xdnd_selection: ffi::Atom,
+    // The property on our own windows that selection owners write converted data into
+    pub property: ffi::Atom,
+}
+
+impl ClipboardAtoms {
+    pub fn new(xconn: &Arc<XConnection>) -> Result<Self, XError> {
+        let names = [
+            b"CLIPBOARD\0".as_ptr() as *mut c_char,
+            b"TARGETS\0".as_ptr() as *mut c_char,
+            b"UTF8_STRING\0".as_ptr() as *mut c_char,
+            b"TEXT\0".as_ptr() as *mut c_char,
+            b"text/plain\0".as_ptr() as *mut c_char,
+            b"INCR\0".as_ptr() as *mut c_char,
+            b"XdndSelection\0".as_ptr() as *mut c_char,
+            b"_WINIT_SELECTION\0".as_ptr() as *mut c_char,
+        ];
+        let atoms = unsafe { xconn.get_atoms(&names) }?;
+        Ok(ClipboardAtoms {
+            clipboard: atoms[0],
+            targets: atoms[1],
+            utf8_string: atoms[2],
+            text: atoms[3],
+            text_plain: atoms[4],
+            incr: atoms[5],
+            xdnd_selection: atoms[6],
+            property: atoms[7],
+        })
+    }
+}
+
+/// Keeps track of the selections owned by this process, and answers requests for their contents.
+pub struct Clipboard {
+    xconn: Arc<XConnection>,
+    pub atoms: ClipboardAtoms,
+    // The first event code of the XFixes extension, if it's available
+    xfixes_event_base: Option<c_int>,
+    // The window owning each selection, along with the contents it serves
+    clipboard: Option<(ffi::Window, ClipboardContents)>,
+    primary: Option<(ffi::Window, ClipboardContents)>,
+    // The contents of the drag and drop operation started by one of our windows
+    drag: Option<(ffi::Window, ClipboardContents)>,
+    // The largest amount of data sent in a single property, above which transfers are incremental
+    max_chunk_size: usize,
+    incr_transfers: Vec<IncrTransfer>,
+}
+
+// The outcome of converting contents to the target asked for by a requestor
+enum Conversion {
+    Refused,
+    Written,
+    // Too large to be written at once, so it has to be sent incrementally
+    Incremental {
+        property_type: ffi::Atom,
+        data: Vec<u8>,
+    },
+}
+
+/// An incremental (`INCR`) transfer of data too large for a single request, which is sent in
+/// chunks as the requestor deletes the property holding the previous one.
+struct IncrTransfer {
+    requestor: ffi::Window,
+    property: ffi::Atom,
+    property_type: ffi::Atom,
+    data: Vec<u8>,
+    offset: usize,
+    // The events we selected on the requestor before the transfer, restored once it's over
+    event_mask: c_long,
+    last_activity: Instant,
+}
+
+impl Clipboard {
+    pub fn new(xconn: Arc<XConnection>) -> Result<Self, XError> {
+        let atoms = ClipboardAtoms::new(&xconn)?;
+        let xfixes_event_base = xconn.xfixes.as_ref().and_then(|xfixes| unsafe {
+            let mut event_base = 0;
+            let mut error_base = 0;
+            let has_extension =
+                (xfixes.XFixesQueryExtension)(xconn.display, &mut event_base, &mut error_base);
+            if has_extension == ffi::True {
+                // Selection tracking was introduced in XFixes 1.0, but the version still has to be
+                // negotiated before any requests are made.
+                let mut major = 1;
+                let minor = 0;
+                (xfixes.XFixesQueryVersion)(xconn.display, &mut major, &minor);
+                Some(event_base)
+            } else {
+                None
+            }
+        });
+        // The request size is in 4-byte units, and leaves room for the header of the request.
+        let max_request_size = unsafe { (xconn.xlib.XMaxRequestSize)(xconn.display) } as usize;
+        let max_chunk_size = (max_request_size * 4).saturating_sub(100).max(4096);
+        Ok(Clipboard {
+            xconn,
+            atoms,
+            xfixes_event_base,
+            clipboard: None,
+            primary: None,
+            drag: None,
+            max_chunk_size,
+            incr_transfers: Vec::new(),
+        })
+    }
+
+    pub fn selection_atom(&self, kind: ClipboardKind) -> ffi::Atom {
+        match kind {
+            ClipboardKind::Clipboard => self.atoms.clipboard,
+            ClipboardKind::Primary => ffi::XA_PRIMARY,
+        }
+    }
+
+    pub fn kind_for_selection(&self, selection: ffi::Atom) -> Option<ClipboardKind> {
+        if selection == self.atoms.clipboard {
+            Some(ClipboardKind::Clipboard)
+        } else if selection == ffi::XA_PRIMARY {
+            Some(ClipboardKind::Primary)
+        } else {
+            None
+        }
+    }
+
+    fn owner_mut(&mut self, kind: ClipboardKind) -> &mut Option<(ffi::Window, ClipboardContents)> {
+        match kind {
+            ClipboardKind::Clipboard => &mut self.clipboard,
+            ClipboardKind::Primary => &mut self.primary,
+        }
+    }
+
+    /// Returns the contents of a selection if it's currently owned by one of our windows.
+    pub fn owned_contents(&self, kind: ClipboardKind) -> Option<&ClipboardContents> {
+        let owner = match kind {
+            ClipboardKind::Clipboard => &self.clipboard,
+            ClipboardKind::Primary => &self.primary,
+        };
+        owner.as_ref().map(|(_, contents)| contents)
+    }
+
+    /// Whether changes to the selection owners are reported through XFixes.
+    pub fn tracks_owner_changes(&self) -> bool {
+        self.xfixes_event_base.is_some()
+    }
+
+    /// Requests XFixes selection notifications for both selections on `window`.
+    pub fn select_owner_change_input(&self, window: ffi::Window) {
+        if let (Some(xfixes), Some(_)) = (self.xconn.xfixes.as_ref(), self.xfixes_event_base) {
+            let mask = XFIXES_SET_SELECTION_OWNER_NOTIFY_MASK
+                | XFIXES_SELECTION_WINDOW_DESTROY_NOTIFY_MASK
+                | XFIXES_SELECTION_CLIENT_CLOSE_NOTIFY_MASK;
+            for &selection in &[self.atoms.clipboard, ffi::XA_PRIMARY] {
+                unsafe {
+                    (xfixes.XFixesSelectSelectionInput)(self.xconn.display, window, selection, mask)
+                };
+            }
+        }
+    }
+
+    /// If `event_type` is an XFixes selection notification, returns the window it was delivered
+    /// to, the new owner of the selection and which selection that is.
+    pub fn owner_change_event(
+        &self,
+        event_type: c_int,
+        xev: &ffi::XEvent,
+    ) -> Option<(ffi::Window, ffi::Window, ClipboardKind)> {
+        let event_base = self.xfixes_event_base?;
+        if event_type != event_base + XFIXES_SELECTION_NOTIFY {
+            return None;
+        }
+        let xev =
+            unsafe { &*(xev as *const ffi::XEvent as *const ffi::XFixesSelectionNotifyEvent) };
+        self.kind_for_selection(xev.selection)
+            .map(|kind| (xev.window, xev.owner, kind))
+    }
+
+    pub fn set_contents(
+        &mut self,
+        window: ffi::Window,
+        kind: ClipboardKind,
+        contents: ClipboardContents,
+    ) -> Result<(), XError> {
+        let selection = self.selection_atom(kind);
+        let owner = unsafe {
+            (self.xconn.xlib.XSetSelectionOwner)(
+                self.xconn.display,
+                selection,
+                window,
+                ffi::CurrentTime,
+            );
+            (self.xconn.xlib.XGetSelectionOwner)(self.xconn.display, selection)
+        };
+        self.xconn.check_errors()?;
+        if owner == window {
+            *self.owner_mut(kind) = Some((window, contents));
+        } else {
+            warn!("Failed to take ownership of the {:?} selection", kind);
+        }
+        Ok(())
+    }
+
+    /// Takes ownership of `XdndSelection` to serve the contents of a drag started by `window`.
+    pub fn set_drag_contents(
+        &mut self,
+        window: ffi::Window,
+        contents: ClipboardContents,
+    ) -> Result<(), XError> {
+        let owner = unsafe {
+            (self.xconn.xlib.XSetSelectionOwner)(
+                self.xconn.display,
+                self.atoms.xdnd_selection,
+                window,
+                ffi::CurrentTime,
+            );
+            (self.xconn.xlib.XGetSelectionOwner)(self.xconn.display, self.atoms.xdnd_selection)
+        };
+        self.xconn.check_errors()?;
+        if owner == window {
+            self.drag = Some((window, contents));
+        } else {
+            warn!("Failed to take ownership of the XdndSelection selection");
+        }
+        Ok(())
+    }
+
+    /// Returns the contents of the drag started by one of our windows, if any.
+    pub fn drag_contents(&self) -> Option<&ClipboardContents> {
+        self.drag.as_ref().map(|(_, contents)| contents)
+    }
+
+    pub fn clear_drag_contents(&mut self) {
+        self.drag = None;
+    }
+
+    /// Forgets the contents served by `window` for `selection`, returning which clipboard that was.
+    pub fn handle_selection_clear(
+        &mut self,
+        window: ffi::Window,
+        selection: ffi::Atom,
+    ) -> Option<ClipboardKind> {
+        if selection == self.atoms.xdnd_selection {
+            if self.drag.as_ref().map(|&(owner, _)| owner) == Some(window) {
+                self.drag = None;
+            }
+            return None;
+        }
+        let kind = self.kind_for_selection(selection)?;
+        let owner = self.owner_mut(kind);
+        if owner.as_ref().map(|&(owner, _)| owner) == Some(window) {
+            *owner = None;
+            Some(kind)
+        } else {
+            None
+        }
+    }
+
+    /// Forgets everything served by a window which is being destroyed.
+    pub fn remove_window(&mut self, window: ffi::Window) {
+        for owner in &mut [&mut self.clipboard, &mut self.primary, &mut self.drag] {
+            if owner.as_ref().map(|&(owner, _)| owner) == Some(window) {
+                **owner = None;
+            }
+        }
+    }
+
+    pub fn handle_selection_request(
+        &mut self,
+        request: &ffi::XSelectionRequestEvent,
+    ) -> Result<(), XError> {
+        // Obsolete clients may not specify a property, in which case the target name is used.
+        let property = if request.property == 0 {
+            request.target
+        } else {
+            request.property
+        };
+
+        let owner = if request.selection == self.atoms.xdnd_selection {
+            self.drag.as_ref()
+        } else {
+            self.kind_for_selection(request.selection)
+                .and_then(|kind| match kind {
+                    ClipboardKind::Clipboard => self.clipboard.as_ref(),
+                    ClipboardKind::Primary => self.primary.as_ref(),
+                })
+        };
+        let contents = owner
+            .filter(|&&(owner, _)| owner == request.owner)
+            .map(|(_, contents)| contents);
+
+        let conversion = match contents {
+            Some(contents) => self.write_contents(request, property, contents),
+            None => Conversion::Refused,
+        };
+        let served = match conversion {
+            Conversion::Refused => false,
+            Conversion::Written => true,
+            Conversion::Incremental {
+                property_type,
+                data,
+            } => self.start_incr_transfer(request.requestor, property, property_type, data),
+        };
+
+        let reply = ffi::XSelectionEvent {
+            type_: ffi::SelectionNotify,
+            serial: 0,
+            send_event: ffi::True,
+            display: self.xconn.display,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: if served { property } else { 0 },
+            time: request.time,
+        };
+        self.xconn
+            .send_event(request.requestor, None, reply)
+            .flush()
+    }
+
+    /// Writes `contents` converted to the requested target to the requestor's property, unless
+    /// they're too large to be written at once.
+    fn write_contents(
+        &self,
+        request: &ffi::XSelectionRequestEvent,
+        property: ffi::Atom,
+        contents: &ClipboardContents,
+    ) -> Conversion {
+        if request.target == self.atoms.targets {
+            let targets = self.targets(contents);
+            self.xconn
+                .change_property(
+                    request.requestor,
+                    property,
+                    ffi::XA_ATOM,
+                    util::PropMode::Replace,
+                    &targets,
+                )
+                .queue();
+            return Conversion::Written;
+        }
+
+        let data = self
+            .xconn
+            .get_atom_name(request.target)
+            .and_then(|name| contents.get(&name));
+        if let Some(data) = data {
+            let property_type = if request.target == self.atoms.text {
+                self.atoms.utf8_string
+            } else {
+                request.target
+            };
+            if data.len() > self.max_chunk_size {
+                return Conversion::Incremental {
+                    property_type,
+                    data: data.to_vec(),
+                };
+            }
+            self.xconn
+                .change_property(
+                    request.requestor,
+                    property,
+                    property_type,
+                    util::PropMode::Replace,
+                    data,
+                )
+                .queue();
+            Conversion::Written
+        } else {
+            Conversion::Refused
+        }
+    }
+
+    // Announces an incremental transfer of `data`, whose chunks are sent by
+    // `handle_property_notify` as the requestor asks for them. Returns whether it could be started.
+    fn start_incr_transfer(
+        &mut self,
+        requestor: ffi::Window,
+        property: ffi::Atom,
+        property_type: ffi::Atom,
+        data: Vec<u8>,
+    ) -> bool {
+        // A requestor asking for another transfer has given up on the ones it didn't finish.
+        self.incr_transfers
+            .retain(|transfer| transfer.requestor != requestor || transfer.property != property);
+
+        // The requestor asks for each chunk by deleting the property, which we have to be told
+        // about without losing the events we already selected if the requestor is our own.
+        let event_mask = unsafe {
+            let mut attributes = MaybeUninit::<ffi::XWindowAttributes>::uninit();
+            (self.xconn.xlib.XGetWindowAttributes)(
+                self.xconn.display,
+                requestor,
+                attributes.as_mut_ptr(),
+            );
+            if self.xconn.check_errors().is_err() {
+                return false;
+            }
+            let event_mask = attributes.assume_init().your_event_mask;
+            (self.xconn.xlib.XSelectInput)(
+                self.xconn.display,
+                requestor,
+                event_mask | ffi::PropertyChangeMask,
+            );
+            event_mask
+        };
+        // The value of an `INCR` property is a lower bound of the size of the data.
+        self.xconn
+            .change_property(
+                requestor,
+                property,
+                self.atoms.incr,
+                util::PropMode::Replace,
+                &[data.len() as c_ulong],
+            )
+            .queue();
+        self.incr_transfers.push(IncrTransfer {
+            requestor,
+            property,
+            property_type,
+            data,
+            offset: 0,
+            event_mask,
+            last_activity: Instant::now(),
+        });
+        true
+    }
+
+    /// Sends the next chunk of an incremental transfer if `xev` reports that its requestor
+    /// deleted the previous one. Returns whether the event belonged to a transfer.
+    pub fn handle_property_notify(&mut self, xev: &ffi::XPropertyEvent) -> bool {
+        let now = Instant::now();
+        let (transfers, abandoned) = std::mem::take(&mut self.incr_transfers)
+            .into_iter()
+            .partition(|transfer| now - transfer.last_activity < INCR_TIMEOUT);
+        self.incr_transfers = transfers;
+        for transfer in abandoned {
+            self.restore_event_mask(&transfer);
+        }
+
+        let index = self
+            .incr_transfers
+            .iter()
+            .position(|transfer| transfer.requestor == xev.window && transfer.property == xev.atom);
+        let index = match index {
+            Some(index) => index,
+            None => return false,
+        };
+        if xev.state != ffi::PropertyDelete {
+            return true;
+        }
+
+        let transfer = &mut self.incr_transfers[index];
+        let end = cmp::min(transfer.offset + self.max_chunk_size, transfer.data.len());
+        // The transfer ends with an empty chunk.
+        self.xconn
+            .change_property(
+                transfer.requestor,
+                transfer.property,
+                transfer.property_type,
+                util::PropMode::Replace,
+                &transfer.data[transfer.offset..end],
+            )
+            .queue();
+        if transfer.offset == end {
+            let transfer = self.incr_transfers.remove(index);
+            self.restore_event_mask(&transfer);
+        } else {
+            transfer.offset = end;
+            transfer.last_activity = now;
+        }
+        let _ = self.xconn.flush_requests();
+        true
+    }
+
+    fn restore_event_mask(&self, transfer: &IncrTransfer) {
+        unsafe {
+            (self.xconn.xlib.XSelectInput)(
+                self.xconn.display,
+                transfer.requestor,
+                transfer.event_mask,
+            );
+        }
+        // The requestor may have been destroyed in the meantime.
+        let _ = self.xconn.check_errors();
+    }
+
+    /// The list of types advertised to the targets of a drag offering `contents`.
+    pub fn drag_types(&self, contents: &ClipboardContents) -> Vec<ffi::Atom> {
+        let mut types = self.targets(contents);
+        types.retain(|&target| target != self.atoms.targets);
+        types
+    }
+
+    // The list of conversion targets offered for `contents`
+    fn targets(&self, contents: &ClipboardContents) -> Vec<ffi::Atom> {
+        let mut targets = vec![self.atoms.targets];
+        for mime_type in contents.mime_types() {
+            if let Ok(name) = CString::new(mime_type) {
+                targets.push(self.xconn.get_atom(name));
+            }
+        }
+        if contents.text().is_some() {
+            targets.extend_from_slice(&[
+                self.atoms.utf8_string,
+                self.atoms.text,
+                self.atoms.text_plain,
+                ffi::XA_STRING,
+            ]);
+        }
+        targets.dedup();
+        targets
+    }
+
+    /// Returns the target atom used to request data in `mime_type`.
+    pub fn target_for_mime_type(&self, mime_type: &str) -> Option<ffi::Atom> {
+        if is_text_mime_type(mime_type) {
+            Some(self.atoms.utf8_string)
+        } else {
+            CString::new(mime_type)
+                .ok()
+                .map(|name| self.xconn.get_atom(name))
+        }
+    }
+}
+
+struct PendingConversion {
+    window: ffi::Window,
+    selection: ffi::Atom,
+}
+
+unsafe extern "C" fn is_conversion_reply(
+    _display: *mut ffi::Display,
+    event: *mut ffi::XEvent,
+    arg: *mut c_char,
+) -> c_int {
+    let pending = &*(arg as *const PendingConversion);
+    let event = &*event;
+    if event.get_type() != ffi::SelectionNotify {
+        return ffi::False;
+    }
+    let xsel: &ffi::XSelectionEvent = event.as_ref();
+    (xsel.requestor == pending.window && xsel.selection == pending.selection) as c_int
+}
+
+struct PendingChunk {
+    window: ffi::Window,
+    property: ffi::Atom,
+}
+
+unsafe extern "C" fn is_new_chunk(
+    _display: *mut ffi::Display,
+    event: *mut ffi::XEvent,
+    arg: *mut c_char,
+) -> c_int {
+    let pending = &*(arg as *const PendingChunk);
+    let event = &*event;
+    if event.get_type() != ffi::PropertyNotify {
+        return ffi::False;
+    }
+    let xprop: &ffi::XPropertyEvent = event.as_ref();
+    (xprop.window == pending.window
+        && xprop.atom == pending.property
+        && xprop.state == ffi::PropertyNewValue) as c_int
+}
+
+/// Waits for the first event matching `predicate` to be queued, and removes it from the queue.
+/// Returns `None` if none arrived by `deadline`.
+fn wait_for_event<A>(
+    xconn: &XConnection,
+    predicate: unsafe extern "C" fn(*mut ffi::Display, *mut ffi::XEvent, *mut c_char) -> c_int,
+    arg: &A,
+    deadline: Instant,
+) -> Option<ffi::XEvent> {
+    loop {
+        let mut xev = MaybeUninit::uninit();
+        let found = unsafe {
+            (xconn.xlib.XCheckIfEvent)(
+                xconn.display,
+                xev.as_mut_ptr(),
+                Some(predicate),
+                arg as *const A as *mut c_char,
+            )
+        };
+        if found == ffi::True {
+            return Some(unsafe { xev.assume_init() });
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return None;
+        }
+        let mut fd = libc::pollfd {
+            fd: xconn.x11_fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        unsafe { libc::poll(&mut fd, 1, (deadline - now).as_millis() as c_int) };
+    }
+}
+
+/// Asks the owner of `selection` to convert it to `target`, and waits for the result.
+///
+/// Only the events answering this request are removed from the event queue, so any other events
+/// which arrive in the meantime are left for the event loop.
+pub fn convert_selection(
+    xconn: &XConnection,
+    atoms: &ClipboardAtoms,
+    window: ffi::Window,
+    selection: ffi::Atom,
+    target: ffi::Atom,
+) -> Result<Option<Vec<u8>>, XError> {
+    request_conversion(
+        xconn,
+        window,
+        selection,
+        target,
+        atoms.property,
+        ffi::CurrentTime,
+    )?;
+
+    let pending = PendingConversion { window, selection };
+    let deadline = Instant::now() + CONVERSION_TIMEOUT;
+    let reply: ffi::XSelectionEvent =
+        match wait_for_event(xconn, is_conversion_reply, &pending, deadline) {
+            Some(xev) => From::from(xev),
+            None => {
+                warn!("Timed out waiting for the selection owner to convert the selection");
+                return Ok(None);
+            }
+        };
+    read_conversion(xconn, atoms, &reply, target)
+}
+
+/// Asks the owner of `selection` to convert it to `target` and to write the result into
+/// `property` of `window`, without waiting for the `SelectionNotify` event telling it did.
+pub fn request_conversion(
+    xconn: &XConnection,
+    window: ffi::Window,
+    selection: ffi::Atom,
+    target: ffi::Atom,
+    property: ffi::Atom,
+    time: ffi::Time,
+) -> Result<(), XError> {
+    unsafe {
+        (xconn.xlib.XDeleteProperty)(xconn.display, window, property);
+        (xconn.xlib.XConvertSelection)(xconn.display, selection, target, property, window, time);
+    }
+    xconn.flush_requests()
+}
+
+/// Reads the data announced by the `SelectionNotify` event answering a conversion to `target`.
+///
+/// Incremental transfers are received before returning.
+pub fn read_conversion(
+    xconn: &XConnection,
+    atoms: &ClipboardAtoms,
+    reply: &ffi::XSelectionEvent,
+    target: ffi::Atom,
+) -> Result<Option<Vec<u8>>, XError> {
+    if reply.property == 0 {
+        // The owner refused the conversion, or there is no owner.
+        return Ok(None);
+    }
+
+    let window = reply.requestor;
+    match read_property(xconn, atoms, window, reply.property, target) {
+        Err(util::GetPropertyError::TypeMismatch(actual_type)) if actual_type == atoms.incr => {
+            read_incr(xconn, atoms, window, reply.property, target)
+        }
+        Ok(data) => Ok(Some(data)),
+        Err(util::GetPropertyError::XError(err)) => Err(err),
+        Err(_) => Ok(None),
+    }
+}
+
+// Reads and deletes a property holding converted data, or an `INCR` property announcing an
+// incremental transfer, which is left for `read_incr`.
+fn read_property(
+    xconn: &XConnection,
+    atoms: &ClipboardAtoms,
+    window: ffi::Window,
+    property: ffi::Atom,
+    target: ffi::Atom,
+) -> Result<Vec<u8>, util::GetPropertyError> {
+    let result = match xconn.get_property::<c_uchar>(window, property, target) {
+        // Owners are free to answer with a different (usually more specific) type.
+        Err(util::GetPropertyError::TypeMismatch(actual_type))
+            if actual_type != 0 && actual_type != atoms.incr =>
+        {
+            xconn.get_property::<c_uchar>(window, property, actual_type)
+        }
+        result => result,
+    };
+    if !matches!(result, Err(ref err) if err.is_actual_property_type(atoms.incr)) {
+        unsafe { (xconn.xlib.XDeleteProperty)(xconn.display, window, property) };
+    }
+    result
+}
+
+// Receives the chunks of an incremental transfer, which the owner sends one at a time as we
+// delete the property holding the previous one. The transfer ends with an empty chunk.
+fn read_incr(
+    xconn: &XConnection,
+    atoms: &ClipboardAtoms,
+    window: ffi::Window,
+    property: ffi::Atom,
+    target: ffi::Atom,
+) -> Result<Option<Vec<u8>>, XError> {
+    let pending = PendingChunk { window, property };
+    // Forget the change of the property announcing the transfer, so it isn't taken for a chunk.
+    while wait_for_event(xconn, is_new_chunk, &pending, Instant::now()).is_some() {}
+    unsafe { (xconn.xlib.XDeleteProperty)(xconn.display, window, property) };
+    xconn.flush_requests()?;
+
+    let mut data = Vec::new();
+    loop {
+        let deadline = Instant::now() + CONVERSION_TIMEOUT;
+        if wait_for_event(xconn, is_new_chunk, &pending, deadline).is_none() {
+            warn!("Timed out waiting for the selection owner to send an incremental transfer");
+            return Ok(None);
+        }
+        match read_property(xconn, atoms, window, property, target) {
+            Ok(ref chunk) if chunk.is_empty() => return Ok(Some(data)),
+            Ok(chunk) => data.extend_from_slice(&chunk),
+            Err(util::GetPropertyError::XError(err)) => return Err(err),
+            Err(_) => return Ok(None),
+        }
+        // Deleting the property asks for the next chunk.
+        xconn.flush_requests()?;
+    }
+}
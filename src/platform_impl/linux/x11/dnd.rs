@@ -1,14 +1,21 @@
 use std::{
-    io,
     os::raw::*,
-    path::{Path, PathBuf},
-    str::Utf8Error,
+    path::PathBuf,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
-use percent_encoding::percent_decode;
-
 use super::{ffi, util, XConnection, XError};
+use crate::{
+    dpi::PhysicalPosition,
+    event::DragAction,
+    platform_impl::platform::dnd::{self, DndDataParseError},
+};
+
+// How long the target of a drop may take to send `XdndFinished` before the drag is cancelled.
+const FINISH_TIMEOUT: Duration = Duration::from_secs(5);
+// How long the source of a drop on one of our windows may take to convert its data.
+const CONVERSION_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug)]
 pub struct DndAtoms {
@@ -18,12 +25,16 @@ pub struct DndAtoms {
     pub drop: ffi::Atom,
     pub position: ffi::Atom,
     pub status: ffi::Atom,
-    pub action_private: ffi::Atom,
+    pub action_copy: ffi::Atom,
+    pub action_move: ffi::Atom,
+    pub action_link: ffi::Atom,
     pub selection: ffi::Atom,
     pub finished: ffi::Atom,
     pub type_list: ffi::Atom,
     pub uri_list: ffi::Atom,
     pub none: ffi::Atom,
+    // The property on our own windows that sources write the data of a drop into
+    pub drop_data: ffi::Atom,
 }
 
 impl DndAtoms {
@@ -35,12 +46,15 @@ impl DndAtoms {
             b"XdndDrop\0".as_ptr() as *mut c_char,
             b"XdndPosition\0".as_ptr() as *mut c_char,
             b"XdndStatus\0".as_ptr() as *mut c_char,
-            b"XdndActionPrivate\0".as_ptr() as *mut c_char,
+            b"XdndActionCopy\0".as_ptr() as *mut c_char,
+            b"XdndActionMove\0".as_ptr() as *mut c_char,
+            b"XdndActionLink\0".as_ptr() as *mut c_char,
             b"XdndSelection\0".as_ptr() as *mut c_char,
             b"XdndFinished\0".as_ptr() as *mut c_char,
             b"XdndTypeList\0".as_ptr() as *mut c_char,
             b"text/uri-list\0".as_ptr() as *mut c_char,
             b"None\0".as_ptr() as *mut c_char,
+            b"_WINIT_DROP_DATA\0".as_ptr() as *mut c_char,
         ];
         let atoms = unsafe { xconn.get_atoms(&names) }?;
         Ok(DndAtoms {
@@ -50,41 +64,46 @@ impl DndAtoms {
             drop: atoms[3],
             position: atoms[4],
             status: atoms[5],
-            action_private: atoms[6],
-            selection: atoms[7],
-            finished: atoms[8],
-            type_list: atoms[9],
-            uri_list: atoms[10],
-            none: atoms[11],
+            action_copy: atoms[6],
+            action_move: atoms[7],
+            action_link: atoms[8],
+            selection: atoms[9],
+            finished: atoms[10],
+            type_list: atoms[11],
+            uri_list: atoms[12],
+            none: atoms[13],
+            drop_data: atoms[14],
         })
     }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum DndState {
-    Accepted,
-    Rejected,
-}
 
-#[derive(Debug)]
-pub enum DndDataParseError {
-    EmptyData,
-    InvalidUtf8(Utf8Error),
-    HostnameSpecified(String),
-    UnexpectedProtocol(String),
-    UnresolvablePath(io::Error),
-}
+    /// Returns the action an action atom stands for. XDND allows sources to propose other
+    /// actions, which are treated as copies.
+    pub fn action(&self, atom: ffi::Atom) -> DragAction {
+        if atom == self.action_move {
+            DragAction::Move
+        } else if atom == self.action_link {
+            DragAction::Link
+        } else {
+            DragAction::Copy
+        }
+    }
 
-impl From<Utf8Error> for DndDataParseError {
-    fn from(e: Utf8Error) -> Self {
-        DndDataParseError::InvalidUtf8(e)
+    pub fn action_atom(&self, action: DragAction) -> ffi::Atom {
+        match action {
+            DragAction::Copy => self.action_copy,
+            DragAction::Move => self.action_move,
+            DragAction::Link => self.action_link,
+        }
     }
 }
 
-impl From<io::Error> for DndDataParseError {
-    fn from(e: io::Error) -> Self {
-        DndDataParseError::UnresolvablePath(e)
-    }
+// The version of the XDND protocol we implement.
+const XDND_VERSION: c_long = 5;
+
+#[derive(Debug, Clone, Copy)]
+pub enum DndState {
+    Accepted(DragAction),
+    Rejected,
 }
 
 pub struct Dnd {
@@ -93,10 +112,83 @@ pub struct Dnd {
     // Populated by XdndEnter event handler
     pub version: Option<c_long>,
     pub type_list: Option<Vec<c_ulong>>,
+    pub mime_types: Vec<String>,
     // Populated by XdndPosition event handler
     pub source_window: Option<c_ulong>,
+    pub position: Option<PhysicalPosition<f64>>,
+    pub action: DragAction,
     // Populated by SelectionNotify event handler (triggered by XdndPosition event handler)
+    pub data: Option<Vec<c_uchar>>,
     pub result: Option<Result<Vec<PathBuf>, DndDataParseError>>,
+    // Populated by XdndDrop event handler, and left alone by `reset`
+    pub pending_drop: Option<Box<PendingDrop>>,
+}
+
+/// A drag and drop operation started by one of our windows.
+pub struct DragSource {
+    pub window: ffi::Window,
+    // The master pointer grabbed for the duration of the drag
+    pub device_id: c_int,
+    pub types: Vec<ffi::Atom>,
+    // The actions targets may perform, the first of which is proposed to them
+    pub actions: Vec<DragAction>,
+    // The XDND aware window under the cursor, along with the version we talk to it in
+    pub target: Option<(ffi::Window, c_long)>,
+    // Set while waiting for the target to answer an `XdndPosition` message
+    pub awaiting_status: bool,
+    // The last motion which happened while waiting, in root coordinates, with its timestamp
+    pub pending_position: Option<(c_int, c_int, ffi::Time)>,
+    pub accepted: Option<DragAction>,
+    // When the drag was dropped on the target, which then has to send `XdndFinished`
+    pub dropped: Option<Instant>,
+}
+
+impl DragSource {
+    pub fn new(
+        window: ffi::Window,
+        device_id: c_int,
+        types: Vec<ffi::Atom>,
+        actions: Vec<DragAction>,
+    ) -> Self {
+        DragSource {
+            window,
+            device_id,
+            types,
+            actions,
+            target: None,
+            awaiting_status: false,
+            pending_position: None,
+            accepted: None,
+            dropped: None,
+        }
+    }
+
+    /// When to give up on the target of the drop sending `XdndFinished`.
+    pub fn finish_deadline(&self) -> Option<Instant> {
+        self.dropped.map(|dropped| dropped + FINISH_TIMEOUT)
+    }
+}
+
+/// A drop on one of our windows, whose source is converting the data the window accepted.
+pub struct PendingDrop {
+    pub window: ffi::Window,
+    pub source_window: ffi::Window,
+    pub mime_type: String,
+    pub target: ffi::Atom,
+    pub position: PhysicalPosition<f64>,
+    pub action: DragAction,
+    // The timestamp of the drop, which the conversion is requested with
+    pub time: ffi::Time,
+    // The files reported by `HoveredFile`, if any
+    pub paths: Option<Vec<PathBuf>>,
+    pub requested: Instant,
+}
+
+impl PendingDrop {
+    /// When to give up on the source converting the data.
+    pub fn deadline(&self) -> Instant {
+        self.requested + CONVERSION_TIMEOUT
+    }
 }
 
 impl Dnd {
@@ -107,15 +199,24 @@ impl Dnd {
             atoms,
             version: None,
             type_list: None,
+            mime_types: Vec::new(),
             source_window: None,
+            position: None,
+            action: DragAction::Copy,
+            data: None,
             result: None,
+            pending_drop: None,
         })
     }
 
     pub fn reset(&mut self) {
         self.version = None;
         self.type_list = None;
+        self.mime_types.clear();
         self.source_window = None;
+        self.position = None;
+        self.action = DragAction::Copy;
+        self.data = None;
         self.result = None;
     }
 
@@ -126,7 +227,7 @@ impl Dnd {
         state: DndState,
     ) -> Result<(), XError> {
         let (accepted, action) = match state {
-            DndState::Accepted => (1, self.atoms.action_private as c_long),
+            DndState::Accepted(action) => (1, self.atoms.action_atom(action) as c_long),
             DndState::Rejected => (0, self.atoms.none as c_long),
         };
         self.xconn
@@ -147,7 +248,7 @@ impl Dnd {
         state: DndState,
     ) -> Result<(), XError> {
         let (accepted, action) = match state {
-            DndState::Accepted => (1, self.atoms.action_private as c_long),
+            DndState::Accepted(action) => (1, self.atoms.action_atom(action) as c_long),
             DndState::Rejected => (0, self.atoms.none as c_long),
         };
         self.xconn
@@ -189,31 +290,137 @@ impl Dnd {
     }
 
     pub fn parse_data(&self, data: &mut Vec<c_uchar>) -> Result<Vec<PathBuf>, DndDataParseError> {
-        if !data.is_empty() {
-            let mut path_list = Vec::new();
-            let decoded = percent_decode(data).decode_utf8()?.into_owned();
-            for uri in decoded.split("\r\n").filter(|u| !u.is_empty()) {
-                // The format is specified as protocol://host/path
-                // However, it's typically simply protocol:///path
-                let path_str = if uri.starts_with("file://") {
-                    let path_str = uri.replace("file://", "");
-                    if !path_str.starts_with('/') {
-                        // A hostname is specified
-                        // Supporting this case is beyond the scope of my mental health
-                        return Err(DndDataParseError::HostnameSpecified(path_str));
-                    }
-                    path_str
+        dnd::parse_uri_list(data)
+    }
+
+    /// Finds the XDND aware window at the given root coordinates, along with the protocol
+    /// version to use with it.
+    pub fn find_target(
+        &self,
+        root: ffi::Window,
+        x: c_int,
+        y: c_int,
+    ) -> Option<(ffi::Window, c_long)> {
+        let mut window = root;
+        loop {
+            let mut child = 0;
+            let (mut child_x, mut child_y) = (0, 0);
+            unsafe {
+                (self.xconn.xlib.XTranslateCoordinates)(
+                    self.xconn.display,
+                    root,
+                    window,
+                    x,
+                    y,
+                    &mut child_x,
+                    &mut child_y,
+                    &mut child,
+                );
+            }
+            if child == 0 {
+                return None;
+            }
+            // Toplevels are usually reparented by the window manager, so look below their frames.
+            let aware: Result<Vec<ffi::Atom>, _> =
+                self.xconn
+                    .get_property(child, self.atoms.aware, ffi::XA_ATOM);
+            if let Some(&version) = aware.ok().as_ref().and_then(|aware| aware.first()) {
+                // Versions before 3 aren't compatible with the current protocol.
+                return if version >= 3 {
+                    Some((child, (version as c_long).min(XDND_VERSION)))
                 } else {
-                    // Only the file protocol is supported
-                    return Err(DndDataParseError::UnexpectedProtocol(uri.to_owned()));
+                    None
                 };
-
-                let path = Path::new(&path_str).canonicalize()?;
-                path_list.push(path);
             }
-            Ok(path_list)
-        } else {
-            Err(DndDataParseError::EmptyData)
+            window = child;
         }
     }
+
+    // The messages sent to the target of our own drags wait for the server, so that a target which
+    // was destroyed in the meantime fails them rather than a later request.
+    pub unsafe fn send_enter(
+        &self,
+        this_window: c_ulong,
+        target_window: c_ulong,
+        version: c_long,
+        types: &[ffi::Atom],
+    ) -> Result<(), XError> {
+        // The full list is read from the `XdndTypeList` property when it doesn't fit.
+        let has_more_types = (types.len() > 3) as c_long;
+        let type_at = |i: usize| types.get(i).cloned().unwrap_or(0) as c_long;
+        self.xconn
+            .send_client_msg(
+                target_window,
+                target_window,
+                self.atoms.enter,
+                None,
+                [
+                    this_window as c_long,
+                    (version << 24) | has_more_types,
+                    type_at(0),
+                    type_at(1),
+                    type_at(2),
+                ],
+            )
+            .sync()
+    }
+
+    pub unsafe fn send_position(
+        &self,
+        this_window: c_ulong,
+        target_window: c_ulong,
+        (x, y): (c_int, c_int),
+        time: ffi::Time,
+        action: DragAction,
+    ) -> Result<(), XError> {
+        let packed_coordinates = ((x as c_long & 0xffff) << 16) | (y as c_long & 0xffff);
+        self.xconn
+            .send_client_msg(
+                target_window,
+                target_window,
+                self.atoms.position,
+                None,
+                [
+                    this_window as c_long,
+                    0,
+                    packed_coordinates,
+                    time as c_long,
+                    self.atoms.action_atom(action) as c_long,
+                ],
+            )
+            .sync()
+    }
+
+    pub unsafe fn send_leave(
+        &self,
+        this_window: c_ulong,
+        target_window: c_ulong,
+    ) -> Result<(), XError> {
+        self.xconn
+            .send_client_msg(
+                target_window,
+                target_window,
+                self.atoms.leave,
+                None,
+                [this_window as c_long, 0, 0, 0, 0],
+            )
+            .sync()
+    }
+
+    pub unsafe fn send_drop(
+        &self,
+        this_window: c_ulong,
+        target_window: c_ulong,
+        time: ffi::Time,
+    ) -> Result<(), XError> {
+        self.xconn
+            .send_client_msg(
+                target_window,
+                target_window,
+                self.atoms.drop,
+                None,
+                [this_window as c_long, 0, time as c_long, 0, 0],
+            )
+            .sync()
+    }
 }
@@ -1,13 +1,21 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc, slice, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::CString,
+    rc::Rc,
+    slice,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use libc::{c_char, c_int, c_long, c_uint, c_ulong};
+use libc::{c_char, c_int, c_long, c_ulong};
 
 use parking_lot::MutexGuard;
 
 use super::{
-    events, ffi, get_xtarget, mkdid, mkwid, monitor, util, Device, DeviceId, DeviceInfo, Dnd,
-    DndState, GenericEventCookie, ImeReceiver, ScrollOrientation, UnownedWindow, WindowId,
-    XExtension,
+    clipboard, ffi, get_xtarget, mkdid, mkwid, monitor, util, Device, DeviceId, DeviceInfo, Dnd,
+    DndState, DragSource, GenericEventCookie, ImeEvent, ImeEventReceiver, ImeReceiver, ImeRequest,
+    PendingDrop, ScrollOrientation, UnownedWindow, WindowId, XConnection, XExtension,
 };
 
 use util::modifiers::{ModifierKeyState, ModifierKeymap};
@@ -15,23 +23,77 @@ use util::modifiers::{ModifierKeyState, ModifierKeymap};
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize},
     event::{
-        DeviceEvent, ElementState, Event, KeyboardInput, ModifiersState, TouchPhase, WindowEvent,
+        DeviceEvent, DragAction, ElementState, Event, KeyboardInput, ModifiersState, PenButtons,
+        PenPhase, TouchPhase, WindowEvent,
     },
     event_loop::EventLoopWindowTarget as RootELW,
+    monitor::MonitorHandle as RootMonitorHandle,
+    platform_impl::platform::{
+        dnd::{default_response, parse_uri_list, URI_LIST},
+        keycode::keycode_from_evdev,
+        keysym,
+        xkb::KbState,
+        MonitorHandle as PlatformMonitorHandle,
+    },
+    window::ClipboardContents,
 };
 
+/// The xkbcommon state of the core keyboard, which is used to translate keys.
+pub(super) struct XkbKeyboard {
+    pub(super) state: KbState,
+    // The event code of every XKB event, which are told apart by their `xkb_type`.
+    pub(super) event_base: c_int,
+    pub(super) device_id: i32,
+}
+
+impl XkbKeyboard {
+    fn process_event(&mut self, xconn: &XConnection, xev: &ffi::XEvent) {
+        let xev: &ffi::XkbAnyEvent = unsafe { &*(xev as *const ffi::XEvent as *const _) };
+        if xev.device as i32 != self.device_id {
+            return;
+        }
+        match xev.xkb_type {
+            ffi::XkbStateNotify => {
+                let xev: &ffi::XkbStateNotifyEvent = unsafe { &*(xev as *const _ as *const _) };
+                self.state.update_mask(
+                    xev.base_mods,
+                    xev.latched_mods,
+                    xev.locked_mods,
+                    xev.base_group as u32,
+                    xev.latched_group as u32,
+                    xev.locked_group as u32,
+                );
+            }
+            ffi::XkbNewKeyboardNotify | ffi::XkbMapNotify => {
+                let connection = unsafe { (xconn.xlib_xcb.XGetXCBConnection)(xconn.display) };
+                if !self.state.reload_x11(connection, self.device_id) {
+                    warn!("Failed to reload the keymap of the core keyboard");
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(super) struct EventProcessor<T: 'static> {
     pub(super) dnd: Dnd,
     pub(super) ime_receiver: ImeReceiver,
+    pub(super) ime_event_receiver: ImeEventReceiver,
+    // Window whose input method is currently composing a preedit string
+    pub(super) composing_window: Option<ffi::Window>,
     pub(super) randr_event_offset: c_int,
     pub(super) devices: RefCell<HashMap<DeviceId, Device>>,
     pub(super) xi2ext: XExtension,
     pub(super) target: Rc<RootELW<T>>,
     pub(super) mod_keymap: ModifierKeymap,
     pub(super) device_mod_state: ModifierKeyState,
+    // `None` if xkbcommon-x11 isn't available, in which case keys are translated by Xlib.
+    pub(super) xkb: Option<XkbKeyboard>,
     // Number of touch events currently in progress
     pub(super) num_touch: u32,
     pub(super) first_touch: Option<u64>,
+    // Scale of the pinch gesture in progress, as of its previous event
+    pub(super) pinch_scale: f64,
     // Currently focused window belonging to this process
     pub(super) active_window: Option<ffi::Window>,
 }
@@ -126,6 +188,8 @@ impl<T: 'static> EventProcessor<T> {
                 })
             }
         {
+            // The preedit callbacks are called while filtering.
+            self.process_ime_events(&mut callback);
             return;
         }
 
@@ -176,7 +240,25 @@ impl<T: 'static> EventProcessor<T> {
                 let window = client_msg.window;
                 let window_id = mkwid(window);
 
-                if client_msg.data.get_long(0) as ffi::Atom == wt.wm_delete_window {
+                if window == wt.root && wt.xsettings.borrow().is_manager_message(client_msg) {
+                    // A new XSETTINGS manager took over.
+                    self.update_xsettings(&mut callback);
+                } else if client_msg.message_type == wt.net_wm_frame_drawn {
+                    // The counter value and the timestamp in microseconds, each split in its low
+                    // and high 32 bits.
+                    let value = (client_msg.data.get_long(0) as u64 & 0xffff_ffff)
+                        | (client_msg.data.get_long(1) as u64) << 32;
+                    let time = (client_msg.data.get_long(2) as u64 & 0xffff_ffff)
+                        | (client_msg.data.get_long(3) as u64) << 32;
+                    if self.with_window(window, |window| window.frame_drawn(value)) == Some(true) {
+                        callback(Event::WindowEvent {
+                            window_id,
+                            event: WindowEvent::FrameCallback {
+                                presentation_time: Duration::from_micros(time),
+                            },
+                        });
+                    }
+                } else if client_msg.data.get_long(0) as ffi::Atom == wt.wm_delete_window {
                     callback(Event::WindowEvent {
                         window_id,
                         event: WindowEvent::CloseRequested,
@@ -191,7 +273,97 @@ impl<T: 'static> EventProcessor<T> {
                             *response_msg,
                         )
                         .queue();
+                } else if client_msg.message_type == self.dnd.atoms.status {
+                    // The target of the drag we started answered our last `XdndPosition`.
+                    let target_window = client_msg.data.get_long(0) as ffi::Window;
+                    let mut event = None;
+                    if let Some(ref mut source) = *wt.drag_source.lock().unwrap() {
+                        if source.window != window
+                            || source.target.map(|(target, _)| target) != Some(target_window)
+                        {
+                            return;
+                        }
+                        let accepted = if client_msg.data.get_long(1) & 1 == 1 {
+                            let action = self
+                                .dnd
+                                .atoms
+                                .action(client_msg.data.get_long(4) as ffi::Atom);
+                            // Targets may answer with an action we didn't allow.
+                            if source.actions.contains(&action) {
+                                Some(action)
+                            } else {
+                                Some(source.actions[0])
+                            }
+                        } else {
+                            None
+                        };
+                        if accepted != source.accepted {
+                            source.accepted = accepted;
+                            event = Some(WindowEvent::DragSourceAction(accepted));
+                        }
+                        source.awaiting_status = false;
+                        if let Some((x, y, time)) = source.pending_position.take() {
+                            let sent = unsafe {
+                                self.dnd.send_position(
+                                    window,
+                                    target_window,
+                                    (x, y),
+                                    time,
+                                    source.actions[0],
+                                )
+                            };
+                            if sent.is_ok() {
+                                source.awaiting_status = true;
+                            } else {
+                                // The target was destroyed meanwhile.
+                                source.target = None;
+                                if source.accepted.take().is_some() {
+                                    event = Some(WindowEvent::DragSourceAction(None));
+                                }
+                            }
+                        }
+                    }
+                    if let Some(event) = event {
+                        callback(Event::WindowEvent { window_id, event });
+                    }
+                } else if client_msg.message_type == self.dnd.atoms.finished {
+                    // The target of the drag we started is done with the data.
+                    let target_window = client_msg.data.get_long(0) as ffi::Window;
+                    let event = {
+                        let mut drag_source = wt.drag_source.lock().unwrap();
+                        let action = match *drag_source {
+                            Some(ref source)
+                                if source.dropped.is_some()
+                                    && source.window == window
+                                    && source.target.map(|(target, _)| target)
+                                        == Some(target_window) =>
+                            {
+                                let version = source.target.map_or(0, |(_, version)| version);
+                                // Whether the drop succeeded is only told since version 5.
+                                if version < 5 {
+                                    source.accepted
+                                } else if client_msg.data.get_long(1) & 1 == 1 {
+                                    Some(
+                                        self.dnd
+                                            .atoms
+                                            .action(client_msg.data.get_long(2) as ffi::Atom),
+                                    )
+                                } else {
+                                    None
+                                }
+                            }
+                            _ => return,
+                        };
+                        *drag_source = None;
+                        wt.clipboard.lock().unwrap().clear_drag_contents();
+                        match action {
+                            Some(action) => WindowEvent::DragSourceFinished(action),
+                            None => WindowEvent::DragSourceCancelled,
+                        }
+                    };
+                    callback(Event::WindowEvent { window_id, event });
                 } else if client_msg.message_type == self.dnd.atoms.enter {
+                    self.dnd.reset();
                     let source_window = client_msg.data.get_long(0) as c_ulong;
                     let flags = client_msg.data.get_long(1);
                     let version = flags >> 24;
@@ -208,93 +380,229 @@ impl<T: 'static> EventProcessor<T> {
                     {
                         self.dnd.type_list = Some(more_types);
                     }
+
+                    let mime_types: Vec<String> = self
+                        .dnd
+                        .type_list
+                        .iter()
+                        .flatten()
+                        .filter(|&&atom| atom != 0)
+                        .filter_map(|&atom| wt.xconn.get_atom_name(atom))
+                        .collect();
+                    *wt.drag_response.lock().unwrap() = (window, default_response(&mime_types));
+                    self.dnd.mime_types = mime_types;
                 } else if client_msg.message_type == self.dnd.atoms.position {
-                    // This event occurs every time the mouse moves while a file's being dragged
-                    // over our window. We emit HoveredFile in response; while the macOS backend
-                    // does that upon a drag entering, XDND doesn't have access to the actual drop
-                    // data until this event. For parity with other platforms, we only emit
-                    // `HoveredFile` the first time, though if winit's API is later extended to
-                    // supply position updates with `HoveredFile` or another event, implementing
-                    // that here would be trivial.
+                    // This event occurs every time the mouse moves while something's being
+                    // dragged over our window. XDND doesn't tell where the drag is before this
+                    // event, so the first one emits `DragEntered` and the following ones
+                    // `DragMoved`. It also doesn't give access to the actual drop data until
+                    // then, so the files are only requested here for `HoveredFile`.
 
                     let source_window = client_msg.data.get_long(0) as c_ulong;
 
-                    // Equivalent to `(x << shift) | y`
-                    // where `shift = mem::size_of::<c_short>() * 8`
+                    // Equivalent to `(x << 16) | y`
                     // Note that coordinates are in "desktop space", not "window space"
                     // (in X11 parlance, they're root window coordinates)
-                    //let packed_coordinates = client_msg.data.get_long(2);
-                    //let shift = mem::size_of::<libc::c_short>() * 8;
-                    //let x = packed_coordinates >> shift;
-                    //let y = packed_coordinates & !(x << shift);
+                    let packed_coordinates = client_msg.data.get_long(2);
+                    let x = (packed_coordinates >> 16) & 0xffff;
+                    let y = packed_coordinates & 0xffff;
+                    let (x, y) = match wt.xconn.translate_coords(window, wt.root) {
+                        Ok(coords) => (
+                            x - coords.x_rel_root as c_long,
+                            y - coords.y_rel_root as c_long,
+                        ),
+                        Err(_) => (x, y),
+                    };
+                    let position = PhysicalPosition::new(x as f64, y as f64);
 
                     // By our own state flow, `version` should never be `None` at this point.
                     let version = self.dnd.version.unwrap_or(5);
 
-                    // Action is specified in versions 2 and up, though we don't need it anyway.
-                    //let action = client_msg.data.get_long(4);
+                    // Action is specified in versions 2 and up.
+                    let action = if version >= 2 {
+                        self.dnd
+                            .atoms
+                            .action(client_msg.data.get_long(4) as ffi::Atom)
+                    } else {
+                        DragAction::Copy
+                    };
+
+                    let entered = self.dnd.position.is_some();
+                    self.dnd.source_window = Some(source_window);
+                    self.dnd.position = Some(position);
+                    self.dnd.action = action;
+                    if entered {
+                        callback(Event::WindowEvent {
+                            window_id,
+                            event: WindowEvent::DragMoved { position, action },
+                        });
+                    } else {
+                        callback(Event::WindowEvent {
+                            window_id,
+                            event: WindowEvent::DragEntered {
+                                mime_types: self.dnd.mime_types.clone(),
+                                position,
+                                action,
+                            },
+                        });
+                    }
+
+                    // The window may have answered the drag while handling the event above.
+                    let state = match *wt.drag_response.lock().unwrap() {
+                        (response_window, Some((_, action))) if response_window == window => {
+                            DndState::Accepted(action)
+                        }
+                        _ => DndState::Rejected,
+                    };
 
-                    let accepted = if let Some(ref type_list) = self.dnd.type_list {
+                    let has_files = if let Some(ref type_list) = self.dnd.type_list {
                         type_list.contains(&self.dnd.atoms.uri_list)
                     } else {
                         false
                     };
 
-                    if accepted {
-                        self.dnd.source_window = Some(source_window);
-                        unsafe {
-                            if self.dnd.result.is_none() {
-                                let time = if version >= 1 {
-                                    client_msg.data.get_long(3) as c_ulong
-                                } else {
-                                    // In version 0, time isn't specified
-                                    ffi::CurrentTime
-                                };
-                                // This results in the `SelectionNotify` event below
-                                self.dnd.convert_selection(window, time);
-                            }
-                            self.dnd
-                                .send_status(window, source_window, DndState::Accepted)
-                                .expect("Failed to send `XdndStatus` message.");
-                        }
-                    } else {
-                        unsafe {
-                            self.dnd
-                                .send_status(window, source_window, DndState::Rejected)
-                                .expect("Failed to send `XdndStatus` message.");
+                    unsafe {
+                        if has_files && self.dnd.result.is_none() {
+                            let time = if version >= 1 {
+                                client_msg.data.get_long(3) as c_ulong
+                            } else {
+                                // In version 0, time isn't specified
+                                ffi::CurrentTime
+                            };
+                            // This results in the `SelectionNotify` event below
+                            self.dnd.convert_selection(window, time);
                         }
-                        self.dnd.reset();
+                        self.dnd
+                            .send_status(window, source_window, state)
+                            .expect("Failed to send `XdndStatus` message.");
                     }
                 } else if client_msg.message_type == self.dnd.atoms.drop {
-                    let (source_window, state) = if let Some(source_window) = self.dnd.source_window
+                    let response = {
+                        let mut drag_response = wt.drag_response.lock().unwrap();
+                        let response = if drag_response.0 == window {
+                            drag_response.1.take()
+                        } else {
+                            None
+                        };
+                        drag_response.0 = 0;
+                        response
+                    };
+
+                    // `source_window` won't be part of our DND state if the drop happens before
+                    // any `XdndPosition` message.
+                    let source_window = self
+                        .dnd
+                        .source_window
+                        .unwrap_or(client_msg.data.get_long(0) as c_ulong);
+                    let paths = self.dnd.result.take().and_then(|result| result.ok());
+
+                    if let (Some(position), Some((mime_type, action))) =
+                        (self.dnd.position, response)
                     {
-                        if let Some(Ok(ref path_list)) = self.dnd.result {
-                            for path in path_list {
-                                callback(Event::WindowEvent {
-                                    window_id,
-                                    event: WindowEvent::DroppedFile(path.clone()),
-                                });
+                        let clipboard = wt.clipboard.lock().unwrap();
+                        // Sources name the types they offer after the MIME types.
+                        let target = if self.dnd.mime_types.contains(&mime_type) {
+                            CString::new(mime_type.as_str())
+                                .ok()
+                                .map(|name| wt.xconn.get_atom(name))
+                        } else {
+                            clipboard.target_for_mime_type(&mime_type)
+                        };
+                        let data = if mime_type == URI_LIST && self.dnd.data.is_some() {
+                            Some(self.dnd.data.take())
+                        } else {
+                            // The drag was started by one of our windows, whose data is at hand.
+                            clipboard
+                                .drag_contents()
+                                .map(|contents| contents.get(&mime_type).map(|data| data.to_vec()))
+                        };
+                        drop(clipboard);
+
+                        // By our own state flow, `version` should never be `None` at this point.
+                        let version = self.dnd.version.unwrap_or(5);
+                        let time = if version >= 1 {
+                            client_msg.data.get_long(2) as c_ulong
+                        } else {
+                            // In version 0, time isn't specified
+                            ffi::CurrentTime
+                        };
+                        let dropped = PendingDrop {
+                            window,
+                            source_window,
+                            mime_type,
+                            target: target.unwrap_or(0),
+                            position,
+                            action,
+                            time,
+                            paths,
+                            requested: Instant::now(),
+                        };
+                        match (data, target) {
+                            (Some(data), _) => self.finish_drop(dropped, data, &mut callback),
+                            (None, Some(target)) => {
+                                // This results in the `SelectionNotify` event below, where the
+                                // drop is finished.
+                                let requested = clipboard::request_conversion(
+                                    &wt.xconn,
+                                    window,
+                                    self.dnd.atoms.selection,
+                                    target,
+                                    self.dnd.atoms.drop_data,
+                                    time,
+                                );
+                                match requested {
+                                    Ok(()) => {
+                                        if let Some(pending) =
+                                            self.dnd.pending_drop.replace(Box::new(dropped))
+                                        {
+                                            self.finish_drop(*pending, None, &mut callback);
+                                        }
+                                    }
+                                    Err(err) => {
+                                        warn!("Failed to request the data of a drop: {:?}", err);
+                                        self.finish_drop(dropped, None, &mut callback);
+                                    }
+                                }
                             }
+                            (None, None) => self.finish_drop(dropped, None, &mut callback),
                         }
-                        (source_window, DndState::Accepted)
                     } else {
-                        // `source_window` won't be part of our DND state if we already rejected the drop in our
-                        // `XdndPosition` handler.
-                        let source_window = client_msg.data.get_long(0) as c_ulong;
-                        (source_window, DndState::Rejected)
-                    };
-                    unsafe {
-                        self.dnd
-                            .send_finished(window, source_window, state)
-                            .expect("Failed to send `XdndFinished` message.");
+                        if paths.is_some() {
+                            callback(Event::WindowEvent {
+                                window_id,
+                                event: WindowEvent::HoveredFileCancelled,
+                            });
+                        }
+                        if self.dnd.position.is_some() {
+                            callback(Event::WindowEvent {
+                                window_id,
+                                event: WindowEvent::DragLeft,
+                            });
+                        }
+                        unsafe {
+                            if let Err(err) =
+                                self.dnd
+                                    .send_finished(window, source_window, DndState::Rejected)
+                            {
+                                warn!("Failed to send `XdndFinished` message: {:?}", err);
+                            }
+                        }
                     }
                     self.dnd.reset();
                 } else if client_msg.message_type == self.dnd.atoms.leave {
+                    let entered = self.dnd.position.is_some();
                     self.dnd.reset();
+                    wt.drag_response.lock().unwrap().0 = 0;
                     callback(Event::WindowEvent {
                         window_id,
                         event: WindowEvent::HoveredFileCancelled,
                     });
+                    if entered {
+                        callback(Event::WindowEvent {
+                            window_id,
+                            event: WindowEvent::DragLeft,
+                        });
+                    }
                 }
             }
 
@@ -304,7 +612,28 @@ impl<T: 'static> EventProcessor<T> {
                 let window = xsel.requestor;
                 let window_id = mkwid(window);
 
-                if xsel.property == self.dnd.atoms.selection {
+                let drop_reply = match self.dnd.pending_drop {
+                    Some(ref pending) => {
+                        pending.window == window
+                            && pending.target == xsel.target
+                            && pending.time == xsel.time
+                            && xsel.selection == self.dnd.atoms.selection
+                            && (xsel.property == self.dnd.atoms.drop_data || xsel.property == 0)
+                    }
+                    None => false,
+                };
+
+                if drop_reply {
+                    // This is where we receive the data of a drop
+                    let pending = self.dnd.pending_drop.take().unwrap();
+                    let atoms = wt.clipboard.lock().unwrap().atoms;
+                    let data = clipboard::read_conversion(&wt.xconn, &atoms, xsel, pending.target)
+                        .unwrap_or_else(|err| {
+                            warn!("Failed to read the data of a drop: {:?}", err);
+                            None
+                        });
+                    self.finish_drop(*pending, data, &mut callback);
+                } else if xsel.property == self.dnd.atoms.selection {
                     let mut result = None;
 
                     // This is where we receive data from drag and drop
@@ -318,6 +647,8 @@ impl<T: 'static> EventProcessor<T> {
                                 });
                             }
                         }
+                        // Kept in case the window accepts the files as they are.
+                        self.dnd.data = Some(data);
                         result = Some(parse_result);
                     }
 
@@ -325,6 +656,33 @@ impl<T: 'static> EventProcessor<T> {
                 }
             }
 
+            ffi::SelectionRequest => {
+                let xev: &ffi::XSelectionRequestEvent = xev.as_ref();
+
+                // Another client wants the contents of a selection owned by one of our windows.
+                if let Err(err) = wt.clipboard.lock().unwrap().handle_selection_request(xev) {
+                    warn!("Failed to answer selection request: {:?}", err);
+                }
+            }
+
+            ffi::SelectionClear => {
+                let xev: &ffi::XSelectionClearEvent = xev.as_ref();
+
+                let cleared = {
+                    let mut clipboard = wt.clipboard.lock().unwrap();
+                    clipboard
+                        .handle_selection_clear(xev.window, xev.selection)
+                        // With XFixes, owner changes are reported below instead.
+                        .filter(|_| !clipboard.tracks_owner_changes())
+                };
+                if let Some(kind) = cleared {
+                    callback(Event::WindowEvent {
+                        window_id: mkwid(xev.window),
+                        event: WindowEvent::ClipboardChanged(kind),
+                    });
+                }
+            }
+
             ffi::ConfigureNotify => {
                 let xev: &ffi::XConfigureEvent = xev.as_ref();
                 let xwindow = xev.window;
@@ -504,9 +862,26 @@ impl<T: 'static> EventProcessor<T> {
                 let window = xev.window;
                 let window_id = mkwid(window);
 
+                // The XSETTINGS manager went away, possibly leaving its place to another one.
+                if wt.xsettings.borrow().is_manager_window(window) {
+                    self.update_xsettings(&mut callback);
+                    return;
+                }
+
+                // The target of our drop went away without sending `XdndFinished`, which is all
+                // there is to report unless it was one of our windows.
+                let drop_target =
+                    |source: &DragSource| source.target.map(|(target, _)| target) == Some(window);
+                if self.cancel_dropped_drag(drop_target, &mut callback)
+                    && !wt.windows.borrow().contains_key(&WindowId(window))
+                {
+                    return;
+                }
+
                 // In the event that the window's been destroyed without being dropped first, we
                 // cleanup again here.
                 wt.windows.borrow_mut().remove(&WindowId(window));
+                wt.clipboard.lock().unwrap().remove_window(window);
 
                 // Since all XIM stuff needs to happen from the same thread, we destroy the input
                 // context here instead of when dropping the window.
@@ -521,11 +896,62 @@ impl<T: 'static> EventProcessor<T> {
                 });
             }
 
+            ffi::PropertyNotify => {
+                let xev: &ffi::XPropertyEvent = xev.as_ref();
+                if wt.clipboard.lock().unwrap().handle_property_notify(xev) {
+                    return;
+                }
+                if wt.xsettings.borrow().is_settings_change(xev) {
+                    self.update_xsettings(&mut callback);
+                    return;
+                }
+
+                let wm_state_atom = unsafe { wt.xconn.get_atom_unchecked(b"WM_STATE\0") };
+                let net_wm_state_atom = unsafe { wt.xconn.get_atom_unchecked(b"_NET_WM_STATE\0") };
+
+                if xev.atom == wm_state_atom || xev.atom == net_wm_state_atom {
+                    let state = self
+                        .with_window(xev.window, |window| window.update_window_state())
+                        .flatten();
+                    if let Some(state) = state {
+                        callback(Event::WindowEvent {
+                            window_id: mkwid(xev.window),
+                            event: WindowEvent::StateChanged(state),
+                        });
+                    }
+                }
+            }
+
             ffi::VisibilityNotify => {
                 let xev: &ffi::XVisibilityEvent = xev.as_ref();
                 let xwindow = xev.window;
 
-                self.with_window(xwindow, |window| window.visibility_notify());
+                let occluded = xev.state == ffi::VisibilityFullyObscured;
+                let changed = self.with_window(xwindow, |window| {
+                    window.visibility_notify();
+                    window.set_occluded(occluded)
+                });
+                if changed == Some(true) {
+                    callback(Event::WindowEvent {
+                        window_id: mkwid(xwindow),
+                        event: WindowEvent::Occluded(occluded),
+                    });
+                }
+            }
+
+            ffi::UnmapNotify => {
+                let xev: &ffi::XUnmapEvent = xev.as_ref();
+                let xwindow = xev.window;
+
+                // Unmapped windows don't get `VisibilityNotify`, and become visible again with
+                // the one sent once they're mapped.
+                let changed = self.with_window(xwindow, |window| window.set_occluded(true));
+                if changed == Some(true) {
+                    callback(Event::WindowEvent {
+                        window_id: mkwid(xwindow),
+                        event: WindowEvent::Occluded(true),
+                    });
+                }
             }
 
             ffi::Expose => {
@@ -562,12 +988,36 @@ impl<T: 'static> EventProcessor<T> {
                 let device_id = mkdid(device);
                 let keycode = xkev.keycode;
 
-                // When a compose sequence or IME pre-edit is finished, it ends in a KeyPress with
-                // a keycode of 0.
+                // Compose sequences and pre-edits handled by the input method end in a KeyPress
+                // with a keycode of 0, whose text can only be looked up through Xlib.
+                let lookup = if state == Pressed && (keycode == 0 || self.xkb.is_none()) {
+                    let ime = wt.ime.borrow();
+                    ime.get_context(window)
+                        .map(|ic| (wt.xconn.lookup_utf8(ic, xkev), ime.is_ime_allowed(window)))
+                } else {
+                    None
+                };
+
                 if keycode != 0 {
                     let scancode = keycode - 8;
-                    let keysym = wt.xconn.lookup_keysym(xkev);
-                    let virtual_keycode = events::keysym_to_element(keysym as c_uint);
+                    let (keysym, text) = match self.xkb {
+                        Some(ref mut xkb) if state == Pressed => {
+                            let press = xkb.state.key_press(keycode);
+                            (press.keysym, press.text)
+                        }
+                        Some(ref xkb) => (xkb.state.keysym(keycode), None),
+                        None => {
+                            let text = match lookup {
+                                Some((ref written, _)) if !written.is_empty() => {
+                                    Some(written.clone())
+                                }
+                                _ => None,
+                            };
+                            (wt.xconn.lookup_keysym(xkev) as u32, text)
+                        }
+                    };
+                    let virtual_keycode = keysym::key_to_vkey(scancode, keysym);
+                    let logical_key = keysym::logical_key(keysym, text.as_deref());
 
                     update_modifiers!(
                         ModifiersState::from_x11_mask(xkev.state),
@@ -584,27 +1034,42 @@ impl<T: 'static> EventProcessor<T> {
                             input: KeyboardInput {
                                 state,
                                 scancode,
+                                physical_key: keycode_from_evdev(scancode),
                                 virtual_keycode,
+                                logical_key,
+                                text: text.clone(),
                                 modifiers,
                             },
                             is_synthetic: false,
                         },
                     });
+
+                    if self.xkb.is_some() {
+                        for chr in text.iter().flat_map(|text| text.chars()) {
+                            callback(Event::WindowEvent {
+                                window_id,
+                                event: WindowEvent::ReceivedCharacter(chr),
+                            });
+                        }
+                    }
                 }
 
-                if state == Pressed {
-                    let written = if let Some(ic) = wt.ime.borrow().get_context(window) {
-                        wt.xconn.lookup_utf8(ic, xkev)
+                if let Some((written, ime_allowed)) = lookup {
+                    if ime_allowed && keycode == 0 {
+                        if !written.is_empty() {
+                            callback(Event::WindowEvent {
+                                window_id,
+                                event: WindowEvent::ImeCommit(written),
+                            });
+                        }
                     } else {
-                        return;
-                    };
-
-                    for chr in written.chars() {
-                        let event = Event::WindowEvent {
-                            window_id,
-                            event: WindowEvent::ReceivedCharacter(chr),
-                        };
-                        callback(event);
+                        for chr in written.chars() {
+                            let event = Event::WindowEvent {
+                                window_id,
+                                event: WindowEvent::ReceivedCharacter(chr),
+                            };
+                            callback(event);
+                        }
                     }
                 }
             }
@@ -634,6 +1099,9 @@ impl<T: 'static> EventProcessor<T> {
                 match xev.evtype {
                     ffi::XI_ButtonPress | ffi::XI_ButtonRelease => {
                         let xev: &ffi::XIDeviceEvent = unsafe { &*(xev.data as *const _) };
+                        if xev.evtype == ffi::XI_ButtonRelease {
+                            self.drag_source_release(xev, &mut callback);
+                        }
                         let window_id = mkwid(xev.event);
                         let device_id = mkdid(xev.deviceid);
                         if (xev.flags & ffi::XIPointerEmulated) != 0 {
@@ -711,9 +1179,12 @@ impl<T: 'static> EventProcessor<T> {
                                 },
                             }),
                         }
+
+                        self.handle_pen_event(xev, &mut callback);
                     }
                     ffi::XI_Motion => {
                         let xev: &ffi::XIDeviceEvent = unsafe { &*(xev.data as *const _) };
+                        self.drag_source_motion(xev, &mut callback);
                         let device_id = mkdid(xev.deviceid);
                         let window_id = mkwid(xev.event);
                         let new_cursor_pos = (xev.event_x, xev.event_y);
@@ -800,6 +1271,8 @@ impl<T: 'static> EventProcessor<T> {
                         for event in events {
                             callback(event);
                         }
+
+                        self.handle_pen_event(xev, &mut callback);
                     }
 
                     ffi::XI_Enter => {
@@ -871,6 +1344,27 @@ impl<T: 'static> EventProcessor<T> {
                                 },
                             });
                         }
+
+                        // Tablet tools are only known to be out of proximity once they leave.
+                        let mut events = Vec::new();
+                        for (device_id, device) in self.devices.borrow_mut().iter_mut() {
+                            if let Some(ref mut pen) = device.pen {
+                                if pen.window == Some(xev.event) {
+                                    pen.window = None;
+                                    if !window_closed {
+                                        let input =
+                                            pen.input(mkdid(device_id.0), PenPhase::ProximityOut);
+                                        events.push(Event::WindowEvent {
+                                            window_id: mkwid(xev.event),
+                                            event: WindowEvent::PenInput(input),
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                        for event in events {
+                            callback(event);
+                        }
                     }
                     ffi::XI_FocusIn => {
                         let xev: &ffi::XIFocusInEvent = unsafe { &*(xev.data as *const _) };
@@ -886,6 +1380,9 @@ impl<T: 'static> EventProcessor<T> {
 
                         if self.active_window != Some(xev.event) {
                             self.active_window = Some(xev.event);
+                            self.with_window(xev.event, |window| {
+                                window.shared_state.lock().has_focus = true;
+                            });
 
                             let window_id = mkwid(xev.event);
                             let position = PhysicalPosition::new(xev.event_x, xev.event_y);
@@ -937,9 +1434,15 @@ impl<T: 'static> EventProcessor<T> {
                             .borrow_mut()
                             .unfocus(xev.event)
                             .expect("Failed to unfocus input context");
+                        if let Some(ref mut xkb) = self.xkb {
+                            xkb.state.reset_compose();
+                        }
 
                         if self.active_window.take() == Some(xev.event) {
                             let window_id = mkwid(xev.event);
+                            self.with_window(xev.event, |window| {
+                                window.shared_state.lock().has_focus = false;
+                            });
 
                             // Issue key release events for all pressed keys
                             self.handle_pressed_keys(
@@ -1002,6 +1505,62 @@ impl<T: 'static> EventProcessor<T> {
                         }
                     }
 
+                    ffi::XI_GesturePinchBegin
+                    | ffi::XI_GesturePinchUpdate
+                    | ffi::XI_GesturePinchEnd => {
+                        let xev: &ffi::XIGesturePinchEvent = unsafe { &*(xev.data as *const _) };
+                        let phase = match xev.evtype {
+                            ffi::XI_GesturePinchBegin => TouchPhase::Started,
+                            ffi::XI_GesturePinchUpdate => TouchPhase::Moved,
+                            _ if xev.flags & ffi::XIGesturePinchEventCancelled != 0 => {
+                                TouchPhase::Cancelled
+                            }
+                            _ => TouchPhase::Ended,
+                        };
+                        // The server reports the scale relative to the start of the gesture.
+                        if phase == TouchPhase::Started {
+                            self.pinch_scale = 1.0;
+                        }
+                        let scale_delta = xev.scale - self.pinch_scale;
+                        self.pinch_scale = xev.scale;
+                        if self.window_exists(xev.event) {
+                            callback(Event::WindowEvent {
+                                window_id: mkwid(xev.event),
+                                event: WindowEvent::PinchGesture {
+                                    device_id: mkdid(xev.deviceid),
+                                    phase,
+                                    scale_delta,
+                                    rotation_delta: xev.delta_angle,
+                                },
+                            });
+                        }
+                    }
+
+                    ffi::XI_GestureSwipeBegin
+                    | ffi::XI_GestureSwipeUpdate
+                    | ffi::XI_GestureSwipeEnd => {
+                        let xev: &ffi::XIGestureSwipeEvent = unsafe { &*(xev.data as *const _) };
+                        let phase = match xev.evtype {
+                            ffi::XI_GestureSwipeBegin => TouchPhase::Started,
+                            ffi::XI_GestureSwipeUpdate => TouchPhase::Moved,
+                            _ if xev.flags & ffi::XIGestureSwipeEventCancelled != 0 => {
+                                TouchPhase::Cancelled
+                            }
+                            _ => TouchPhase::Ended,
+                        };
+                        if self.window_exists(xev.event) {
+                            callback(Event::WindowEvent {
+                                window_id: mkwid(xev.event),
+                                event: WindowEvent::SwipeGesture {
+                                    device_id: mkdid(xev.deviceid),
+                                    phase,
+                                    fingers: xev.detail as u32,
+                                    delta: PhysicalPosition::new(xev.delta_x, xev.delta_y),
+                                },
+                            });
+                        }
+                    }
+
                     ffi::XI_RawButtonPress | ffi::XI_RawButtonRelease => {
                         let xev: &ffi::XIRawEvent = unsafe { &*(xev.data as *const _) };
                         if xev.flags & ffi::XIPointerEmulated == 0 {
@@ -1085,8 +1644,8 @@ impl<T: 'static> EventProcessor<T> {
                             return;
                         }
                         let scancode = (keycode - 8) as u32;
-                        let keysym = wt.xconn.keycode_to_keysym(keycode as ffi::KeyCode);
-                        let virtual_keycode = events::keysym_to_element(keysym as c_uint);
+                        let keysym = self.keysym(keycode as u32);
+                        let virtual_keycode = keysym::key_to_vkey(scancode, keysym);
                         let modifiers = self.device_mod_state.modifiers();
 
                         #[allow(deprecated)]
@@ -1094,7 +1653,10 @@ impl<T: 'static> EventProcessor<T> {
                             device_id,
                             event: DeviceEvent::Key(KeyboardInput {
                                 scancode,
+                                physical_key: keycode_from_evdev(scancode),
                                 virtual_keycode,
+                                logical_key: keysym::keysym_to_key(keysym),
+                                text: None,
                                 state,
                                 modifiers,
                             }),
@@ -1149,77 +1711,560 @@ impl<T: 'static> EventProcessor<T> {
                 }
             }
             _ => {
-                if event_type == self.randr_event_offset {
-                    // In the future, it would be quite easy to emit monitor hotplug events.
-                    let prev_list = monitor::invalidate_cached_monitor_list();
-                    if let Some(prev_list) = prev_list {
-                        let new_list = wt.xconn.available_monitors();
-                        for new_monitor in new_list {
-                            prev_list
-                                .iter()
-                                .find(|prev_monitor| prev_monitor.name == new_monitor.name)
-                                .map(|prev_monitor| {
-                                    if new_monitor.scale_factor != prev_monitor.scale_factor {
-                                        for (window_id, window) in wt.windows.borrow().iter() {
-                                            if let Some(window) = window.upgrade() {
-                                                // Check if the window is on this monitor
-                                                let monitor = window.current_monitor();
-                                                if monitor.name == new_monitor.name {
-                                                    let (width, height) =
-                                                        window.inner_size_physical();
-                                                    let (new_width, new_height) = window
-                                                        .adjust_for_dpi(
-                                                            prev_monitor.scale_factor,
-                                                            new_monitor.scale_factor,
-                                                            width,
-                                                            height,
-                                                            &*window.shared_state.lock(),
-                                                        );
-
-                                                    let window_id = crate::window::WindowId(
-                                                        crate::platform_impl::platform::WindowId::X(
-                                                            *window_id,
-                                                        ),
-                                                    );
-                                                    let old_inner_size =
-                                                        PhysicalSize::new(width, height);
-                                                    let mut new_inner_size =
-                                                        PhysicalSize::new(new_width, new_height);
-
-                                                    callback(Event::WindowEvent {
-                                                        window_id,
-                                                        event: WindowEvent::ScaleFactorChanged {
-                                                            scale_factor: new_monitor.scale_factor,
-                                                            new_inner_size: &mut new_inner_size,
-                                                        },
-                                                    });
-
-                                                    if new_inner_size != old_inner_size {
-                                                        let (new_width, new_height) =
-                                                            new_inner_size.into();
-                                                        window.set_inner_size_physical(
-                                                            new_width, new_height,
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
+                let owner_change = wt
+                    .clipboard
+                    .lock()
+                    .unwrap()
+                    .owner_change_event(event_type, xev);
+                if let Some((window, owner, kind)) = owner_change {
+                    // Our own windows taking ownership are `set_clipboard_contents` calls.
+                    if self.window_exists(window) && !self.window_exists(owner) {
+                        callback(Event::WindowEvent {
+                            window_id: mkwid(window),
+                            event: WindowEvent::ClipboardChanged(kind),
+                        });
+                    }
+                }
+
+                if let Some(ref mut xkb) = self.xkb {
+                    if event_type == xkb.event_base {
+                        xkb.process_event(&wt.xconn, xev);
+                    }
+                }
+
+                let randr_notify = event_type == self.randr_event_offset + ffi::RRNotify && {
+                    let xev: &ffi::XRRNotifyEvent = unsafe { &*(xev as *const _ as *const _) };
+                    xev.subtype == ffi::RRNotify_CrtcChange
+                        || xev.subtype == ffi::RRNotify_OutputChange
+                };
+                if event_type == self.randr_event_offset || randr_notify {
+                    self.update_monitors(&mut callback);
+                }
+            }
+        }
+
+        self.process_ime_requests(&mut callback);
+        self.process_ime_events(&mut callback);
+    }
+
+    // Reads the settings of the XSETTINGS manager again, reporting the changes.
+    fn update_xsettings<F>(&self, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let wt = get_xtarget(&self.target);
+
+        let (old, new) = {
+            let mut xsettings = wt.xsettings.borrow_mut();
+            let old = xsettings.refresh();
+            let new = xsettings.settings().clone();
+            if new.dpi != old.dpi
+                || new.cursor_theme_name != old.cursor_theme_name
+                || new.cursor_theme_size != old.cursor_theme_size
+            {
+                xsettings.apply();
+            }
+            (old, new)
+        };
+
+        if new.theme() != old.theme() {
+            let window_ids: Vec<_> = wt.windows.borrow().keys().cloned().collect();
+            for window_id in window_ids {
+                callback(Event::WindowEvent {
+                    window_id: mkwid(window_id.0),
+                    event: WindowEvent::ThemeChanged(new.theme()),
+                });
+            }
+        }
+
+        // The scale factor of the monitors is derived from the DPI.
+        if new.dpi != old.dpi {
+            self.update_monitors(callback);
+        }
+    }
+
+    // Compares the monitors with the cached ones, reporting the changes.
+    fn update_monitors<F>(&self, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let wt = get_xtarget(&self.target);
+
+        let prev_list = monitor::invalidate_cached_monitor_list();
+        if let Some(prev_list) = prev_list {
+            let new_list = wt.xconn.available_monitors();
+            for new_monitor in new_list.iter() {
+                let prev_monitor = match prev_list
+                    .iter()
+                    .find(|prev_monitor| prev_monitor.name == new_monitor.name)
+                {
+                    Some(prev_monitor) => prev_monitor,
+                    None => {
+                        callback(Event::MonitorAdded(RootMonitorHandle {
+                            inner: PlatformMonitorHandle::X(new_monitor.clone()),
+                        }));
+                        continue;
+                    }
+                };
+
+                if new_monitor.scale_factor != prev_monitor.scale_factor {
+                    for (window_id, window) in wt.windows.borrow().iter() {
+                        if let Some(window) = window.upgrade() {
+                            // Check if the window is on this monitor
+                            let monitor = window.current_monitor();
+                            if monitor.name == new_monitor.name {
+                                let (width, height) = window.inner_size_physical();
+                                let (new_width, new_height) = window.adjust_for_dpi(
+                                    prev_monitor.scale_factor,
+                                    new_monitor.scale_factor,
+                                    width,
+                                    height,
+                                    &*window.shared_state.lock(),
+                                );
+
+                                let window_id = crate::window::WindowId(
+                                    crate::platform_impl::platform::WindowId::X(*window_id),
+                                );
+                                let old_inner_size = PhysicalSize::new(width, height);
+                                let mut new_inner_size = PhysicalSize::new(new_width, new_height);
+
+                                callback(Event::WindowEvent {
+                                    window_id,
+                                    event: WindowEvent::ScaleFactorChanged {
+                                        scale_factor: new_monitor.scale_factor,
+                                        new_inner_size: &mut new_inner_size,
+                                    },
                                 });
+
+                                if new_inner_size != old_inner_size {
+                                    let (new_width, new_height) = new_inner_size.into();
+                                    window.set_inner_size_physical(new_width, new_height);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if !new_monitor.same_configuration(prev_monitor) {
+                    callback(Event::MonitorChanged(RootMonitorHandle {
+                        inner: PlatformMonitorHandle::X(new_monitor.clone()),
+                    }));
+                }
+            }
+
+            for prev_monitor in prev_list {
+                if !new_list.iter().any(|m| m.name == prev_monitor.name) {
+                    callback(Event::MonitorRemoved(RootMonitorHandle {
+                        inner: PlatformMonitorHandle::X(prev_monitor),
+                    }));
+                }
+            }
+        }
+    }
+
+    fn process_ime_requests<F>(&mut self, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let wt = get_xtarget(&self.target);
+
+        while let Ok(request) = self.ime_receiver.try_recv() {
+            match request {
+                ImeRequest::Position(window, x, y) => {
+                    wt.ime.borrow_mut().send_xim_spot(window, x, y);
+                }
+                ImeRequest::Allow(window, allowed) => {
+                    let mut ime = wt.ime.borrow_mut();
+                    match ime.set_ime_allowed(window, allowed) {
+                        Ok(true) => {
+                            // The new context has to be focused if the window already is.
+                            if self.active_window == Some(window) {
+                                let _ = ime.focus(window);
+                            }
+                        }
+                        Ok(false) => continue,
+                        Err(err) => {
+                            warn!("Failed to replace the input context: {:?}", err);
+                            continue;
+                        }
+                    }
+                    drop(ime);
+
+                    // The old context is gone along with its preedit string.
+                    if self.composing_window == Some(window) {
+                        self.composing_window = None;
+                        callback(Event::WindowEvent {
+                            window_id: mkwid(window),
+                            event: WindowEvent::ImePreedit {
+                                text: String::new(),
+                                cursor_range: None,
+                            },
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_ime_events<F>(&mut self, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        while let Ok((window, event)) = self.ime_event_receiver.try_recv() {
+            let event = match event {
+                ImeEvent::Start => {
+                    self.composing_window = Some(window);
+                    continue;
+                }
+                ImeEvent::Update(text, caret) => WindowEvent::ImePreedit {
+                    text,
+                    cursor_range: Some((caret, caret)),
+                },
+                ImeEvent::End => {
+                    self.composing_window = None;
+                    WindowEvent::ImePreedit {
+                        text: String::new(),
+                        cursor_range: None,
+                    }
+                }
+            };
+            callback(Event::WindowEvent {
+                window_id: mkwid(window),
+                event,
+            });
+        }
+    }
+
+    // Returns the keysym of a key in the current keyboard state.
+    fn keysym(&self, keycode: u32) -> u32 {
+        match self.xkb {
+            Some(ref xkb) => xkb.state.keysym(keycode),
+            None => {
+                let wt = get_xtarget(&self.target);
+                wt.xconn.keycode_to_keysym(keycode as ffi::KeyCode) as u32
+            }
+        }
+    }
+
+    // Reports the events of tablet tools, which come from the same device events as the pointer.
+    fn handle_pen_event<F>(&self, xev: &ffi::XIDeviceEvent, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let mut events = Vec::new();
+        {
+            let mut devices = self.devices.borrow_mut();
+            let pen = match devices
+                .get_mut(&DeviceId(xev.sourceid))
+                .and_then(|device| device.pen.as_mut())
+            {
+                Some(pen) => pen,
+                None => return,
+            };
+            let device_id = mkdid(xev.sourceid);
+            let window_id = mkwid(xev.event);
+
+            pen.update_axes(&xev.valuators);
+            let phase = match xev.evtype {
+                ffi::XI_ButtonPress | ffi::XI_ButtonRelease => {
+                    let pressed = xev.evtype == ffi::XI_ButtonPress;
+                    match xev.detail {
+                        1 if pressed => PenPhase::Down,
+                        1 => PenPhase::Up,
+                        2 => {
+                            pen.buttons.set(PenButtons::PRIMARY, pressed);
+                            PenPhase::Moved
                         }
+                        3 => {
+                            pen.buttons.set(PenButtons::SECONDARY, pressed);
+                            PenPhase::Moved
+                        }
+                        8 => {
+                            pen.buttons.set(PenButtons::TERTIARY, pressed);
+                            PenPhase::Moved
+                        }
+                        _ => return,
                     }
                 }
+                _ => PenPhase::Moved,
+            };
+
+            let entered = pen.window != Some(xev.event);
+            if let (true, Some(window)) = (entered, pen.window) {
+                events.push(Event::WindowEvent {
+                    window_id: mkwid(window),
+                    event: WindowEvent::PenInput(pen.input(device_id, PenPhase::ProximityOut)),
+                });
+            }
+            pen.window = Some(xev.event);
+            pen.position = PhysicalPosition::new(xev.event_x, xev.event_y);
+            if entered {
+                events.push(Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::PenInput(pen.input(device_id, PenPhase::ProximityIn)),
+                });
+            }
+            events.push(Event::WindowEvent {
+                window_id,
+                event: WindowEvent::PenInput(pen.input(device_id, phase)),
+            });
+        }
+        for event in events {
+            callback(event);
+        }
+    }
+
+    // Moves the drag started by one of our windows, telling the XDND aware window under the
+    // pointer where it is.
+    fn drag_source_motion<F>(&self, xev: &ffi::XIDeviceEvent, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let wt = get_xtarget(&self.target);
+        let mut drag_source = wt.drag_source.lock().unwrap();
+        let source = match *drag_source {
+            Some(ref mut source)
+                if source.device_id == xev.deviceid && source.dropped.is_none() =>
+            {
+                source
+            }
+            _ => return,
+        };
+        let position = (xev.root_x as c_int, xev.root_y as c_int);
+        let target = self.dnd.find_target(wt.root, position.0, position.1);
+
+        // The targets may be destroyed at any time, which fails the messages sent to them and
+        // leaves the drag without a target.
+        let mut rejected = false;
+        if target.map(|(window, _)| window) != source.target.map(|(window, _)| window) {
+            if let Some((old, _)) = source.target {
+                let _ = unsafe { self.dnd.send_leave(source.window, old) };
+            }
+            source.target = None;
+            if let Some((new, version)) = target {
+                let entered = unsafe {
+                    self.dnd
+                        .send_enter(source.window, new, version, &source.types)
+                };
+                if entered.is_ok() {
+                    source.target = target;
+                }
+            }
+            source.awaiting_status = false;
+            source.pending_position = None;
+            rejected = source.accepted.take().is_some();
+        }
+        if let Some((target, _)) = source.target {
+            // Targets must answer every `XdndPosition` before being sent the next one.
+            if source.awaiting_status {
+                source.pending_position = Some((position.0, position.1, xev.time));
+            } else {
+                let sent = unsafe {
+                    self.dnd.send_position(
+                        source.window,
+                        target,
+                        position,
+                        xev.time,
+                        source.actions[0],
+                    )
+                };
+                if sent.is_ok() {
+                    source.awaiting_status = true;
+                } else {
+                    source.target = None;
+                    rejected |= source.accepted.take().is_some();
+                }
+            }
+        }
+
+        let window_id = mkwid(source.window);
+        drop(drag_source);
+        if rejected {
+            callback(Event::WindowEvent {
+                window_id,
+                event: WindowEvent::DragSourceAction(None),
+            });
+        }
+    }
+
+    // Drops the drag started by one of our windows once the button holding it is released.
+    fn drag_source_release<F>(&self, xev: &ffi::XIDeviceEvent, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let wt = get_xtarget(&self.target);
+        let mut drag_source = wt.drag_source.lock().unwrap();
+        let source = match *drag_source {
+            Some(ref mut source)
+                if source.device_id == xev.deviceid && source.dropped.is_none() =>
+            {
+                source
+            }
+            _ => return,
+        };
+        unsafe {
+            (wt.xconn.xinput2.XIUngrabDevice)(wt.xconn.display, source.device_id, ffi::CurrentTime)
+        };
+        let _ = wt.xconn.flush_requests();
+
+        let window_id = mkwid(source.window);
+        match (source.target, source.accepted) {
+            (Some((target, _)), Some(_)) => {
+                // Watch for the target being destroyed before it sends `XdndFinished`. Our own
+                // windows already report it.
+                if !self.window_exists(target) {
+                    unsafe {
+                        (wt.xconn.xlib.XSelectInput)(
+                            wt.xconn.display,
+                            target,
+                            ffi::StructureNotifyMask,
+                        )
+                    };
+                }
+                let dropped = unsafe { self.dnd.send_drop(source.window, target, xev.time) };
+                if dropped.is_ok() {
+                    // The drag ends once the target sends `XdndFinished`, or gives up on it.
+                    source.dropped = Some(Instant::now());
+                    return;
+                }
             }
+            (Some((target, _)), None) => {
+                // The target may be gone already.
+                let _ = unsafe { self.dnd.send_leave(source.window, target) };
+            }
+            (None, _) => (),
         }
+        *drag_source = None;
+        drop(drag_source);
+        wt.clipboard.lock().unwrap().clear_drag_contents();
+        callback(Event::WindowEvent {
+            window_id,
+            event: WindowEvent::DragSourceCancelled,
+        });
+    }
 
-        match self.ime_receiver.try_recv() {
-            Ok((window_id, x, y)) => {
-                wt.ime.borrow_mut().send_xim_spot(window_id, x, y);
+    // Tells the window and the source of a drop whether it succeeded, which it did if its data
+    // could be read.
+    fn finish_drop<F>(&self, dropped: PendingDrop, data: Option<Vec<u8>>, callback: &mut F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let window_id = mkwid(dropped.window);
+        let state = if let Some(data) = data {
+            let path_list = if dropped.mime_type == URI_LIST {
+                parse_uri_list(&data).ok()
+            } else {
+                dropped.paths
+            };
+            for path in path_list.into_iter().flatten() {
+                callback(Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::DroppedFile(path),
+                });
+            }
+            callback(Event::WindowEvent {
+                window_id,
+                event: WindowEvent::DragDropped {
+                    contents: ClipboardContents::new().with_data(dropped.mime_type, data),
+                    position: dropped.position,
+                    action: dropped.action,
+                },
+            });
+            DndState::Accepted(dropped.action)
+        } else {
+            if dropped.paths.is_some() {
+                callback(Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::HoveredFileCancelled,
+                });
+            }
+            callback(Event::WindowEvent {
+                window_id,
+                event: WindowEvent::DragLeft,
+            });
+            DndState::Rejected
+        };
+
+        unsafe {
+            if let Err(err) = self
+                .dnd
+                .send_finished(dropped.window, dropped.source_window, state)
+            {
+                warn!("Failed to send `XdndFinished` message: {:?}", err);
             }
-            Err(_) => (),
         }
     }
 
+    /// Rejects the drop whose source didn't convert its data in time.
+    pub(super) fn expire_pending_drop<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let expired = matches!(
+            self.dnd.pending_drop,
+            Some(ref pending) if pending.deadline() <= Instant::now()
+        );
+        if expired {
+            warn!("Timed out waiting for the source of a drop to convert its data");
+            let pending = self.dnd.pending_drop.take().unwrap();
+            self.finish_drop(*pending, None, &mut callback);
+        }
+    }
+
+    /// When the drop whose data is being converted is to be rejected.
+    pub(super) fn pending_drop_deadline(&self) -> Option<Instant> {
+        self.dnd
+            .pending_drop
+            .as_ref()
+            .map(|pending| pending.deadline())
+    }
+
+    // Cancels the drag started by one of our windows if it was dropped and matches `predicate`,
+    // which is used when its target can't send `XdndFinished` anymore. Returns whether it did.
+    fn cancel_dropped_drag<F, P>(&self, predicate: P, callback: &mut F) -> bool
+    where
+        F: FnMut(Event<'_, T>),
+        P: FnOnce(&DragSource) -> bool,
+    {
+        let wt = get_xtarget(&self.target);
+        let window_id = {
+            let mut drag_source = wt.drag_source.lock().unwrap();
+            match *drag_source {
+                Some(ref source) if source.dropped.is_some() && predicate(source) => {
+                    let window_id = mkwid(source.window);
+                    *drag_source = None;
+                    window_id
+                }
+                _ => return false,
+            }
+        };
+        wt.clipboard.lock().unwrap().clear_drag_contents();
+        callback(Event::WindowEvent {
+            window_id,
+            event: WindowEvent::DragSourceCancelled,
+        });
+        true
+    }
+
+    /// Cancels the dropped drag whose target didn't send `XdndFinished` in time.
+    pub(super) fn expire_drag_source<F>(&self, mut callback: F)
+    where
+        F: FnMut(Event<'_, T>),
+    {
+        let now = Instant::now();
+        self.cancel_dropped_drag(
+            |source| matches!(source.finish_deadline(), Some(deadline) if deadline <= now),
+            &mut callback,
+        );
+    }
+
+    /// When the dropped drag is to be cancelled if its target doesn't send `XdndFinished`.
+    pub(super) fn drag_source_deadline(&self) -> Option<Instant> {
+        let wt = get_xtarget(&self.target);
+        let drag_source = wt.drag_source.lock().unwrap();
+        drag_source.as_ref().and_then(DragSource::finish_deadline)
+    }
+
     fn handle_pressed_keys<F>(
         &self,
         window_id: crate::window::WindowId,
@@ -1242,8 +2287,8 @@ impl<T: 'static> EventProcessor<T> {
             }
 
             let scancode = (keycode - 8) as u32;
-            let keysym = wt.xconn.keycode_to_keysym(keycode);
-            let virtual_keycode = events::keysym_to_element(keysym as c_uint);
+            let keysym = self.keysym(keycode as u32);
+            let virtual_keycode = keysym::key_to_vkey(scancode, keysym);
 
             #[allow(deprecated)]
             callback(Event::WindowEvent {
@@ -1252,8 +2297,11 @@ impl<T: 'static> EventProcessor<T> {
                     device_id,
                     input: KeyboardInput {
                         scancode,
+                        physical_key: keycode_from_evdev(scancode),
                         state,
                         virtual_keycode,
+                        logical_key: keysym::keysym_to_key(keysym),
+                        text: None,
                         modifiers,
                     },
                     is_synthetic: true,
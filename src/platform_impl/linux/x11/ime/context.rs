@@ -1,10 +1,12 @@
 use std::{
-    os::raw::{c_short, c_void},
+    ffi::CStr,
+    mem,
+    os::raw::{c_int, c_short, c_void},
     ptr,
     sync::Arc,
 };
 
-use super::{ffi, util, XConnection, XError};
+use super::{ffi, util, ImeEvent, ImeEventSender, XConnection, XError};
 
 #[derive(Debug)]
 pub enum ImeContextCreationError {
@@ -28,14 +30,175 @@ unsafe fn create_pre_edit_attr<'a>(
     .expect("XVaCreateNestedList returned NULL")
 }
 
+unsafe fn create_pre_edit_attr_with_callbacks<'a>(
+    xconn: &'a Arc<XConnection>,
+    ic_spot: &'a ffi::XPoint,
+    callbacks: &'a PreeditCallbacks,
+) -> util::XSmartPointer<'a, c_void> {
+    util::XSmartPointer::new(
+        xconn,
+        (xconn.xlib.XVaCreateNestedList)(
+            0,
+            ffi::XNSpotLocation_0.as_ptr() as *const _,
+            ic_spot,
+            ffi::XNPreeditStartCallback_0.as_ptr() as *const _,
+            &callbacks.start as *const _,
+            ffi::XNPreeditDoneCallback_0.as_ptr() as *const _,
+            &callbacks.done as *const _,
+            ffi::XNPreeditDrawCallback_0.as_ptr() as *const _,
+            &callbacks.draw as *const _,
+            ffi::XNPreeditCaretCallback_0.as_ptr() as *const _,
+            &callbacks.caret as *const _,
+            ptr::null_mut::<()>(),
+        ),
+    )
+    .expect("XVaCreateNestedList returned NULL")
+}
+
+/// The state shared with the preedit callbacks of a context.
+struct PreeditState {
+    window: ffi::Window,
+    event_sender: ImeEventSender,
+    // Positions given by XIM are in characters, so the text is kept as such.
+    text: Vec<char>,
+    caret: usize,
+}
+
+impl PreeditState {
+    fn send(&self, event: ImeEvent) {
+        let _ = self.event_sender.send((self.window, event));
+    }
+
+    fn send_update(&self) {
+        let caret = self
+            .text
+            .iter()
+            .take(self.caret)
+            .map(|c| c.len_utf8())
+            .sum();
+        self.send(ImeEvent::Update(self.text.iter().collect(), caret));
+    }
+}
+
+struct PreeditCallbacks {
+    start: ffi::XIMCallback,
+    done: ffi::XIMCallback,
+    draw: ffi::XIMCallback,
+    caret: ffi::XIMCallback,
+}
+
+impl PreeditCallbacks {
+    fn new(client_data: ffi::XPointer) -> PreeditCallbacks {
+        let callback = |callback| ffi::XIMCallback {
+            client_data,
+            callback: Some(callback),
+        };
+        PreeditCallbacks {
+            // The start callback returns the maximum length of the preedit string, which
+            // `XIMProc` doesn't account for.
+            start: callback(unsafe {
+                mem::transmute::<
+                    unsafe extern "C" fn(ffi::XIM, ffi::XPointer, ffi::XPointer) -> c_int,
+                    unsafe extern "C" fn(ffi::XIM, ffi::XPointer, ffi::XPointer),
+                >(preedit_start_callback)
+            }),
+            done: callback(preedit_done_callback),
+            draw: callback(preedit_draw_callback),
+            caret: callback(preedit_caret_callback),
+        }
+    }
+}
+
+unsafe extern "C" fn preedit_start_callback(
+    _xim: ffi::XIM,
+    client_data: ffi::XPointer,
+    _call_data: ffi::XPointer,
+) -> c_int {
+    let state = &mut *(client_data as *mut PreeditState);
+    state.text.clear();
+    state.caret = 0;
+    state.send(ImeEvent::Start);
+    // No limit on the length of the preedit string.
+    -1
+}
+
+unsafe extern "C" fn preedit_done_callback(
+    _xim: ffi::XIM,
+    client_data: ffi::XPointer,
+    _call_data: ffi::XPointer,
+) {
+    let state = &mut *(client_data as *mut PreeditState);
+    state.send(ImeEvent::End);
+}
+
+unsafe extern "C" fn preedit_draw_callback(
+    _xim: ffi::XIM,
+    client_data: ffi::XPointer,
+    call_data: ffi::XPointer,
+) {
+    let state = &mut *(client_data as *mut PreeditState);
+    let call_data = &*(call_data as *const ffi::XIMPreeditDrawCallbackStruct);
+
+    let first = call_data.chg_first as usize;
+    let last = first + call_data.chg_length as usize;
+    if last > state.text.len() {
+        warn!(
+            "Invalid preedit change range {}..{} for a preedit of length {}",
+            first,
+            last,
+            state.text.len()
+        );
+        return;
+    }
+
+    // A null text means that the range was deleted.
+    let new_text = if call_data.text.is_null() {
+        String::new()
+    } else {
+        let text = &*call_data.text;
+        if text.encoding_is_wchar != 0 {
+            warn!("Wide character preedit strings are unsupported");
+            return;
+        }
+        let multi_byte = text.string.multi_byte;
+        if multi_byte.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(multi_byte).to_string_lossy().into_owned()
+        }
+    };
+
+    state.text.splice(first..last, new_text.chars());
+    state.caret = (call_data.caret as usize).min(state.text.len());
+    state.send_update();
+}
+
+unsafe extern "C" fn preedit_caret_callback(
+    _xim: ffi::XIM,
+    client_data: ffi::XPointer,
+    call_data: ffi::XPointer,
+) {
+    let state = &mut *(client_data as *mut PreeditState);
+    let call_data = &mut *(call_data as *mut ffi::XIMPreeditCaretCallbackStruct);
+
+    if call_data.direction == ffi::XIMCaretDirection::XIMAbsolutePosition {
+        state.caret = (call_data.position as usize).min(state.text.len());
+        state.send_update();
+    }
+}
+
 // WARNING: this struct doesn't destroy its XIC resource when dropped.
 // This is intentional, as it doesn't have enough information to know whether or not the context
 // still exists on the server. Since `ImeInner` has that awareness, destruction must be handled
 // through `ImeInner`.
-#[derive(Debug)]
 pub struct ImeContext {
     pub ic: ffi::XIC,
     pub ic_spot: ffi::XPoint,
+    // Whether the context was created with preedit callbacks.
+    pub allowed: bool,
+    // The callbacks and their state need a fixed location in memory, since pointers to them are
+    // handed to Xlib. They must outlive `ic`.
+    _callbacks: Option<(Box<PreeditState>, Box<PreeditCallbacks>)>,
 }
 
 impl ImeContext {
@@ -44,13 +207,45 @@ impl ImeContext {
         im: ffi::XIM,
         window: ffi::Window,
         ic_spot: Option<ffi::XPoint>,
+        allowed: bool,
+        event_sender: ImeEventSender,
     ) -> Result<Self, ImeContextCreationError> {
-        let ic = if let Some(ic_spot) = ic_spot {
-            ImeContext::create_ic_with_spot(xconn, im, window, ic_spot)
+        let mut callbacks = None;
+        let ic = if allowed {
+            let mut state = Box::new(PreeditState {
+                window,
+                event_sender,
+                text: Vec::new(),
+                caret: 0,
+            });
+            let preedit_callbacks = Box::new(PreeditCallbacks::new(
+                &mut *state as *mut PreeditState as ffi::XPointer,
+            ));
+            let ic = ImeContext::create_ic_with_callbacks(
+                xconn,
+                im,
+                window,
+                ic_spot.unwrap_or(ffi::XPoint { x: 0, y: 0 }),
+                &preedit_callbacks,
+            );
+            callbacks = Some((state, preedit_callbacks));
+            ic
         } else {
-            ImeContext::create_ic(xconn, im, window)
+            None
         };
 
+        // Not every input method supports preedit callbacks, in which case it has to draw the
+        // preedit string itself.
+        let allowed = ic.is_some();
+        let ic = ic.or_else(|| {
+            callbacks = None;
+            if let Some(ic_spot) = ic_spot {
+                ImeContext::create_ic_with_spot(xconn, im, window, ic_spot)
+            } else {
+                ImeContext::create_ic(xconn, im, window)
+            }
+        });
+
         let ic = ic.ok_or(ImeContextCreationError::Null)?;
         xconn
             .check_errors()
@@ -59,6 +254,8 @@ impl ImeContext {
         Ok(ImeContext {
             ic,
             ic_spot: ic_spot.unwrap_or_else(|| ffi::XPoint { x: 0, y: 0 }),
+            allowed,
+            _callbacks: callbacks,
         })
     }
 
@@ -106,6 +303,33 @@ impl ImeContext {
         }
     }
 
+    unsafe fn create_ic_with_callbacks(
+        xconn: &Arc<XConnection>,
+        im: ffi::XIM,
+        window: ffi::Window,
+        ic_spot: ffi::XPoint,
+        callbacks: &PreeditCallbacks,
+    ) -> Option<ffi::XIC> {
+        let pre_edit_attr = create_pre_edit_attr_with_callbacks(xconn, &ic_spot, callbacks);
+        let ic = (xconn.xlib.XCreateIC)(
+            im,
+            ffi::XNInputStyle_0.as_ptr() as *const _,
+            ffi::XIMPreeditCallbacks | ffi::XIMStatusNothing,
+            ffi::XNClientWindow_0.as_ptr() as *const _,
+            window,
+            ffi::XNPreeditAttributes_0.as_ptr() as *const _,
+            pre_edit_attr.ptr,
+            ptr::null_mut::<()>(),
+        );
+        if ic.is_null() {
+            // The input method doesn't support this style, which isn't an error on our end.
+            let _ = xconn.check_errors();
+            None
+        } else {
+            Some(ic)
+        }
+    }
+
     pub fn focus(&self, xconn: &Arc<XConnection>) -> Result<(), XError> {
         unsafe {
             (xconn.xlib.XSetICFocus)(self.ic);
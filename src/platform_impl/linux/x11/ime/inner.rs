@@ -2,7 +2,7 @@ use std::{collections::HashMap, mem, ptr, sync::Arc};
 
 use super::{ffi, XConnection, XError};
 
-use super::{context::ImeContext, input_method::PotentialInputMethods};
+use super::{context::ImeContext, input_method::PotentialInputMethods, ImeEventSender};
 
 pub unsafe fn close_im(xconn: &Arc<XConnection>, im: ffi::XIM) -> Result<(), XError> {
     (xconn.xlib.XCloseIM)(im);
@@ -20,6 +20,7 @@ pub struct ImeInner {
     pub im: ffi::XIM,
     pub potential_input_methods: PotentialInputMethods,
     pub contexts: HashMap<ffi::Window, Option<ImeContext>>,
+    pub event_sender: ImeEventSender,
     // WARNING: this is initially zeroed!
     pub destroy_callback: ffi::XIMCallback,
     // Indicates whether or not the the input method was destroyed on the server end
@@ -29,12 +30,17 @@ pub struct ImeInner {
 }
 
 impl ImeInner {
-    pub fn new(xconn: Arc<XConnection>, potential_input_methods: PotentialInputMethods) -> Self {
+    pub fn new(
+        xconn: Arc<XConnection>,
+        potential_input_methods: PotentialInputMethods,
+        event_sender: ImeEventSender,
+    ) -> Self {
         ImeInner {
             xconn,
             im: ptr::null_mut(),
             potential_input_methods,
             contexts: HashMap::new(),
+            event_sender,
             destroy_callback: unsafe { mem::zeroed() },
             is_destroyed: false,
             is_fallback: false,
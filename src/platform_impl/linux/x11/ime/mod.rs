@@ -20,8 +20,29 @@ use self::{
     input_method::PotentialInputMethods,
 };
 
-pub type ImeReceiver = Receiver<(ffi::Window, i16, i16)>;
-pub type ImeSender = Sender<(ffi::Window, i16, i16)>;
+/// A request made by a window, which has to be handled on the event loop thread.
+#[derive(Debug)]
+pub enum ImeRequest {
+    /// Sets the position of the candidate box.
+    Position(ffi::Window, i16, i16),
+    /// Sets whether the window receives preedit callbacks.
+    Allow(ffi::Window, bool),
+}
+
+pub type ImeReceiver = Receiver<ImeRequest>;
+pub type ImeSender = Sender<ImeRequest>;
+
+/// An event reported by the preedit callbacks of an input context.
+#[derive(Debug, PartialEq)]
+pub enum ImeEvent {
+    Start,
+    /// The whole preedit string and the byte position of the caret in it.
+    Update(String, usize),
+    End,
+}
+
+pub type ImeEventReceiver = Receiver<(ffi::Window, ImeEvent)>;
+pub type ImeEventSender = Sender<(ffi::Window, ImeEvent)>;
 
 #[derive(Debug)]
 pub enum ImeCreationError {
@@ -37,11 +58,14 @@ pub struct Ime {
 }
 
 impl Ime {
-    pub fn new(xconn: Arc<XConnection>) -> Result<Self, ImeCreationError> {
+    pub fn new(
+        xconn: Arc<XConnection>,
+        event_sender: ImeEventSender,
+    ) -> Result<Self, ImeCreationError> {
         let potential_input_methods = PotentialInputMethods::new(&xconn);
 
         let (mut inner, client_data) = {
-            let mut inner = Box::new(ImeInner::new(xconn, potential_input_methods));
+            let mut inner = Box::new(ImeInner::new(xconn, potential_input_methods, event_sender));
             let inner_ptr = Box::into_raw(inner);
             let client_data = inner_ptr as _;
             let destroy_callback = ffi::XIMCallback {
@@ -88,12 +112,25 @@ impl Ime {
     // Ok(_) indicates that nothing went wrong internally
     // Ok(true) indicates that the action was actually performed
     // Ok(false) indicates that the action is not presently applicable
-    pub fn create_context(&mut self, window: ffi::Window) -> Result<bool, ImeContextCreationError> {
+    pub fn create_context(
+        &mut self,
+        window: ffi::Window,
+        allowed: bool,
+    ) -> Result<bool, ImeContextCreationError> {
         let context = if self.is_destroyed() {
             // Create empty entry in map, so that when IME is rebuilt, this window has a context.
             None
         } else {
-            Some(unsafe { ImeContext::new(&self.inner.xconn, self.inner.im, window, None) }?)
+            Some(unsafe {
+                ImeContext::new(
+                    &self.inner.xconn,
+                    self.inner.im,
+                    window,
+                    None,
+                    allowed,
+                    self.inner.event_sender.clone(),
+                )
+            }?)
         };
         self.inner.contexts.insert(window, context);
         Ok(!self.is_destroyed())
@@ -151,6 +188,46 @@ impl Ime {
             context.set_spot(&self.xconn, x as _, y as _);
         }
     }
+
+    pub fn is_ime_allowed(&self, window: ffi::Window) -> bool {
+        if self.is_destroyed() {
+            return false;
+        }
+        if let Some(&Some(ref context)) = self.inner.contexts.get(&window) {
+            context.allowed
+        } else {
+            false
+        }
+    }
+
+    // The input style of a context can't be changed, so the context is replaced by a new one.
+    pub fn set_ime_allowed(
+        &mut self,
+        window: ffi::Window,
+        allowed: bool,
+    ) -> Result<bool, ImeContextCreationError> {
+        if self.is_destroyed() {
+            return Ok(false);
+        }
+        let spot = match self.inner.contexts.get(&window) {
+            Some(&Some(ref context)) if context.allowed != allowed => context.ic_spot,
+            _ => return Ok(false),
+        };
+        let context = unsafe {
+            ImeContext::new(
+                &self.inner.xconn,
+                self.inner.im,
+                window,
+                Some(spot),
+                allowed,
+                self.inner.event_sender.clone(),
+            )
+        }?;
+        self.remove_context(window)
+            .map_err(ImeContextCreationError::XError)?;
+        self.inner.contexts.insert(window, Some(context));
+        Ok(true)
+    }
 }
 
 impl Drop for Ime {
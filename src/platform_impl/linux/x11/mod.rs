@@ -6,20 +6,22 @@
     target_os = "openbsd"
 ))]
 
+mod clipboard;
 mod dnd;
 mod event_processor;
-mod events;
 pub mod ffi;
 mod ime;
 mod monitor;
 pub mod util;
 mod window;
 mod xdisplay;
+mod xsettings;
 
 pub use self::{
     monitor::{MonitorHandle, VideoMode},
     window::UnownedWindow,
     xdisplay::{XConnection, XError, XNotSupported},
+    xsettings::XSettings,
 };
 
 use std::{
@@ -28,7 +30,10 @@ use std::{
     ffi::CStr,
     mem::{self, MaybeUninit},
     ops::Deref,
-    os::raw::*,
+    os::{
+        raw::*,
+        unix::io::{AsRawFd, RawFd},
+    },
     ptr,
     rc::Rc,
     slice,
@@ -43,16 +48,30 @@ use mio::{unix::EventedFd, Events, Poll, PollOpt, Ready, Token};
 use mio_extras::channel::{channel, Receiver, SendError, Sender};
 
 use self::{
-    dnd::{Dnd, DndState},
-    event_processor::EventProcessor,
-    ime::{Ime, ImeCreationError, ImeReceiver, ImeSender},
+    clipboard::Clipboard,
+    dnd::{Dnd, DndState, DragSource, PendingDrop},
+    event_processor::{EventProcessor, XkbKeyboard},
+    ime::{Ime, ImeCreationError, ImeEvent, ImeEventReceiver, ImeReceiver, ImeRequest, ImeSender},
     util::modifiers::ModifierKeymap,
+    xsettings::XSettingsClient,
 };
 use crate::{
+    dpi::PhysicalPosition,
     error::OsError as RootOsError,
-    event::{Event, StartCause},
+    event::{Event, PenButtons, PenInput, PenPhase, PenTool, StartCause},
     event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
-    platform_impl::{platform::sticky_exit_callback, PlatformSpecificWindowBuilderAttributes},
+    platform::unix::PumpStatus,
+    platform_impl::{
+        platform::{
+            dnd::DragResponse,
+            min_timeout,
+            sources::{first_timer, Sources},
+            sticky_exit_callback,
+            xkb::KbState,
+            PumpState,
+        },
+        PlatformSpecificWindowBuilderAttributes,
+    },
     window::WindowAttributes,
 };
 
@@ -63,20 +82,33 @@ pub struct EventLoopWindowTarget<T> {
     xconn: Arc<XConnection>,
     wm_delete_window: ffi::Atom,
     net_wm_ping: ffi::Atom,
+    net_wm_frame_drawn: ffi::Atom,
     ime_sender: ImeSender,
     root: ffi::Window,
     ime: RefCell<Ime>,
     windows: RefCell<HashMap<WindowId, Weak<UnownedWindow>>>,
     pending_redraws: Arc<Mutex<HashSet<WindowId>>>,
+    clipboard: Arc<Mutex<Clipboard>>,
+    // The answer to the drag and drop operation over the window it holds.
+    drag_response: Arc<Mutex<(ffi::Window, DragResponse)>>,
+    // The drag and drop operation started by one of our windows
+    drag_source: Arc<Mutex<Option<DragSource>>>,
+    // Whether the server sends XInput 2.4 gesture events.
+    xi2_gestures: bool,
+    xsettings: RefCell<XSettingsClient>,
+    pub(crate) sources: RefCell<Sources>,
     _marker: ::std::marker::PhantomData<T>,
 }
 
 pub struct EventLoop<T: 'static> {
-    poll: Poll,
+    poll: Rc<Poll>,
     event_processor: EventProcessor<T>,
     user_channel: Receiver<T>,
     user_sender: Sender<T>,
     target: Rc<RootELW<T>>,
+    pump_state: PumpState,
+    // The events of the user's sources found ready by the last wait
+    ready_sources: Vec<Event<'static, ()>>,
 }
 
 pub struct EventLoopProxy<T: 'static> {
@@ -99,10 +131,16 @@ impl<T: 'static> EventLoop<T> {
 
         let net_wm_ping = unsafe { xconn.get_atom_unchecked(b"_NET_WM_PING\0") };
 
+        let net_wm_frame_drawn = unsafe { xconn.get_atom_unchecked(b"_NET_WM_FRAME_DRAWN\0") };
+
         let dnd = Dnd::new(Arc::clone(&xconn))
             .expect("Failed to call XInternAtoms when initializing drag and drop");
 
+        let clipboard = Clipboard::new(Arc::clone(&xconn))
+            .expect("Failed to call XInternAtoms when initializing the clipboard");
+
         let (ime_sender, ime_receiver) = mpsc::channel();
+        let (ime_event_sender, ime_event_receiver) = mpsc::channel();
         // Input methods will open successfully without setting the locale, but it won't be
         // possible to actually commit pre-edit sequences.
         unsafe {
@@ -127,16 +165,22 @@ impl<T: 'static> EventLoop<T> {
             }
         }
         let ime = RefCell::new({
-            let result = Ime::new(Arc::clone(&xconn));
+            let result = Ime::new(Arc::clone(&xconn), ime_event_sender);
             if let Err(ImeCreationError::OpenFailure(ref state)) = result {
                 panic!(format!("Failed to open input method: {:#?}", state));
             }
             result.expect("Failed to set input method destruction callback")
         });
 
+        // The scale factor of the monitors depends on the DPI published over XSETTINGS.
+        let xsettings = XSettingsClient::new(Arc::clone(&xconn), root);
+        xsettings.apply();
+
         let randr_event_offset = xconn
             .select_xrandr_input(root)
             .expect("Failed to query XRandR extension");
+        // Fill the monitor cache, which RandR events are compared against to report changes.
+        xconn.available_monitors();
 
         let xi2ext = unsafe {
             let mut ext = XExtension::default();
@@ -156,9 +200,11 @@ impl<T: 'static> EventLoop<T> {
             ext
         };
 
-        unsafe {
+        // Gesture events are only sent to clients announcing XInput 2.4, and the server replies
+        // with the highest version both sides support.
+        let xi2_gestures = unsafe {
             let mut xinput_major_ver = ffi::XI_2_Major;
-            let mut xinput_minor_ver = ffi::XI_2_Minor;
+            let mut xinput_minor_ver = ffi::XI_2_4_Minor;
             if (xconn.xinput2.XIQueryVersion)(
                 xconn.display,
                 &mut xinput_major_ver,
@@ -170,7 +216,8 @@ impl<T: 'static> EventLoop<T> {
                     xinput_major_ver, xinput_minor_ver,
                 );
             }
-        }
+            (xinput_major_ver, xinput_minor_ver) >= (ffi::XI_2_Major, ffi::XI_2_4_Minor)
+        };
 
         xconn.update_cached_wm_info(root);
 
@@ -179,6 +226,26 @@ impl<T: 'static> EventLoop<T> {
         let mut mod_keymap = ModifierKeymap::new();
         mod_keymap.reset_from_x_connection(&xconn);
 
+        // Keys are translated by xkbcommon when it's available, just like on Wayland, with its
+        // state kept in sync through XKB events.
+        let connection = unsafe { (xconn.xlib_xcb.XGetXCBConnection)(xconn.display) };
+        let xkb = KbState::new_x11(connection).map(|(state, event_base, device_id)| {
+            xconn
+                .select_xkb_events(
+                    device_id as c_uint,
+                    ffi::XkbNewKeyboardNotifyMask | ffi::XkbMapNotifyMask | ffi::XkbStateNotifyMask,
+                )
+                .expect("Failed to select XKB events")
+                .queue();
+            XkbKeyboard {
+                state,
+                event_base: event_base as c_int,
+                device_id,
+            }
+        });
+
+        let poll = Rc::new(Poll::new().unwrap());
+
         let target = Rc::new(RootELW {
             p: super::EventLoopWindowTarget::X(EventLoopWindowTarget {
                 ime,
@@ -189,13 +256,18 @@ impl<T: 'static> EventLoop<T> {
                 xconn,
                 wm_delete_window,
                 net_wm_ping,
+                net_wm_frame_drawn,
                 pending_redraws: pending_redraws.clone(),
+                clipboard: Arc::new(Mutex::new(clipboard)),
+                drag_response: Arc::new(Mutex::new((0, None))),
+                drag_source: Default::default(),
+                xi2_gestures,
+                xsettings: RefCell::new(xsettings),
+                sources: RefCell::new(Sources::new(Rc::clone(&poll))),
             }),
             _marker: ::std::marker::PhantomData,
         });
 
-        let poll = Poll::new().unwrap();
-
         let (user_sender, user_channel) = channel();
 
         poll.register(
@@ -220,11 +292,15 @@ impl<T: 'static> EventLoop<T> {
             devices: Default::default(),
             randr_event_offset,
             ime_receiver,
+            ime_event_receiver,
+            composing_window: None,
             xi2ext,
             mod_keymap,
             device_mod_state: Default::default(),
+            xkb,
             num_touch: 0,
             first_touch: None,
+            pinch_scale: 1.0,
             active_window: None,
         };
 
@@ -232,7 +308,7 @@ impl<T: 'static> EventLoop<T> {
         // (The request buffer is flushed during `init_device`)
         get_xtarget(&target)
             .xconn
-            .select_xinput_events(root, ffi::XIAllDevices, ffi::XI_HierarchyChangedMask)
+            .select_xinput_events(root, ffi::XIAllDevices, ffi::XI_HierarchyChangedMask.into())
             .queue();
 
         event_processor.init_device(ffi::XIAllDevices);
@@ -243,6 +319,8 @@ impl<T: 'static> EventLoop<T> {
             user_sender,
             event_processor,
             target,
+            pump_state: PumpState::NotStarted,
+            ready_sources: Vec::new(),
         };
 
         result
@@ -267,120 +345,216 @@ impl<T: 'static> EventLoop<T> {
         F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
     {
         let mut control_flow = ControlFlow::default();
-        let mut events = Events::with_capacity(8);
         let mut cause = StartCause::Init;
 
         loop {
-            sticky_exit_callback(
-                crate::event::Event::NewEvents(cause),
+            self.dispatch_iteration(cause, &mut callback, &mut control_flow);
+
+            if control_flow == ControlFlow::Exit {
+                break;
+            }
+            cause = self.wait_events(control_flow, None);
+        }
+
+        callback(
+            crate::event::Event::LoopDestroyed,
+            &self.target,
+            &mut control_flow,
+        );
+    }
+
+    pub fn pump_events<F>(&mut self, timeout: Option<Duration>, mut callback: F) -> PumpStatus
+    where
+        F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        let (mut cause, mut control_flow) = match self.pump_state {
+            PumpState::NotStarted => (StartCause::Init, ControlFlow::default()),
+            PumpState::Running(control_flow) => {
+                (self.wait_events(control_flow, timeout), control_flow)
+            }
+            PumpState::Exited => return PumpStatus::Exit,
+        };
+
+        loop {
+            self.dispatch_iteration(cause, &mut callback, &mut control_flow);
+
+            // Xlib may have read events from the connection while the callback ran, which leaves
+            // the file descriptor unreadable, so they're dispatched right away.
+            if control_flow == ControlFlow::Exit || !self.event_processor.poll() {
+                break;
+            }
+            cause = self.wait_events(control_flow, Some(Duration::from_millis(0)));
+        }
+
+        if control_flow == ControlFlow::Exit {
+            callback(
+                crate::event::Event::LoopDestroyed,
                 &self.target,
                 &mut control_flow,
-                &mut callback,
             );
+            self.pump_state = PumpState::Exited;
+            PumpStatus::Exit
+        } else {
+            self.pump_state = PumpState::Running(control_flow);
+            if control_flow == ControlFlow::Poll || self.redraws_pending() {
+                PumpStatus::Pending
+            } else {
+                PumpStatus::Continue
+            }
+        }
+    }
 
-            // Process all pending events
-            self.drain_events(&mut callback, &mut control_flow);
+    // Whether redraws were requested too late to be served by the last iteration.
+    fn redraws_pending(&self) -> bool {
+        !get_xtarget(&self.target)
+            .pending_redraws
+            .lock()
+            .unwrap()
+            .is_empty()
+    }
 
-            let wt = get_xtarget(&self.target);
+    #[inline]
+    pub fn as_raw_fd(&self) -> RawFd {
+        self.poll.as_raw_fd()
+    }
 
-            // Empty the user event buffer
-            {
-                while let Ok(event) = self.user_channel.try_recv() {
-                    sticky_exit_callback(
-                        crate::event::Event::UserEvent(event),
-                        &self.target,
-                        &mut control_flow,
-                        &mut callback,
-                    );
-                }
-            }
-            // send MainEventsCleared
-            {
+    // Dispatches one iteration of the loop, from `NewEvents` to `RedrawEventsCleared`.
+    fn dispatch_iteration<F>(
+        &mut self,
+        cause: StartCause,
+        callback: &mut F,
+        control_flow: &mut ControlFlow,
+    ) where
+        F: FnMut(Event<'_, T>, &RootELW<T>, &mut ControlFlow),
+    {
+        sticky_exit_callback(
+            crate::event::Event::NewEvents(cause),
+            &self.target,
+            control_flow,
+            callback,
+        );
+
+        for event in self.ready_sources.drain(..) {
+            let event = event.map_nonuser_event().unwrap();
+            sticky_exit_callback(event, &self.target, control_flow, callback);
+        }
+
+        // Process all pending events
+        self.drain_events(callback, control_flow);
+
+        let wt = get_xtarget(&self.target);
+
+        // Empty the user event buffer
+        {
+            while let Ok(event) = self.user_channel.try_recv() {
                 sticky_exit_callback(
-                    crate::event::Event::MainEventsCleared,
+                    crate::event::Event::UserEvent(event),
                     &self.target,
-                    &mut control_flow,
-                    &mut callback,
+                    control_flow,
+                    callback,
                 );
             }
-            // Empty the redraw requests
-            {
-                // Release the lock to prevent deadlock
-                let windows: Vec<_> = wt.pending_redraws.lock().unwrap().drain().collect();
-
-                for wid in windows {
-                    sticky_exit_callback(
-                        Event::RedrawRequested(crate::window::WindowId(super::WindowId::X(wid))),
-                        &self.target,
-                        &mut control_flow,
-                        &mut callback,
-                    );
+        }
+        // send MainEventsCleared
+        {
+            sticky_exit_callback(
+                crate::event::Event::MainEventsCleared,
+                &self.target,
+                control_flow,
+                callback,
+            );
+        }
+        // Empty the redraw requests
+        {
+            // Release the lock to prevent deadlock
+            let windows: Vec<_> = wt.pending_redraws.lock().unwrap().drain().collect();
+
+            for wid in windows {
+                let window = wt.windows.borrow().get(&wid).and_then(Weak::upgrade);
+                if let Some(ref window) = window {
+                    window.start_frame();
                 }
-            }
-            // send RedrawEventsCleared
-            {
                 sticky_exit_callback(
-                    crate::event::Event::RedrawEventsCleared,
+                    Event::RedrawRequested(crate::window::WindowId(super::WindowId::X(wid))),
                     &self.target,
-                    &mut control_flow,
-                    &mut callback,
+                    control_flow,
+                    callback,
                 );
-            }
-
-            let start = Instant::now();
-            let (deadline, timeout);
-
-            match control_flow {
-                ControlFlow::Exit => break,
-                ControlFlow::Poll => {
-                    cause = StartCause::Poll;
-                    deadline = None;
-                    timeout = Some(Duration::from_millis(0));
-                }
-                ControlFlow::Wait => {
-                    cause = StartCause::WaitCancelled {
-                        start,
-                        requested_resume: None,
-                    };
-                    deadline = None;
-                    timeout = None;
+                if let Some(ref window) = window {
+                    window.finish_frame();
                 }
-                ControlFlow::WaitUntil(wait_deadline) => {
-                    cause = StartCause::ResumeTimeReached {
-                        start,
-                        requested_resume: wait_deadline,
-                    };
-                    timeout = if wait_deadline > start {
-                        Some(wait_deadline - start)
-                    } else {
-                        Some(Duration::from_millis(0))
-                    };
-                    deadline = Some(wait_deadline);
-                }
-            }
-
-            // If the XConnection already contains buffered events, we don't
-            // need to wait for data on the socket.
-            if !self.event_processor.poll() {
-                self.poll.poll(&mut events, timeout).unwrap();
-                events.clear();
             }
+        }
+        // send RedrawEventsCleared
+        {
+            sticky_exit_callback(
+                crate::event::Event::RedrawEventsCleared,
+                &self.target,
+                control_flow,
+                callback,
+            );
+        }
+    }
 
-            let wait_cancelled = deadline.map_or(false, |deadline| Instant::now() < deadline);
+    // Waits for events as long as `control_flow` asks for, but no longer than `timeout`, and
+    // returns the cause of the next iteration.
+    fn wait_events(&mut self, control_flow: ControlFlow, timeout: Option<Duration>) -> StartCause {
+        let mut events = Events::with_capacity(8);
+        let start = Instant::now();
+        let (deadline, wait) = match control_flow {
+            ControlFlow::Exit | ControlFlow::Poll => (None, Some(Duration::from_millis(0))),
+            ControlFlow::Wait => (None, None),
+            ControlFlow::WaitUntil(wait_deadline) => (
+                Some(wait_deadline),
+                Some(wait_deadline.saturating_duration_since(start)),
+            ),
+        };
 
-            if wait_cancelled {
-                cause = StartCause::WaitCancelled {
+        // If the XConnection already contains buffered events, we don't
+        // need to wait for data on the socket, nor for redraws which are already due.
+        let wait = if self.event_processor.poll() || self.redraws_pending() {
+            Some(Duration::from_millis(0))
+        } else {
+            let sources = get_xtarget(&self.target).sources.borrow();
+            let timer = sources
+                .next_deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+            // Wake up to cancel a drop the target never finishes.
+            let drop = self
+                .event_processor
+                .drag_source_deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+            // Wake up to reject a drop whose source never converts its data.
+            let pending_drop = self
+                .event_processor
+                .pending_drop_deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+            min_timeout(
+                min_timeout(min_timeout(min_timeout(wait, timeout), timer), drop),
+                pending_drop,
+            )
+        };
+        self.poll.poll(&mut events, wait).unwrap();
+        let now = Instant::now();
+        self.ready_sources = get_xtarget(&self.target)
+            .sources
+            .borrow_mut()
+            .ready(&events, now);
+
+        match (control_flow, deadline) {
+            (ControlFlow::Poll, _) => StartCause::Poll,
+            (_, Some(deadline)) if now >= deadline => StartCause::ResumeTimeReached {
+                start,
+                requested_resume: deadline,
+            },
+            _ => match first_timer(&self.ready_sources) {
+                Some(id) => StartCause::TimerFired(id),
+                None => StartCause::WaitCancelled {
                     start,
                     requested_resume: deadline,
-                };
-            }
+                },
+            },
         }
-
-        callback(
-            crate::event::Event::LoopDestroyed,
-            &self.target,
-            &mut control_flow,
-        );
     }
 
     pub fn run<F>(mut self, callback: F) -> !
@@ -400,26 +574,30 @@ impl<T: 'static> EventLoop<T> {
 
         let wt = get_xtarget(&self.target);
 
+        let mut handler = |event: Event<'_, T>| {
+            sticky_exit_callback(
+                event,
+                target,
+                control_flow,
+                &mut |event, window_target, control_flow| {
+                    if let Event::RedrawRequested(crate::window::WindowId(super::WindowId::X(
+                        wid,
+                    ))) = event
+                    {
+                        wt.pending_redraws.lock().unwrap().insert(wid);
+                    } else {
+                        callback(event, window_target, control_flow);
+                    }
+                },
+            );
+        };
+
         while unsafe { self.event_processor.poll_one_event(xev.as_mut_ptr()) } {
             let mut xev = unsafe { xev.assume_init() };
-            self.event_processor.process_event(&mut xev, |event| {
-                sticky_exit_callback(
-                    event,
-                    target,
-                    control_flow,
-                    &mut |event, window_target, control_flow| {
-                        if let Event::RedrawRequested(crate::window::WindowId(
-                            super::WindowId::X(wid),
-                        )) = event
-                        {
-                            wt.pending_redraws.lock().unwrap().insert(wid);
-                        } else {
-                            callback(event, window_target, control_flow);
-                        }
-                    },
-                );
-            });
+            self.event_processor.process_event(&mut xev, &mut handler);
         }
+        self.event_processor.expire_drag_source(&mut handler);
+        self.event_processor.expire_pending_drop(&mut handler);
     }
 }
 
@@ -436,6 +614,12 @@ impl<T> EventLoopWindowTarget<T> {
     pub fn x_connection(&self) -> &Arc<XConnection> {
         &self.xconn
     }
+
+    /// Returns the settings published by the XSETTINGS manager.
+    #[inline]
+    pub fn xsettings(&self) -> XSettings {
+        self.xsettings.borrow().settings().clone()
+    }
 }
 
 impl<T: 'static> EventLoopProxy<T> {
@@ -582,6 +766,8 @@ fn mkdid(w: c_int) -> crate::event::DeviceId {
 struct Device {
     name: String,
     scroll_axes: Vec<(i32, ScrollAxis)>,
+    // Only set for the tools of graphics tablets.
+    pen: Option<Pen>,
     // For master devices, this is the paired device (pointer <-> keyboard).
     // For slave devices, this is the master.
     attachment: c_int,
@@ -600,10 +786,151 @@ enum ScrollOrientation {
     Horizontal,
 }
 
+/// The state of a tablet tool, which is kept since events only carry the axes that changed.
+#[derive(Debug)]
+struct Pen {
+    tool: PenTool,
+    pressure: Option<PenAxis>,
+    tilt_x: Option<PenAxis>,
+    tilt_y: Option<PenAxis>,
+    rotation: Option<PenAxis>,
+    buttons: PenButtons,
+    // The window the tool was last seen over, which stands in for its proximity.
+    window: Option<ffi::Window>,
+    position: PhysicalPosition<f64>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct PenAxis {
+    number: i32,
+    min: f64,
+    max: f64,
+    value: f64,
+}
+
+impl PenAxis {
+    fn normalized(&self) -> f64 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    // Maps the axis range onto -1.0..=1.0, with the middle of the range at 0.0.
+    fn centered(&self) -> f64 {
+        self.normalized() * 2.0 - 1.0
+    }
+}
+
+// X drivers don't report the unit of the tilt axes. Both xf86-input-wacom and
+// xf86-input-libinput span about -64..64 over a physical range of roughly 64 degrees either
+// way, so the axis range is scaled onto that to cope with drivers using other ranges.
+const TILT_RANGE_DEGREES: f64 = 64.0;
+
+impl Pen {
+    // Identifies tablet tools by their pressure axis, leaving out touchscreens.
+    fn new(xconn: &XConnection, name: &str, classes: &[*const ffi::XIAnyClassInfo]) -> Option<Pen> {
+        let label = |name: &[u8]| unsafe { xconn.get_atom_unchecked(name) };
+        let (pressure, tilt_x, tilt_y, rotation) = (
+            label(b"Abs Pressure\0"),
+            label(b"Abs Tilt X\0"),
+            label(b"Abs Tilt Y\0"),
+            label(b"Abs Rotary Z\0"),
+        );
+        let mut pen = Pen {
+            tool: if name.to_lowercase().contains("eraser") {
+                PenTool::Eraser
+            } else {
+                PenTool::Pen
+            },
+            pressure: None,
+            tilt_x: None,
+            tilt_y: None,
+            rotation: None,
+            buttons: PenButtons::empty(),
+            window: None,
+            position: PhysicalPosition::new(0.0, 0.0),
+        };
+        for class_ptr in classes {
+            let class = unsafe { &**class_ptr };
+            match class._type {
+                ffi::XITouchClass => return None,
+                ffi::XIValuatorClass => {
+                    let info = unsafe {
+                        mem::transmute::<&ffi::XIAnyClassInfo, &ffi::XIValuatorClassInfo>(class)
+                    };
+                    let axis = Some(PenAxis {
+                        number: info.number,
+                        min: info.min,
+                        max: info.max,
+                        value: info.value,
+                    });
+                    match info.label {
+                        label if label == pressure => pen.pressure = axis,
+                        label if label == tilt_x => pen.tilt_x = axis,
+                        label if label == tilt_y => pen.tilt_y = axis,
+                        label if label == rotation => pen.rotation = axis,
+                        _ => (),
+                    }
+                }
+                _ => {}
+            }
+        }
+        pen.pressure.map(|_| pen)
+    }
+
+    fn update_axes(&mut self, valuators: &ffi::XIValuatorState) {
+        let mask = unsafe { slice::from_raw_parts(valuators.mask, valuators.mask_len as usize) };
+        let mut value = valuators.values;
+        for i in 0..valuators.mask_len * 8 {
+            if ffi::XIMaskIsSet(mask, i) {
+                let x = unsafe { *value };
+                for axis in [
+                    &mut self.pressure,
+                    &mut self.tilt_x,
+                    &mut self.tilt_y,
+                    &mut self.rotation,
+                ]
+                .iter_mut()
+                {
+                    if let Some(ref mut axis) = **axis {
+                        if axis.number == i {
+                            axis.value = x;
+                        }
+                    }
+                }
+                value = unsafe { value.offset(1) };
+            }
+        }
+    }
+
+    fn input(&self, device_id: crate::event::DeviceId, phase: PenPhase) -> PenInput {
+        let tilt = match (self.tilt_x, self.tilt_y) {
+            (Some(x), Some(y)) => Some((
+                x.centered() * TILT_RANGE_DEGREES,
+                y.centered() * TILT_RANGE_DEGREES,
+            )),
+            _ => None,
+        };
+        PenInput {
+            device_id,
+            phase,
+            tool: self.tool,
+            location: self.position,
+            pressure: self.pressure.map(|axis| axis.normalized()),
+            tilt,
+            rotation: self.rotation.map(|axis| axis.normalized() * 360.0),
+            buttons: self.buttons,
+        }
+    }
+}
+
 impl Device {
     fn new<T: 'static>(el: &EventProcessor<T>, info: &ffi::XIDeviceInfo) -> Self {
         let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() };
         let mut scroll_axes = Vec::new();
+        let mut pen = None;
 
         let wt = get_xtarget(&el.target);
 
@@ -616,7 +943,7 @@ impl Device {
                 | ffi::XI_RawKeyReleaseMask;
             // The request buffer is flushed when we poll for events
             wt.xconn
-                .select_xinput_events(wt.root, info.deviceid, mask)
+                .select_xinput_events(wt.root, info.deviceid, mask.into())
                 .queue();
 
             // Identify scroll axes
@@ -643,11 +970,14 @@ impl Device {
                     _ => {}
                 }
             }
+
+            pen = Pen::new(&wt.xconn, &name, Device::classes(info));
         }
 
         let mut device = Device {
             name: name.into_owned(),
             scroll_axes,
+            pen,
             attachment: info.attachment,
         };
         device.reset_scroll_position(info);
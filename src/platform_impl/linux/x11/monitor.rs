@@ -12,7 +12,10 @@ use super::{
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize},
     monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
-    platform_impl::{MonitorHandle as PlatformMonitorHandle, VideoMode as PlatformVideoMode},
+    platform_impl::{
+        platform::X11_BACKEND, MonitorHandle as PlatformMonitorHandle,
+        VideoMode as PlatformVideoMode,
+    },
 };
 
 // Used for testing. This should always be committed as false.
@@ -130,6 +133,15 @@ impl MonitorHandle {
         })
     }
 
+    /// Returns whether `other`, a previous state of this monitor, has the same configuration.
+    pub(crate) fn same_configuration(&self, other: &MonitorHandle) -> bool {
+        self.dimensions == other.dimensions
+            && self.position == other.position
+            && self.primary == other.primary
+            && self.scale_factor == other.scale_factor
+            && self.video_modes == other.video_modes
+    }
+
     pub fn dummy() -> Self {
         MonitorHandle {
             id: 0,
@@ -170,6 +182,17 @@ impl MonitorHandle {
         self.scale_factor
     }
 
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        // The lock is released before talking to the X server, as the error handler takes it.
+        let xconn = X11_BACKEND.lock().as_ref().ok().cloned();
+        // Panels come and go, so unlike the rest of the monitor this isn't cached.
+        let work_area = match xconn {
+            Some(ref xconn) if !self.is_dummy() => xconn.get_work_area(&self.rect),
+            _ => self.rect.clone(),
+        };
+        (work_area.position().into(), work_area.size().into())
+    }
+
     #[inline]
     pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
         let monitor = self.clone();
@@ -69,4 +69,17 @@ impl XConnection {
         );*/
         Ok(atoms)
     }
+
+    pub fn get_atom_name(&self, atom: ffi::Atom) -> Option<String> {
+        unsafe {
+            let name = (self.xlib.XGetAtomName)(self.display, atom);
+            if name.is_null() {
+                let _ = self.check_errors();
+                return None;
+            }
+            let string = CStr::from_ptr(name).to_string_lossy().into_owned();
+            (self.xlib.XFree)(name as _);
+            Some(string)
+        }
+    }
 }
@@ -1,18 +1,114 @@
-use crate::window::CursorIcon;
+use std::{ffi::CString, slice};
+
+use crate::{cursor::CursorImage, window::CursorIcon, window::CustomCursor};
 
 use super::*;
 
+/// The cursor of a window, either loaded from the cursor theme or created from an image.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WindowCursor {
+    Icon(CursorIcon),
+    Custom(CustomCursor),
+}
+
+impl Default for WindowCursor {
+    fn default() -> Self {
+        WindowCursor::Icon(CursorIcon::default())
+    }
+}
+
 impl XConnection {
-    pub fn set_cursor_icon(&self, window: ffi::Window, cursor: Option<CursorIcon>) {
+    pub fn set_cursor(&self, window: ffi::Window, cursor: Option<&WindowCursor>) {
+        let icon = match cursor {
+            Some(&WindowCursor::Icon(icon)) => Some(icon),
+            Some(&WindowCursor::Custom(ref cursor)) => {
+                // Custom cursors aren't cached, as there's no telling when they stop being used.
+                // The server keeps the cursor alive for as long as the window shows it.
+                let xcursor = self.create_custom_cursor(cursor);
+                self.update_cursor(window, xcursor);
+                if xcursor != 0 {
+                    unsafe { (self.xlib.XFreeCursor)(self.display, xcursor) };
+                }
+                return;
+            }
+            None => None,
+        };
         let cursor = *self
             .cursor_cache
             .lock()
-            .entry(cursor)
-            .or_insert_with(|| self.get_cursor(cursor));
+            .entry(icon)
+            .or_insert_with(|| self.get_cursor(icon));
 
         self.update_cursor(window, cursor);
     }
 
+    /// Loads the cursors from the theme `name` at `size` pixels from now on. The settings that
+    /// are `None` are left as they are.
+    pub fn set_cursor_theme(&self, name: Option<&str>, size: Option<u32>) {
+        unsafe {
+            if let Some(name) = name.and_then(|name| CString::new(name).ok()) {
+                (self.xcursor.XcursorSetTheme)(self.display, name.as_ptr());
+            }
+            if let Some(size) = size {
+                (self.xcursor.XcursorSetDefaultSize)(self.display, size as c_int);
+            }
+        }
+
+        // Windows keep the cursors they already have until they're given another one.
+        self.cursor_cache
+            .lock()
+            .retain(|cursor, xcursor| match *cursor {
+                Some(_) => {
+                    unsafe { (self.xlib.XFreeCursor)(self.display, *xcursor) };
+                    false
+                }
+                None => true,
+            });
+    }
+
+    // Animated cursors are handled by Xcursor, which cycles through the frames on the server.
+    fn create_custom_cursor(&self, cursor: &CustomCursor) -> ffi::Cursor {
+        unsafe {
+            let images = (self.xcursor.XcursorImagesCreate)(cursor.frames.len() as c_int);
+            if images.is_null() {
+                return 0;
+            }
+            for frame in cursor.frames.iter() {
+                let xcursor_image = self.create_xcursor_image(&frame.image);
+                if xcursor_image.is_null() {
+                    (self.xcursor.XcursorImagesDestroy)(images);
+                    return 0;
+                }
+                if cursor.is_animated() {
+                    (*xcursor_image).delay = frame.duration.as_millis() as c_uint;
+                }
+                *(*images).images.offset((*images).nimage as isize) = xcursor_image;
+                (*images).nimage += 1;
+            }
+
+            let xcursor = (self.xcursor.XcursorImagesLoadCursor)(self.display, images);
+            // This also destroys the images.
+            (self.xcursor.XcursorImagesDestroy)(images);
+            xcursor
+        }
+    }
+
+    unsafe fn create_xcursor_image(&self, image: &CursorImage) -> *mut ffi::XcursorImage {
+        let xcursor_image =
+            (self.xcursor.XcursorImageCreate)(image.width as c_int, image.height as c_int);
+        if xcursor_image.is_null() {
+            return xcursor_image;
+        }
+        (*xcursor_image).xhot = image.hotspot_x;
+        (*xcursor_image).yhot = image.hotspot_y;
+        let pixels = slice::from_raw_parts_mut(
+            (*xcursor_image).pixels,
+            (image.width * image.height) as usize,
+        );
+        pixels.copy_from_slice(&image.argb_pixels());
+        xcursor_image
+    }
+
     fn create_empty_cursor(&self) -> ffi::Cursor {
         let data = 0;
         let pixmap = unsafe {
@@ -23,6 +23,23 @@ impl AaRect {
         }
     }
 
+    fn from_edges(left: i64, top: i64, right: i64, bottom: i64) -> Self {
+        AaRect {
+            x: left,
+            y: top,
+            width: cmp::max(0, right - left),
+            height: cmp::max(0, bottom - top),
+        }
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        (self.x as i32, self.y as i32)
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width as u32, self.height as u32)
+    }
+
     pub fn contains_point(&self, x: i64, y: i64) -> bool {
         x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
     }
@@ -38,6 +55,116 @@ impl AaRect {
         );
         x_overlap * y_overlap
     }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        AaRect::from_edges(
+            cmp::max(self.x, other.x),
+            cmp::max(self.y, other.y),
+            cmp::min(self.x + self.width, other.x + other.width),
+            cmp::min(self.y + self.height, other.y + other.height),
+        )
+    }
+}
+
+// The space reserved by a panel along the edges of the X screen, as described by
+// `_NET_WM_STRUT_PARTIAL`: the widths of the reserved strips, followed by the ranges they span.
+#[derive(Debug, Clone)]
+struct Strut {
+    left: i64,
+    right: i64,
+    top: i64,
+    bottom: i64,
+    left_start_y: i64,
+    left_end_y: i64,
+    right_start_y: i64,
+    right_end_y: i64,
+    top_start_x: i64,
+    top_end_x: i64,
+    bottom_start_x: i64,
+    bottom_end_x: i64,
+}
+
+impl Strut {
+    fn from_partial(values: &[c_ulong]) -> Self {
+        let value = |i: usize| values[i] as i64;
+        Strut {
+            left: value(0),
+            right: value(1),
+            top: value(2),
+            bottom: value(3),
+            left_start_y: value(4),
+            left_end_y: value(5),
+            right_start_y: value(6),
+            right_end_y: value(7),
+            top_start_x: value(8),
+            top_end_x: value(9),
+            bottom_start_x: value(10),
+            bottom_end_x: value(11),
+        }
+    }
+
+    // `_NET_WM_STRUT` is the older form of the hint, whose strips span the whole screen.
+    fn from_full(values: &[c_ulong], (screen_width, screen_height): (i64, i64)) -> Self {
+        Strut {
+            left: values[0] as i64,
+            right: values[1] as i64,
+            top: values[2] as i64,
+            bottom: values[3] as i64,
+            left_start_y: 0,
+            left_end_y: screen_height - 1,
+            right_start_y: 0,
+            right_end_y: screen_height - 1,
+            top_start_x: 0,
+            top_end_x: screen_width - 1,
+            bottom_start_x: 0,
+            bottom_end_x: screen_width - 1,
+        }
+    }
+
+    // Removes the strips that cover `area` from its sides.
+    fn exclude_from(&self, area: &AaRect, (screen_width, screen_height): (i64, i64)) -> AaRect {
+        let (mut left, mut top) = (area.x, area.y);
+        let (mut right, mut bottom) = (area.x + area.width, area.y + area.height);
+        let covers = |x: i64, y: i64, width: i64, height: i64| {
+            width > 0
+                && height > 0
+                && area.get_overlapping_area(&AaRect {
+                    x,
+                    y,
+                    width,
+                    height,
+                }) > 0
+        };
+
+        let left_span = self.left_end_y - self.left_start_y + 1;
+        if covers(0, self.left_start_y, self.left, left_span) {
+            left = cmp::max(left, self.left);
+        }
+        let right_span = self.right_end_y - self.right_start_y + 1;
+        if covers(
+            screen_width - self.right,
+            self.right_start_y,
+            self.right,
+            right_span,
+        ) {
+            right = cmp::min(right, screen_width - self.right);
+        }
+        let top_span = self.top_end_x - self.top_start_x + 1;
+        if covers(self.top_start_x, 0, top_span, self.top) {
+            top = cmp::max(top, self.top);
+        }
+        let bottom_span = self.bottom_end_x - self.bottom_start_x + 1;
+        if covers(
+            self.bottom_start_x,
+            screen_height - self.bottom,
+            bottom_span,
+            self.bottom,
+        ) {
+            bottom = cmp::min(bottom, screen_height - self.bottom);
+        }
+
+        AaRect::from_edges(left, top, right, bottom)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -216,6 +343,102 @@ impl XConnection {
         })
     }
 
+    // Returns the struts of the docks managed by the window manager, or `None` if it doesn't
+    // tell which windows it manages.
+    fn get_dock_struts(&self, root: ffi::Window, screen_size: (i64, i64)) -> Option<Vec<Strut>> {
+        let client_list_atom = unsafe { self.get_atom_unchecked(b"_NET_CLIENT_LIST\0") };
+
+        if !hint_is_supported(client_list_atom) {
+            return None;
+        }
+
+        let client_list: Vec<ffi::Window> = self
+            .get_property(root, client_list_atom, ffi::XA_WINDOW)
+            .ok()?;
+
+        let type_atom = unsafe { self.get_atom_unchecked(b"_NET_WM_WINDOW_TYPE\0") };
+        let dock_atom = unsafe { self.get_atom_unchecked(b"_NET_WM_WINDOW_TYPE_DOCK\0") };
+        let strut_partial_atom = unsafe { self.get_atom_unchecked(b"_NET_WM_STRUT_PARTIAL\0") };
+        let strut_atom = unsafe { self.get_atom_unchecked(b"_NET_WM_STRUT\0") };
+
+        let struts = client_list
+            .into_iter()
+            .filter(|&window| {
+                self.get_property::<ffi::Atom>(window, type_atom, ffi::XA_ATOM)
+                    .map(|types| types.contains(&dock_atom))
+                    .unwrap_or(false)
+            })
+            .filter_map(|window| {
+                let partial: Vec<c_ulong> = self
+                    .get_property(window, strut_partial_atom, ffi::XA_CARDINAL)
+                    .unwrap_or_default();
+                if partial.len() >= 12 {
+                    return Some(Strut::from_partial(&partial));
+                }
+                let full: Vec<c_ulong> = self
+                    .get_property(window, strut_atom, ffi::XA_CARDINAL)
+                    .unwrap_or_default();
+                if full.len() >= 4 {
+                    return Some(Strut::from_full(&full, screen_size));
+                }
+                None
+            })
+            .collect();
+        Some(struts)
+    }
+
+    // `_NET_WORKAREA` of the current desktop. It spans every monitor, so it can only tell how
+    // much the panels take from the edges of the whole X screen.
+    fn get_net_work_area(&self, root: ffi::Window) -> Option<AaRect> {
+        let work_area_atom = unsafe { self.get_atom_unchecked(b"_NET_WORKAREA\0") };
+
+        if !hint_is_supported(work_area_atom) {
+            return None;
+        }
+
+        let current_desktop_atom = unsafe { self.get_atom_unchecked(b"_NET_CURRENT_DESKTOP\0") };
+        let desktop = self
+            .get_property::<c_ulong>(root, current_desktop_atom, ffi::XA_CARDINAL)
+            .ok()
+            .and_then(|desktop| desktop.first().cloned())
+            .unwrap_or(0) as usize;
+
+        let work_areas: Vec<c_ulong> = self
+            .get_property(root, work_area_atom, ffi::XA_CARDINAL)
+            .ok()?;
+        let work_area = work_areas
+            .get(desktop * 4..desktop * 4 + 4)
+            .or_else(|| work_areas.get(0..4))?;
+        Some(AaRect::new(
+            (work_area[0] as i32, work_area[1] as i32),
+            (work_area[2] as u32, work_area[3] as u32),
+        ))
+    }
+
+    /// Returns the part of `monitor` that isn't covered by panels and docks.
+    pub fn get_work_area(&self, monitor: &AaRect) -> AaRect {
+        let root = unsafe { (self.xlib.XDefaultRootWindow)(self.display) };
+        let screen_size = match self.get_geometry(root) {
+            Ok(geometry) => (geometry.width as i64, geometry.height as i64),
+            Err(_) => return monitor.clone(),
+        };
+
+        // The struts of the docks tell which monitor each panel is on. Only when there are none
+        // to look at is `_NET_WORKAREA` used, as it would also shrink the monitors next to a
+        // panel.
+        match self.get_dock_struts(root, screen_size) {
+            Some(ref struts) if !struts.is_empty() => {
+                struts.iter().fold(monitor.clone(), |area, strut| {
+                    strut.exclude_from(&area, screen_size)
+                })
+            }
+            _ => match self.get_net_work_area(root) {
+                Some(work_area) => monitor.intersection(&work_area),
+                None => monitor.clone(),
+            },
+        }
+    }
+
     pub fn is_top_level(&self, window: ffi::Window, root: ffi::Window) -> Option<bool> {
         let client_list_atom = unsafe { self.get_atom_unchecked(b"_NET_CLIENT_LIST\0") };
 
@@ -2,6 +2,7 @@ use std::slice;
 use std::sync::Arc;
 
 use super::*;
+use crate::window::ResizeDirection;
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -21,6 +22,35 @@ impl From<bool> for StateOperation {
     }
 }
 
+/// The operation requested with a `_NET_WM_MOVERESIZE` message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveResizeOperation {
+    SizeTopLeft = 0,     // _NET_WM_MOVERESIZE_SIZE_TOPLEFT
+    SizeTop = 1,         // _NET_WM_MOVERESIZE_SIZE_TOP
+    SizeTopRight = 2,    // _NET_WM_MOVERESIZE_SIZE_TOPRIGHT
+    SizeRight = 3,       // _NET_WM_MOVERESIZE_SIZE_RIGHT
+    SizeBottomRight = 4, // _NET_WM_MOVERESIZE_SIZE_BOTTOMRIGHT
+    SizeBottom = 5,      // _NET_WM_MOVERESIZE_SIZE_BOTTOM
+    SizeBottomLeft = 6,  // _NET_WM_MOVERESIZE_SIZE_BOTTOMLEFT
+    SizeLeft = 7,        // _NET_WM_MOVERESIZE_SIZE_LEFT
+    Move = 8,            // _NET_WM_MOVERESIZE_MOVE
+}
+
+impl From<ResizeDirection> for MoveResizeOperation {
+    fn from(direction: ResizeDirection) -> Self {
+        match direction {
+            ResizeDirection::East => MoveResizeOperation::SizeRight,
+            ResizeDirection::North => MoveResizeOperation::SizeTop,
+            ResizeDirection::NorthEast => MoveResizeOperation::SizeTopRight,
+            ResizeDirection::NorthWest => MoveResizeOperation::SizeTopLeft,
+            ResizeDirection::South => MoveResizeOperation::SizeBottom,
+            ResizeDirection::SouthEast => MoveResizeOperation::SizeBottomRight,
+            ResizeDirection::SouthWest => MoveResizeOperation::SizeBottomLeft,
+            ResizeDirection::West => MoveResizeOperation::SizeLeft,
+        }
+    }
+}
+
 /// X window type. Maps directly to
 /// [`_NET_WM_WINDOW_TYPE`](https://specifications.freedesktop.org/wm-spec/wm-spec-1.5.html).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -141,6 +171,11 @@ impl MotifHints {
         self.hints.decorations = decorations as c_ulong;
     }
 
+    pub fn decorations(&self) -> bool {
+        // Windows are decorated unless the hints say otherwise.
+        self.hints.flags & mwm::MWM_HINTS_DECORATIONS == 0 || self.hints.decorations != 0
+    }
+
     pub fn set_maximizable(&mut self, maximizable: bool) {
         if maximizable {
             self.add_func(mwm::MWM_FUNC_MAXIMIZE);
@@ -45,6 +45,19 @@ impl<'a> PointerState<'a> {
     pub fn get_modifier_state(&self) -> ModifiersState {
         ModifiersState::from_x11(&self.modifiers)
     }
+
+    /// Returns the lowest numbered button which is held down, if any.
+    pub fn pressed_button(&self) -> Option<c_uint> {
+        if self.buttons.mask.is_null() {
+            return None;
+        }
+        let mask =
+            unsafe { slice::from_raw_parts(self.buttons.mask, self.buttons.mask_len as usize) };
+        // Bit 0 doesn't stand for any button.
+        (1..mask.len() * 8)
+            .find(|&button| mask[button / 8] & (1 << (button % 8)) != 0)
+            .map(|button| button as c_uint)
+    }
 }
 
 impl<'a> Drop for PointerState<'a> {
@@ -63,7 +76,7 @@ impl XConnection {
         &self,
         window: c_ulong,
         device_id: c_int,
-        mask: i32,
+        mask: i64,
     ) -> Flusher<'_> {
         let mut event_mask = ffi::XIEventMask {
             deviceid: device_id,
@@ -81,7 +94,6 @@ impl XConnection {
         Flusher::new(self)
     }
 
-    #[allow(dead_code)]
     pub fn select_xkb_events(&self, device_id: c_uint, mask: c_ulong) -> Option<Flusher<'_>> {
         let status = unsafe { (self.xlib.XkbSelectEvents)(self.display, device_id, mask, mask) };
         if status == ffi::True {
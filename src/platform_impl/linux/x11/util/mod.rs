@@ -17,8 +17,8 @@ mod window_property;
 mod wm;
 
 pub use self::{
-    atom::*, client_msg::*, format::*, geometry::*, hint::*, icon::*, input::*, memory::*,
-    randr::*, window_property::*, wm::*,
+    atom::*, client_msg::*, cursor::*, format::*, geometry::*, hint::*, icon::*, input::*,
+    memory::*, randr::*, window_property::*, wm::*,
 };
 
 use std::{
@@ -31,8 +31,13 @@ pub fn calc_dpi_factor(
 }
 
 impl XConnection {
-    // Retrieve DPI from Xft.dpi property
+    // Retrieve DPI from the Xft/DPI setting, or else the Xft.dpi resource
     pub unsafe fn get_xft_dpi(&self) -> Option<f64> {
+        // The resource manager string is only read when the display is opened, so only
+        // XSETTINGS can tell about changes.
+        if let Some(dpi) = *self.xsettings_dpi.lock() {
+            return Some(dpi);
+        }
         (self.xlib.XrmInitialize)();
         let resource_manager_str = (self.xlib.XResourceManagerString)(self.display);
         if resource_manager_str == ptr::null_mut() {
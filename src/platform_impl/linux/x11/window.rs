@@ -17,16 +17,28 @@ use parking_lot::Mutex;
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
     error::{ExternalError, NotSupportedError, OsError as RootOsError},
+    event::{DragAction, WindowState},
     monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
     platform_impl::{
-        x11::{ime::ImeContextCreationError, MonitorHandle as X11MonitorHandle},
+        platform::dnd::DragResponse,
+        x11::{
+            ime::{ImeContextCreationError, ImeRequest},
+            MonitorHandle as X11MonitorHandle,
+        },
         MonitorHandle as PlatformMonitorHandle, OsError, PlatformSpecificWindowBuilderAttributes,
         VideoMode as PlatformVideoMode,
     },
-    window::{CursorIcon, Fullscreen, Icon, WindowAttributes},
+    window::{
+        ClipboardContents, ClipboardKind, CursorIcon, CustomCursor, Fullscreen, Icon,
+        ResizeDirection, WindowAttributes,
+    },
 };
 
-use super::{ffi, util, EventLoopWindowTarget, ImeSender, WindowId, XConnection, XError};
+use super::{
+    clipboard::{self, Clipboard},
+    dnd::DragSource,
+    ffi, util, EventLoopWindowTarget, ImeSender, WindowId, XConnection, XError,
+};
 
 #[derive(Debug)]
 pub struct SharedState {
@@ -50,6 +62,14 @@ pub struct SharedState {
     pub resize_increments: Option<Size>,
     pub base_size: Option<Size>,
     pub visibility: Visibility,
+    pub resizable: bool,
+    pub has_focus: bool,
+    // The state last reported with `StateChanged`
+    pub window_state: WindowState,
+    // The state last reported with `Occluded`
+    pub occluded: bool,
+    // Set by `request_frame_callback`, until the compositor reports the next frame as drawn
+    pub frame_callback: bool,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -71,6 +91,11 @@ impl SharedState {
         Mutex::new(SharedState {
             last_monitor,
             visibility,
+            resizable: true,
+            has_focus: false,
+            window_state: WindowState::empty(),
+            occluded: false,
+            frame_callback: false,
 
             cursor_pos: None,
             size: None,
@@ -91,6 +116,87 @@ impl SharedState {
     }
 }
 
+/// The counters of the extended frame synchronization protocol, which the compositor answers with
+/// `_NET_WM_FRAME_DRAWN` once it has drawn a frame of the window.
+struct FrameSync {
+    basic_counter: ffi::XSyncCounter,
+    extended_counter: ffi::XSyncCounter,
+    // The value of the extended counter, which is odd while a frame is being drawn
+    value: u64,
+    // The value of the extended counter at the end of the last frame the compositor reported as
+    // drawn, until which it isn't known to follow the counter
+    drawn: Option<u64>,
+}
+
+impl FrameSync {
+    fn new(xconn: &XConnection, window: ffi::Window) -> Option<Self> {
+        let xsync = xconn.xsync.as_ref()?;
+        let frame_drawn_atom = unsafe { xconn.get_atom_unchecked(b"_NET_WM_FRAME_DRAWN\0") };
+        if !util::hint_is_supported(frame_drawn_atom) {
+            return None;
+        }
+
+        let zero = ffi::XSyncValue { hi: 0, lo: 0 };
+        let (basic_counter, extended_counter) = unsafe {
+            (
+                (xsync.XSyncCreateCounter)(xconn.display, zero),
+                (xsync.XSyncCreateCounter)(xconn.display, zero),
+            )
+        };
+        let counter_atom = unsafe { xconn.get_atom_unchecked(b"_NET_WM_SYNC_REQUEST_COUNTER\0") };
+        xconn
+            .change_property(
+                window,
+                counter_atom,
+                ffi::XA_CARDINAL,
+                util::PropMode::Replace,
+                &[basic_counter as c_ulong, extended_counter as c_ulong],
+            )
+            .queue();
+
+        Some(FrameSync {
+            basic_counter,
+            extended_counter,
+            value: 0,
+            drawn: None,
+        })
+    }
+
+    // Whether the compositor will report a frame of the window as drawn, either the one being
+    // drawn or one it hasn't drawn yet.
+    fn frame_pending(&self) -> bool {
+        matches!(self.drawn, Some(drawn) if drawn < self.value)
+    }
+
+    // Moves the extended counter to its next value, odd values starting a frame and even values
+    // ending it.
+    fn increment(&mut self, xconn: &XConnection) {
+        let xsync = match xconn.xsync {
+            Some(ref xsync) => xsync,
+            None => return,
+        };
+        self.value += 1;
+        let value = ffi::XSyncValue {
+            hi: (self.value >> 32) as c_int,
+            lo: self.value as c_uint,
+        };
+        unsafe {
+            (xsync.XSyncSetCounter)(xconn.display, self.extended_counter, value);
+        }
+        // The frame is drawn by the application on its own connection, if it uses OpenGL.
+        let _ = xconn.flush_requests();
+    }
+
+    fn destroy(&self, xconn: &XConnection) {
+        if let Some(ref xsync) = xconn.xsync {
+            unsafe {
+                (xsync.XSyncDestroyCounter)(xconn.display, self.basic_counter);
+                (xsync.XSyncDestroyCounter)(xconn.display, self.extended_counter);
+            }
+        }
+    }
+}
+
 unsafe impl Send for UnownedWindow {}
 unsafe impl Sync for UnownedWindow {}
 
@@ -99,12 +205,17 @@ pub struct UnownedWindow {
     xwindow: ffi::Window,        // never changes
     root: ffi::Window,           // never changes
     screen_id: i32,              // never changes
-    cursor: Mutex<CursorIcon>,
+    cursor: Mutex<util::WindowCursor>,
     cursor_grabbed: Mutex<bool>,
     cursor_visible: Mutex<bool>,
     ime_sender: Mutex<ImeSender>,
     pub shared_state: Mutex<SharedState>,
     pending_redraws: Arc<::std::sync::Mutex<HashSet<WindowId>>>,
+    clipboard: Arc<::std::sync::Mutex<Clipboard>>,
+    drag_response: Arc<::std::sync::Mutex<(ffi::Window, DragResponse)>>,
+    drag_source: Arc<::std::sync::Mutex<Option<DragSource>>>,
+    // Created by the first `request_frame_callback`
+    frame_sync: Mutex<Option<FrameSync>>,
 }
 
 impl UnownedWindow {
@@ -190,6 +301,7 @@ impl UnownedWindow {
             swa.event_mask = ffi::ExposureMask
                 | ffi::StructureNotifyMask
                 | ffi::VisibilityChangeMask
+                | ffi::PropertyChangeMask
                 | ffi::KeyPressMask
                 | ffi::KeyReleaseMask
                 | ffi::KeymapStateMask
@@ -250,6 +362,10 @@ impl UnownedWindow {
             ime_sender: Mutex::new(event_loop.ime_sender.clone()),
             shared_state: SharedState::new(guessed_monitor, window_attrs.visible),
             pending_redraws: event_loop.pending_redraws.clone(),
+            clipboard: event_loop.clipboard.clone(),
+            drag_response: event_loop.drag_response.clone(),
+            drag_source: event_loop.drag_source.clone(),
+            frame_sync: Mutex::new(None),
         };
 
         // Title must be set before mapping. Some tiling window managers (i.e. i3) use the window
@@ -337,6 +453,7 @@ impl UnownedWindow {
                         min_inner_size = Some(dimensions.into());
 
                         let mut shared_state = window.shared_state.get_mut();
+                        shared_state.resizable = false;
                         shared_state.min_inner_size = window_attrs.min_inner_size;
                         shared_state.max_inner_size = window_attrs.max_inner_size;
                         shared_state.resize_increments = pl_attribs.resize_increments;
@@ -401,26 +518,47 @@ impl UnownedWindow {
 
             // Select XInput2 events
             let mask = {
-                let mask = ffi::XI_MotionMask
-                    | ffi::XI_ButtonPressMask
-                    | ffi::XI_ButtonReleaseMask
-                    //| ffi::XI_KeyPressMask
-                    //| ffi::XI_KeyReleaseMask
-                    | ffi::XI_EnterMask
-                    | ffi::XI_LeaveMask
-                    | ffi::XI_FocusInMask
-                    | ffi::XI_FocusOutMask
-                    | ffi::XI_TouchBeginMask
-                    | ffi::XI_TouchUpdateMask
-                    | ffi::XI_TouchEndMask;
+                let mut mask = i64::from(
+                    ffi::XI_MotionMask
+                        | ffi::XI_ButtonPressMask
+                        | ffi::XI_ButtonReleaseMask
+                        //| ffi::XI_KeyPressMask
+                        //| ffi::XI_KeyReleaseMask
+                        | ffi::XI_EnterMask
+                        | ffi::XI_LeaveMask
+                        | ffi::XI_FocusInMask
+                        | ffi::XI_FocusOutMask
+                        | ffi::XI_TouchBeginMask
+                        | ffi::XI_TouchUpdateMask
+                        | ffi::XI_TouchEndMask,
+                );
+                // Older servers reject masks with bits they don't know about.
+                if event_loop.xi2_gestures {
+                    mask |= ffi::XI_GesturePinchBeginMask
+                        | ffi::XI_GesturePinchUpdateMask
+                        | ffi::XI_GesturePinchEndMask
+                        | ffi::XI_GestureSwipeBeginMask
+                        | ffi::XI_GestureSwipeUpdateMask
+                        | ffi::XI_GestureSwipeEndMask;
+                }
                 mask
             };
             xconn
                 .select_xinput_events(window.xwindow, ffi::XIAllMasterDevices, mask)
                 .queue();
 
+            // Track changes to the clipboard owners
+            window
+                .clipboard
+                .lock()
+                .unwrap()
+                .select_owner_change_input(window.xwindow);
+
             {
-                let result = event_loop.ime.borrow_mut().create_context(window.xwindow);
+                let result = event_loop
+                    .ime
+                    .borrow_mut()
+                    .create_context(window.xwindow, false);
                 if let Err(err) = result {
                     let e = match err {
                         ImeContextCreationError::XError(err) => OsError::XError(err),
@@ -741,6 +879,13 @@ impl UnownedWindow {
         }
     }
 
+    /// Records whether the window is occluded, returning whether this differs from the state last
+    /// reported.
+    pub(crate) fn set_occluded(&self, occluded: bool) -> bool {
+        let mut shared_state = self.shared_state.lock();
+        replace(&mut shared_state.occluded, occluded) != occluded
+    }
+
     #[inline]
     pub fn current_monitor(&self) -> X11MonitorHandle {
         self.shared_state.lock().last_monitor.clone()
@@ -806,6 +951,89 @@ impl UnownedWindow {
         self.invalidate_cached_frame_extents();
     }
 
+    fn net_wm_state(&self) -> Vec<ffi::Atom> {
+        let state_atom = unsafe { self.xconn.get_atom_unchecked(b"_NET_WM_STATE\0") };
+        self.xconn
+            .get_property(self.xwindow, state_atom, ffi::XA_ATOM)
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        let horz_atom = unsafe {
+            self.xconn
+                .get_atom_unchecked(b"_NET_WM_STATE_MAXIMIZED_HORZ\0")
+        };
+        let vert_atom = unsafe {
+            self.xconn
+                .get_atom_unchecked(b"_NET_WM_STATE_MAXIMIZED_VERT\0")
+        };
+        let state = self.net_wm_state();
+        state.contains(&horz_atom) && state.contains(&vert_atom)
+    }
+
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        Some(self.is_minimized_inner(&self.net_wm_state()))
+    }
+
+    fn is_minimized_inner(&self, net_wm_state: &[ffi::Atom]) -> bool {
+        // `IconicState` from the ICCCM.
+        const ICONIC_STATE: c_ulong = 3;
+
+        let wm_state_atom = unsafe { self.xconn.get_atom_unchecked(b"WM_STATE\0") };
+        match self
+            .xconn
+            .get_property::<c_ulong>(self.xwindow, wm_state_atom, wm_state_atom)
+        {
+            Ok(ref state) if !state.is_empty() => state[0] == ICONIC_STATE,
+            // Window managers that don't follow the ICCCM may still follow the EWMH.
+            _ => {
+                let hidden_atom =
+                    unsafe { self.xconn.get_atom_unchecked(b"_NET_WM_STATE_HIDDEN\0") };
+                net_wm_state.contains(&hidden_atom)
+            }
+        }
+    }
+
+    /// Reads the state of the window after `WM_STATE` or `_NET_WM_STATE` changed, returning it
+    /// if it differs from the one last reported.
+    pub(crate) fn update_window_state(&self) -> Option<WindowState> {
+        let horz_atom = unsafe {
+            self.xconn
+                .get_atom_unchecked(b"_NET_WM_STATE_MAXIMIZED_HORZ\0")
+        };
+        let vert_atom = unsafe {
+            self.xconn
+                .get_atom_unchecked(b"_NET_WM_STATE_MAXIMIZED_VERT\0")
+        };
+        let fullscreen_atom =
+            unsafe { self.xconn.get_atom_unchecked(b"_NET_WM_STATE_FULLSCREEN\0") };
+        let net_wm_state = self.net_wm_state();
+
+        let mut state = WindowState::empty();
+        state.set(
+            WindowState::MAXIMIZED,
+            net_wm_state.contains(&horz_atom) && net_wm_state.contains(&vert_atom),
+        );
+        state.set(
+            WindowState::MINIMIZED,
+            self.is_minimized_inner(&net_wm_state),
+        );
+        state.set(
+            WindowState::FULLSCREEN,
+            net_wm_state.contains(&fullscreen_atom),
+        );
+
+        let mut shared_state = self.shared_state.lock();
+        if shared_state.window_state != state {
+            shared_state.window_state = state;
+            Some(state)
+        } else {
+            None
+        }
+    }
+
     fn set_title_inner(&self, title: &str) -> util::Flusher<'_> {
         let wm_name_atom = unsafe { self.xconn.get_atom_unchecked(b"_NET_WM_NAME\0") };
         let utf8_atom = unsafe { self.xconn.get_atom_unchecked(b"UTF8_STRING\0") };
@@ -833,6 +1061,20 @@ impl UnownedWindow {
             .expect("Failed to set window title");
     }
 
+    pub fn title(&self) -> String {
+        let wm_name_atom = unsafe { self.xconn.get_atom_unchecked(b"_NET_WM_NAME\0") };
+        let utf8_atom = unsafe { self.xconn.get_atom_unchecked(b"UTF8_STRING\0") };
+        let title = self
+            .xconn
+            .get_property::<c_uchar>(self.xwindow, wm_name_atom, utf8_atom)
+            .or_else(|_| {
+                self.xconn
+                    .get_property(self.xwindow, ffi::XA_WM_NAME, ffi::XA_STRING)
+            })
+            .unwrap_or_default();
+        String::from_utf8_lossy(&title).into_owned()
+    }
+
     fn set_decorations_inner(&self, decorations: bool) -> util::Flusher<'_> {
         let mut hints = self.xconn.get_motif_hints(self.xwindow);
 
@@ -849,6 +1091,11 @@ impl UnownedWindow {
         self.invalidate_cached_frame_extents();
     }
 
+    #[inline]
+    pub fn is_decorated(&self) -> bool {
+        self.xconn.get_motif_hints(self.xwindow).decorations()
+    }
+
     fn set_maximizable_inner(&self, maximizable: bool) -> util::Flusher<'_> {
         let mut hints = self.xconn.get_motif_hints(self.xwindow);
 
@@ -903,6 +1150,16 @@ impl UnownedWindow {
         .expect("Failed to set icons");
     }
 
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        Some(self.shared_state.lock().visibility == Visibility::Yes)
+    }
+
+    #[inline]
+    pub fn has_focus(&self) -> bool {
+        self.shared_state.lock().has_focus
+    }
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         let mut shared_state = self.shared_state.lock();
@@ -1132,6 +1389,7 @@ impl UnownedWindow {
             warn!("To avoid a WM bug, disabling resizing has no effect on Xfwm4");
             return;
         }
+        self.shared_state.lock().resizable = resizable;
 
         let (min_size, max_size) = if resizable {
             let shared_state_lock = self.shared_state.lock();
@@ -1160,6 +1418,11 @@ impl UnownedWindow {
         .expect("Failed to call `XSetWMNormalHints`");
     }
 
+    #[inline]
+    pub fn is_resizable(&self) -> bool {
+        self.shared_state.lock().resizable
+    }
+
     #[inline]
     pub fn xlib_display(&self) -> *mut c_void {
         self.xconn.display as _
@@ -1187,9 +1450,18 @@ impl UnownedWindow {
 
     #[inline]
     pub fn set_cursor_icon(&self, cursor: CursorIcon) {
-        let old_cursor = replace(&mut *self.cursor.lock(), cursor);
+        self.set_cursor(util::WindowCursor::Icon(cursor));
+    }
+
+    #[inline]
+    pub fn set_custom_cursor(&self, cursor: CustomCursor) {
+        self.set_cursor(util::WindowCursor::Custom(cursor));
+    }
+
+    fn set_cursor(&self, cursor: util::WindowCursor) {
+        let old_cursor = replace(&mut *self.cursor.lock(), cursor.clone());
         if cursor != old_cursor && *self.cursor_visible.lock() {
-            self.xconn.set_cursor_icon(self.xwindow, Some(cursor));
+            self.xconn.set_cursor(self.xwindow, Some(&cursor));
         }
     }
 
@@ -1262,13 +1534,172 @@ impl UnownedWindow {
             return;
         }
         let cursor = if visible {
-            Some(*self.cursor.lock())
+            Some(self.cursor.lock().clone())
         } else {
             None
         };
         *visible_lock = visible;
         drop(visible_lock);
-        self.xconn.set_cursor_icon(self.xwindow, cursor);
+        self.xconn.set_cursor(self.xwindow, cursor.as_ref());
+    }
+
+    #[inline]
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        self.move_resize(util::MoveResizeOperation::Move)
+    }
+
+    #[inline]
+    pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
+        self.move_resize(direction.into())
+    }
+
+    // Asks the window manager to move or resize the window with the pointer.
+    fn move_resize(&self, operation: util::MoveResizeOperation) -> Result<(), ExternalError> {
+        let moveresize_atom = unsafe { self.xconn.get_atom_unchecked(b"_NET_WM_MOVERESIZE\0") };
+        if !util::hint_is_supported(moveresize_atom) {
+            return Err(ExternalError::NotSupported(NotSupportedError::new()));
+        }
+        let pointer = self
+            .xconn
+            .query_pointer(self.root, util::VIRTUAL_CORE_POINTER)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err))))?;
+        // The window manager waits for this button to be released to end the operation.
+        let button = pointer.pressed_button().unwrap_or(ffi::Button1);
+        unsafe {
+            // The window manager can't grab the pointer while we hold the implicit grab of the
+            // button press. `set_cursor_grab(false)` only releases grabs it made itself.
+            (self.xconn.xlib.XUngrabPointer)(self.xconn.display, ffi::CurrentTime);
+        }
+        self.xconn
+            .send_client_msg(
+                self.xwindow,
+                self.root,
+                moveresize_atom,
+                Some(ffi::SubstructureRedirectMask | ffi::SubstructureNotifyMask),
+                [
+                    pointer.root_x as c_long,
+                    pointer.root_y as c_long,
+                    operation as c_long,
+                    button as c_long,
+                    1, // The request comes from a regular application
+                ],
+            )
+            .flush()
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err))))
+    }
+
+    #[inline]
+    pub fn set_clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        self.clipboard
+            .lock()
+            .unwrap()
+            .set_contents(self.xwindow, kind, contents)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err))))
+    }
+
+    #[inline]
+    pub fn clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        let (atoms, selection, target) = {
+            let clipboard = self.clipboard.lock().unwrap();
+            // Asking the X server would make us wait on ourselves, so answer directly.
+            if let Some(contents) = clipboard.owned_contents(kind) {
+                return Ok(contents.get(mime_type).map(|data| data.to_vec()));
+            }
+            match clipboard.target_for_mime_type(mime_type) {
+                Some(target) => (clipboard.atoms, clipboard.selection_atom(kind), target),
+                None => return Ok(None),
+            }
+        };
+        clipboard::convert_selection(&self.xconn, &atoms, self.xwindow, selection, target)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err))))
+    }
+
+    #[inline]
+    pub fn set_drag_response(&self, response: DragResponse) {
+        let mut drag_response = self.drag_response.lock().unwrap();
+        // Only the window the drag is over may answer it.
+        if drag_response.0 == self.xwindow {
+            drag_response.1 = response;
+        }
+    }
+
+    pub fn start_drag(
+        &self,
+        contents: ClipboardContents,
+        allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        let mut drag_source = self.drag_source.lock().unwrap();
+        if drag_source.is_some() {
+            return Err(ExternalError::Os(os_error!(OsError::XMisc(
+                "a drag is already in progress"
+            ))));
+        }
+
+        let types = {
+            let mut clipboard = self.clipboard.lock().unwrap();
+            let types = clipboard.drag_types(&contents);
+            clipboard
+                .set_drag_contents(self.xwindow, contents)
+                .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err))))?;
+            types
+        };
+        // Targets read the types from this property when there are more than three of them.
+        let type_list_atom = unsafe { self.xconn.get_atom_unchecked(b"XdndTypeList\0") };
+        self.xconn
+            .change_property(
+                self.xwindow,
+                type_list_atom,
+                ffi::XA_ATOM,
+                util::PropMode::Replace,
+                &types,
+            )
+            .queue();
+
+        // The pointer is grabbed until the drag is dropped, so that we keep receiving its motion
+        // while it's over other windows.
+        let mut device_id = 0;
+        unsafe { (self.xconn.xinput2.XIGetClientPointer)(self.xconn.display, 0, &mut device_id) };
+        let mut mask = (ffi::XI_MotionMask | ffi::XI_ButtonReleaseMask) as i64;
+        let mut event_mask = ffi::XIEventMask {
+            deviceid: device_id,
+            mask: &mut mask as *mut _ as *mut c_uchar,
+            mask_len: mem::size_of_val(&mask) as c_int,
+        };
+        let status = unsafe {
+            (self.xconn.xinput2.XIGrabDevice)(
+                self.xconn.display,
+                device_id,
+                self.xwindow,
+                ffi::CurrentTime,
+                0,
+                ffi::GrabModeAsync,
+                ffi::GrabModeAsync,
+                ffi::False,
+                &mut event_mask,
+            )
+        };
+        if status != ffi::GrabSuccess {
+            self.clipboard.lock().unwrap().clear_drag_contents();
+            return Err(ExternalError::Os(os_error!(OsError::XMisc(
+                "failed to grab the pointer"
+            ))));
+        }
+
+        let actions = if allowed_actions.is_empty() {
+            vec![DragAction::Copy]
+        } else {
+            allowed_actions.to_vec()
+        };
+        *drag_source = Some(DragSource::new(self.xwindow, device_id, types, actions));
+        Ok(())
     }
 
     #[inline]
@@ -1295,7 +1726,7 @@ impl UnownedWindow {
         let _ = self
             .ime_sender
             .lock()
-            .send((self.xwindow, x as i16, y as i16));
+            .send(ImeRequest::Position(self.xwindow, x as i16, y as i16));
     }
 
     #[inline]
@@ -1304,6 +1735,14 @@ impl UnownedWindow {
         self.set_ime_position_physical(x, y);
     }
 
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        let _ = self
+            .ime_sender
+            .lock()
+            .send(ImeRequest::Allow(self.xwindow, allowed));
+    }
+
     #[inline]
     pub fn id(&self) -> WindowId {
         WindowId(self.xwindow)
@@ -1317,6 +1756,50 @@ impl UnownedWindow {
             .insert(WindowId(self.xwindow));
     }
 
+    pub fn request_frame_callback(&self) {
+        let mut frame_sync = self.frame_sync.lock();
+        if frame_sync.is_none() {
+            // The counters are only created once needed, as the compositor then keeps track of
+            // every frame of the window.
+            *frame_sync = FrameSync::new(&self.xconn, self.xwindow);
+        }
+        match *frame_sync {
+            Some(ref frame_sync) if frame_sync.frame_pending() => {
+                self.shared_state.lock().frame_callback = true;
+            }
+            // Nothing would be reported as drawn, so a frame has to be drawn first.
+            _ => self.request_redraw(),
+        }
+    }
+
+    /// Marks the start of a frame of the window, which is drawn while handling `RedrawRequested`.
+    pub(super) fn start_frame(&self) {
+        if let Some(ref mut frame_sync) = *self.frame_sync.lock() {
+            frame_sync.increment(&self.xconn);
+        }
+    }
+
+    /// Marks the end of the frame started with `start_frame`.
+    pub(super) fn finish_frame(&self) {
+        if let Some(ref mut frame_sync) = *self.frame_sync.lock() {
+            frame_sync.increment(&self.xconn);
+        }
+    }
+
+    /// Called when the compositor reports the frame ending with the counter at `value` as drawn,
+    /// redrawing the window if the application asked for a frame callback. Returns whether it did.
+    pub(super) fn frame_drawn(&self, value: u64) -> bool {
+        let mut frame_sync = self.frame_sync.lock();
+        if let Some(ref mut frame_sync) = *frame_sync {
+            frame_sync.drawn = Some(frame_sync.drawn.map_or(value, |drawn| drawn.max(value)));
+        }
+        let wanted = replace(&mut self.shared_state.lock().frame_callback, false);
+        if wanted {
+            self.request_redraw();
+        }
+        wanted
+    }
+
     #[inline]
     pub fn raw_window_handle(&self) -> XlibHandle {
         XlibHandle {
@@ -1326,3 +1809,11 @@ impl UnownedWindow {
         }
     }
 }
+
+impl Drop for UnownedWindow {
+    fn drop(&mut self) {
+        if let Some(ref frame_sync) = *self.frame_sync.get_mut() {
+            frame_sync.destroy(&self.xconn);
+        }
+    }
+}
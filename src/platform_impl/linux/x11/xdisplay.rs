@@ -18,10 +18,16 @@ pub struct XConnection {
     pub xinput2: ffi::XInput2,
     pub xlib_xcb: ffi::Xlib_xcb,
     pub xrender: ffi::Xrender,
+    /// Exposes XFixes functions, if the library is available
+    pub xfixes: Option<ffi::Xfixes>,
+    /// Exposes XSync functions, if the library and the extension are available
+    pub xsync: Option<ffi::Xsync>,
     pub display: *mut ffi::Display,
     pub x11_fd: c_int,
     pub latest_error: Mutex<Option<XError>>,
     pub cursor_cache: Mutex<HashMap<Option<CursorIcon>, ffi::Cursor>>,
+    /// `Xft/DPI` as last published over XSETTINGS, which takes precedence over `Xft.dpi`
+    pub xsettings_dpi: Mutex<Option<f64>>,
 }
 
 unsafe impl Send for XConnection {}
@@ -40,6 +46,7 @@ impl XConnection {
         let xinput2 = ffi::XInput2::open()?;
         let xlib_xcb = ffi::Xlib_xcb::open()?;
         let xrender = ffi::Xrender::open()?;
+        let xfixes = ffi::Xfixes::open().ok();
 
         unsafe { (xlib.XInitThreads)() };
         unsafe { (xlib.XSetErrorHandler)(error_handler) };
@@ -53,6 +60,13 @@ impl XConnection {
             display
         };
 
+        let xsync = ffi::Xsync::open().ok().filter(|xsync| unsafe {
+            let (mut event_base, mut error_base) = (0, 0);
+            let (mut major, mut minor) = (0, 0);
+            (xsync.XSyncQueryExtension)(display, &mut event_base, &mut error_base) != 0
+                && (xsync.XSyncInitialize)(display, &mut major, &mut minor) != 0
+        });
+
         // Get X11 socket file descriptor
         let fd = unsafe { (xlib.XConnectionNumber)(display) };
 
@@ -64,10 +78,13 @@ impl XConnection {
             xinput2,
             xlib_xcb,
             xrender,
+            xfixes,
+            xsync,
             display,
             x11_fd: fd,
             latest_error: Mutex::new(None),
             cursor_cache: Default::default(),
+            xsettings_dpi: Mutex::new(None),
         })
     }
 
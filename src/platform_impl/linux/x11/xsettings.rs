@@ -0,0 +1,235 @@
+use std::{ffi::CString, os::raw::*, sync::Arc, time::Duration};
+
+use super::{ffi, XConnection};
+use crate::window::Theme;
+
+/// Settings of the desktop environment, published by its settings daemon over
+/// [XSETTINGS](https://specifications.freedesktop.org/xsettings-spec/xsettings-latest.html).
+///
+/// Every field is `None` when no settings daemon is running, or when it doesn't publish that
+/// setting.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XSettings {
+    /// `Net/ThemeName`, the name of the GTK theme.
+    pub theme_name: Option<String>,
+    /// `Gtk/CursorThemeName`, the name of the cursor theme.
+    pub cursor_theme_name: Option<String>,
+    /// `Gtk/CursorThemeSize`, the size of the cursors in pixels.
+    pub cursor_theme_size: Option<u32>,
+    /// `Net/DoubleClickTime`, the longest time between the clicks of a double click.
+    pub double_click_time: Option<Duration>,
+    /// `Net/DndDragThreshold`, the distance in pixels the pointer has to travel with a button
+    /// pressed before a drag starts.
+    pub dnd_drag_threshold: Option<u32>,
+    /// `Xft/DPI`, the resolution fonts are rendered at.
+    pub dpi: Option<f64>,
+}
+
+impl XSettings {
+    /// The theme of the windows, going by the convention of naming the dark variant of a GTK
+    /// theme `<theme>-dark`.
+    pub(crate) fn theme(&self) -> Theme {
+        match self.theme_name {
+            Some(ref name) if name.to_lowercase().ends_with("-dark") => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+
+    fn set_integer(&mut self, name: &[u8], value: i32) {
+        let positive = if value >= 0 { Some(value as u32) } else { None };
+        match name {
+            b"Gtk/CursorThemeSize" => self.cursor_theme_size = positive.filter(|&size| size > 0),
+            b"Net/DoubleClickTime" => {
+                self.double_click_time = positive.map(|ms| Duration::from_millis(ms as u64))
+            }
+            b"Net/DndDragThreshold" => self.dnd_drag_threshold = positive,
+            // In 1024ths of a dot per inch, with -1 standing for the default.
+            b"Xft/DPI" => self.dpi = positive.map(|dpi| dpi as f64 / 1024.0),
+            _ => (),
+        }
+    }
+
+    fn set_string(&mut self, name: &[u8], value: &[u8]) {
+        let value = Some(String::from_utf8_lossy(value).into_owned()).filter(|s| !s.is_empty());
+        match name {
+            b"Net/ThemeName" => self.theme_name = value,
+            b"Gtk/CursorThemeName" => self.cursor_theme_name = value,
+            _ => (),
+        }
+    }
+
+    // The layout of the property is described in the "Setting property" section of the spec.
+    fn parse(data: &[u8]) -> Option<XSettings> {
+        let mut reader = Reader {
+            big_endian: match *data.first()? {
+                0 => false,
+                1 => true,
+                _ => return None,
+            },
+            data,
+            // The byte order is followed by 3 bytes of padding.
+            offset: 4,
+        };
+        let _serial = reader.card32()?;
+        let count = reader.card32()?;
+
+        let mut settings = XSettings::default();
+        for _ in 0..count {
+            let kind = reader.bytes(2)?[0];
+            let name_len = reader.card16()? as usize;
+            let name = reader.padded_bytes(name_len)?;
+            let _last_change_serial = reader.card32()?;
+            match kind {
+                0 => settings.set_integer(name, reader.card32()? as i32),
+                1 => {
+                    let len = reader.card32()? as usize;
+                    settings.set_string(name, reader.padded_bytes(len)?);
+                }
+                // Colors, which are four 16-bit channels.
+                2 => {
+                    reader.bytes(8)?;
+                }
+                _ => return None,
+            }
+        }
+        Some(settings)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    // Strings are padded to a multiple of 4 bytes.
+    fn padded_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes(len)?;
+        self.bytes((4 - len % 4) % 4)?;
+        Some(bytes)
+    }
+
+    fn card16(&mut self) -> Option<u16> {
+        let b = self.bytes(2)?;
+        let bytes = [b[0], b[1]];
+        Some(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn card32(&mut self) -> Option<u32> {
+        let b = self.bytes(4)?;
+        let bytes = [b[0], b[1], b[2], b[3]];
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+}
+
+/// Follows the XSETTINGS manager of the screen, which is the owner of the `_XSETTINGS_S<n>`
+/// selection, and reads the settings it publishes on its window.
+pub struct XSettingsClient {
+    xconn: Arc<XConnection>,
+    selection: ffi::Atom,
+    settings_atom: ffi::Atom,
+    manager_atom: ffi::Atom,
+    // The window of the current manager, or 0 if there is none
+    owner: ffi::Window,
+    settings: XSettings,
+}
+
+impl XSettingsClient {
+    pub fn new(xconn: Arc<XConnection>, root: ffi::Window) -> Self {
+        let screen = unsafe { (xconn.xlib.XDefaultScreen)(xconn.display) };
+        let selection = xconn.get_atom(CString::new(format!("_XSETTINGS_S{}", screen)).unwrap());
+        let settings_atom = unsafe { xconn.get_atom_unchecked(b"_XSETTINGS_SETTINGS\0") };
+        let manager_atom = unsafe { xconn.get_atom_unchecked(b"MANAGER\0") };
+
+        // New managers announce themselves with a `MANAGER` message sent to the root window.
+        unsafe { (xconn.xlib.XSelectInput)(xconn.display, root, ffi::StructureNotifyMask) };
+
+        let mut client = XSettingsClient {
+            xconn,
+            selection,
+            settings_atom,
+            manager_atom,
+            owner: 0,
+            settings: XSettings::default(),
+        };
+        client.refresh();
+        client
+    }
+
+    pub fn settings(&self) -> &XSettings {
+        &self.settings
+    }
+
+    /// Makes the connection use the DPI and the cursor theme of the settings.
+    pub fn apply(&self) {
+        *self.xconn.xsettings_dpi.lock() = self.settings.dpi;
+        self.xconn.set_cursor_theme(
+            self.settings.cursor_theme_name.as_deref(),
+            self.settings.cursor_theme_size,
+        );
+    }
+
+    /// Whether `window` is the one of the current manager.
+    pub fn is_manager_window(&self, window: ffi::Window) -> bool {
+        self.owner != 0 && window == self.owner
+    }
+
+    /// Whether the message announces a new manager for our screen.
+    pub fn is_manager_message(&self, client_msg: &ffi::XClientMessageEvent) -> bool {
+        client_msg.message_type == self.manager_atom
+            && client_msg.data.get_long(1) as ffi::Atom == self.selection
+    }
+
+    /// Whether the event is about the settings of the current manager.
+    pub fn is_settings_change(&self, xev: &ffi::XPropertyEvent) -> bool {
+        self.is_manager_window(xev.window) && xev.atom == self.settings_atom
+    }
+
+    /// Looks up the current manager and reads its settings again, returning the previous ones.
+    pub fn refresh(&mut self) -> XSettings {
+        let xconn = &self.xconn;
+        // The server is grabbed so the manager can't go away before we listen to its window.
+        self.owner = unsafe {
+            (xconn.xlib.XGrabServer)(xconn.display);
+            let owner = (xconn.xlib.XGetSelectionOwner)(xconn.display, self.selection);
+            if owner != 0 {
+                (xconn.xlib.XSelectInput)(
+                    xconn.display,
+                    owner,
+                    ffi::StructureNotifyMask | ffi::PropertyChangeMask,
+                );
+            }
+            (xconn.xlib.XUngrabServer)(xconn.display);
+            owner
+        };
+        if xconn.flush_requests().is_err() {
+            self.owner = 0;
+        }
+
+        let settings = if self.owner != 0 {
+            xconn
+                .get_property::<c_uchar>(self.owner, self.settings_atom, self.settings_atom)
+                .ok()
+                .and_then(|data| XSettings::parse(&data))
+                .unwrap_or_default()
+        } else {
+            XSettings::default()
+        };
+        std::mem::replace(&mut self.settings, settings)
+    }
+}
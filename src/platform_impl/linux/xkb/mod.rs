@@ -0,0 +1,259 @@
+//! Keyboard handling through xkbcommon, which is loaded at runtime.
+//!
+//! This is the same library smithay-client-toolkit uses for Wayland keyboards, so keys are
+//! translated and composed the same way on both backends.
+
+use std::{
+    env,
+    ffi::CString,
+    os::{raw::c_char, unix::ffi::OsStringExt},
+    ptr,
+};
+
+use self::ffi::{xkb_compose_feed_result, xkb_compose_status, XkbCommon, XKBCOMMON, XKBCOMMON_X11};
+
+mod ffi;
+
+/// The keymap and the state of a keyboard, along with the state of its compose sequence.
+pub struct KbState {
+    xkbh: &'static XkbCommon,
+    context: *mut ffi::xkb_context,
+    keymap: *mut ffi::xkb_keymap,
+    state: *mut ffi::xkb_state,
+    // Both are null if no compose table exists for the current locale.
+    compose_table: *mut ffi::xkb_compose_table,
+    compose_state: *mut ffi::xkb_compose_state,
+}
+
+/// The result of feeding a key press through the keymap and the compose state.
+pub struct KeyPress {
+    pub keysym: u32,
+    /// The text produced by the key press, if any. This is `None` while a compose sequence is in
+    /// progress, and holds the composed text once it's finished.
+    pub text: Option<String>,
+}
+
+impl KbState {
+    /// Creates the state of the core keyboard of an X server, which is kept up to date with
+    /// [`KbState::update_mask`] and [`KbState::reload_x11`].
+    ///
+    /// Returns the state along with the first event code of the XKB extension and the device ID
+    /// of the core keyboard, or `None` if xkbcommon-x11 isn't available.
+    pub fn new_x11(connection: *mut ffi::xcb_connection_t) -> Option<(KbState, u8, i32)> {
+        let xkbh = XKBCOMMON.as_ref()?;
+        let x11h = XKBCOMMON_X11.as_ref()?;
+        let mut base_event = 0;
+        unsafe {
+            let success = (x11h.xkb_x11_setup_xkb_extension)(
+                connection,
+                ffi::XKB_X11_MIN_MAJOR_XKB_VERSION,
+                ffi::XKB_X11_MIN_MINOR_XKB_VERSION,
+                ffi::xkb_x11_setup_xkb_extension_flags::XKB_X11_SETUP_XKB_EXTENSION_NO_FLAGS,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut base_event,
+                ptr::null_mut(),
+            );
+            if success != 1 {
+                return None;
+            }
+            let device_id = (x11h.xkb_x11_get_core_keyboard_device_id)(connection);
+            if device_id == -1 {
+                return None;
+            }
+
+            let context = (xkbh.xkb_context_new)(ffi::xkb_context_flags::XKB_CONTEXT_NO_FLAGS);
+            if context.is_null() {
+                return None;
+            }
+            let mut kb_state = KbState {
+                xkbh,
+                context,
+                keymap: ptr::null_mut(),
+                state: ptr::null_mut(),
+                compose_table: ptr::null_mut(),
+                compose_state: ptr::null_mut(),
+            };
+            kb_state.init_compose();
+            if !kb_state.reload_x11(connection, device_id) {
+                return None;
+            }
+            Some((kb_state, base_event, device_id))
+        }
+    }
+
+    /// Fetches the keymap and the state of a keyboard from the X server again, which is needed
+    /// after it was changed or replaced. Returns whether it succeeded.
+    pub fn reload_x11(&mut self, connection: *mut ffi::xcb_connection_t, device_id: i32) -> bool {
+        let x11h = match XKBCOMMON_X11.as_ref() {
+            Some(x11h) => x11h,
+            None => return false,
+        };
+        unsafe {
+            let keymap = (x11h.xkb_x11_keymap_new_from_device)(
+                self.context,
+                connection,
+                device_id,
+                ffi::xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            if keymap.is_null() {
+                return false;
+            }
+            let state = (x11h.xkb_x11_state_new_from_device)(keymap, connection, device_id);
+            if state.is_null() {
+                (self.xkbh.xkb_keymap_unref)(keymap);
+                return false;
+            }
+            self.free_keymap();
+            self.keymap = keymap;
+            self.state = state;
+        }
+        true
+    }
+
+    /// Updates the modifiers and the layout of the state, as reported by the display server.
+    pub fn update_mask(
+        &mut self,
+        depressed_mods: u32,
+        latched_mods: u32,
+        locked_mods: u32,
+        depressed_layout: u32,
+        latched_layout: u32,
+        locked_layout: u32,
+    ) {
+        unsafe {
+            (self.xkbh.xkb_state_update_mask)(
+                self.state,
+                depressed_mods,
+                latched_mods,
+                locked_mods,
+                depressed_layout,
+                latched_layout,
+                locked_layout,
+            );
+        }
+    }
+
+    /// Returns the keysym of a key in the current state, without going through the compose state.
+    pub fn keysym(&self, keycode: u32) -> u32 {
+        unsafe { (self.xkbh.xkb_state_key_get_one_sym)(self.state, keycode) }
+    }
+
+    /// Translates a key press, feeding it to the compose sequence in progress.
+    pub fn key_press(&mut self, keycode: u32) -> KeyPress {
+        let keysym = self.keysym(keycode);
+        if self.compose_state.is_null() {
+            return KeyPress {
+                keysym,
+                text: self.key_utf8(keycode),
+            };
+        }
+        let text = unsafe {
+            match (self.xkbh.xkb_compose_state_feed)(self.compose_state, keysym) {
+                // Modifier keys don't take part in compose sequences.
+                xkb_compose_feed_result::XKB_COMPOSE_FEED_IGNORED => None,
+                xkb_compose_feed_result::XKB_COMPOSE_FEED_ACCEPTED => {
+                    match (self.xkbh.xkb_compose_state_get_status)(self.compose_state) {
+                        xkb_compose_status::XKB_COMPOSE_NOTHING => self.key_utf8(keycode),
+                        xkb_compose_status::XKB_COMPOSE_COMPOSED => self.compose_utf8(),
+                        _ => None,
+                    }
+                }
+            }
+        };
+        KeyPress { keysym, text }
+    }
+
+    /// Cancels the compose sequence in progress, for instance when the keyboard focus is lost.
+    pub fn reset_compose(&mut self) {
+        if !self.compose_state.is_null() {
+            unsafe { (self.xkbh.xkb_compose_state_reset)(self.compose_state) };
+        }
+    }
+
+    fn key_utf8(&self, keycode: u32) -> Option<String> {
+        unsafe {
+            read_utf8(|buffer, size| {
+                (self.xkbh.xkb_state_key_get_utf8)(self.state, keycode, buffer, size)
+            })
+        }
+    }
+
+    fn compose_utf8(&self) -> Option<String> {
+        unsafe {
+            read_utf8(|buffer, size| {
+                (self.xkbh.xkb_compose_state_get_utf8)(self.compose_state, buffer, size)
+            })
+        }
+    }
+
+    unsafe fn init_compose(&mut self) {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(env::var_os)
+            .find(|locale| !locale.is_empty())
+            .unwrap_or_else(|| "C".into());
+        let locale = match CString::new(locale.into_vec()) {
+            Ok(locale) => locale,
+            Err(_) => return,
+        };
+
+        let table = (self.xkbh.xkb_compose_table_new_from_locale)(
+            self.context,
+            locale.as_ptr(),
+            ffi::xkb_compose_compile_flags::XKB_COMPOSE_COMPILE_NO_FLAGS,
+        );
+        if table.is_null() {
+            // Compose sequences are simply unsupported then.
+            return;
+        }
+        let state = (self.xkbh.xkb_compose_state_new)(
+            table,
+            ffi::xkb_compose_state_flags::XKB_COMPOSE_STATE_NO_FLAGS,
+        );
+        if state.is_null() {
+            (self.xkbh.xkb_compose_table_unref)(table);
+            return;
+        }
+        self.compose_table = table;
+        self.compose_state = state;
+    }
+
+    unsafe fn free_keymap(&mut self) {
+        if !self.state.is_null() {
+            (self.xkbh.xkb_state_unref)(self.state);
+        }
+        if !self.keymap.is_null() {
+            (self.xkbh.xkb_keymap_unref)(self.keymap);
+        }
+    }
+}
+
+impl Drop for KbState {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.compose_state.is_null() {
+                (self.xkbh.xkb_compose_state_unref)(self.compose_state);
+                (self.xkbh.xkb_compose_table_unref)(self.compose_table);
+            }
+            self.free_keymap();
+            (self.xkbh.xkb_context_unref)(self.context);
+        }
+    }
+}
+
+// Reads a string from one of the xkbcommon functions following the `snprintf` convention.
+unsafe fn read_utf8<F>(mut get: F) -> Option<String>
+where
+    F: FnMut(*mut c_char, usize) -> i32,
+{
+    let size = get(ptr::null_mut(), 0);
+    if size <= 0 {
+        return None;
+    }
+    // Including the terminating NUL.
+    let mut buffer = vec![0u8; size as usize + 1];
+    get(buffer.as_mut_ptr() as *mut c_char, buffer.len());
+    buffer.pop();
+    String::from_utf8(buffer).ok()
+}
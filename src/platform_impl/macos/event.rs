@@ -7,7 +7,10 @@ use cocoa::{
 
 use crate::{
     dpi::LogicalSize,
-    event::{ElementState, Event, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent},
+    event::{
+        ElementState, Event, Key, KeyCode, KeyboardInput, ModifiersState, VirtualKeyCode,
+        WindowEvent,
+    },
     platform_impl::platform::{
         util::{IdRef, Never},
         DEVICE_ID,
@@ -293,6 +296,9 @@ pub unsafe fn modifier_event(
             input: KeyboardInput {
                 state,
                 scancode: scancode as _,
+                physical_key: KeyCode::Unidentified,
+                logical_key: Key::Unidentified,
+                text: None,
                 virtual_keycode,
                 modifiers: event_mods(ns_event),
             },
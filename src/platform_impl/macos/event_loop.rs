@@ -10,8 +10,11 @@ use cocoa::{
 };
 
 use crate::{
+    error::{ExternalError, NotSupportedError},
     event::Event,
-    event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootWindowTarget},
+    event_loop::{
+        ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootWindowTarget, Timer, TimerId,
+    },
     platform_impl::platform::{
         app::APP_CLASS,
         app_delegate::APP_DELEGATE_CLASS,
@@ -34,6 +37,16 @@ impl<T> Default for EventLoopWindowTarget<T> {
     }
 }
 
+impl<T> EventLoopWindowTarget<T> {
+    #[inline]
+    pub fn set_timer(&self, _timer: Timer) -> Result<TimerId, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn cancel_timer(&self, _id: TimerId) {}
+}
+
 pub struct EventLoop<T: 'static> {
     window_target: Rc<RootWindowTarget<T>>,
     _delegate: IdRef,
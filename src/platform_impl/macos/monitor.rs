@@ -215,6 +215,36 @@ impl MonitorHandle {
         )
     }
 
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        let (position, size) = (self.position(), self.size());
+        let screen = match self.ns_screen() {
+            Some(screen) => screen,
+            None => return (position, size),
+        };
+        // `visibleFrame` leaves out the menu bar and the Dock. Both frames are in points, with
+        // the origin at the bottom-left, so only the insets are carried over to the display.
+        let (frame, visible, scale_factor) = unsafe {
+            (
+                NSScreen::frame(screen),
+                NSScreen::visibleFrame(screen),
+                NSScreen::backingScaleFactor(screen) as f64,
+            )
+        };
+        let left = (visible.origin.x - frame.origin.x) * scale_factor;
+        let bottom = (visible.origin.y - frame.origin.y) * scale_factor;
+        let top = ((frame.origin.y + frame.size.height) - (visible.origin.y + visible.size.height))
+            * scale_factor;
+        let right = ((frame.origin.x + frame.size.width) - (visible.origin.x + visible.size.width))
+            * scale_factor;
+        (
+            PhysicalPosition::new(position.x + left as i32, position.y + top as i32),
+            PhysicalSize::new(
+                size.width.saturating_sub((left + right) as u32),
+                size.height.saturating_sub((top + bottom) as u32),
+            ),
+        )
+    }
+
     pub fn scale_factor(&self) -> f64 {
         let screen = match self.ns_screen() {
             Some(screen) => screen,
@@ -19,7 +19,7 @@ use objc::{
 use crate::{
     dpi::LogicalPosition,
     event::{
-        DeviceEvent, ElementState, Event, KeyboardInput, ModifiersState, MouseButton,
+        DeviceEvent, ElementState, Event, Key, KeyCode, KeyboardInput, ModifiersState, MouseButton,
         MouseScrollDelta, TouchPhase, VirtualKeyCode, WindowEvent,
     },
     platform_impl::platform::{
@@ -661,6 +661,9 @@ extern "C" fn key_down(this: &Object, _sel: Sel, event: id) {
                 input: KeyboardInput {
                     state: ElementState::Pressed,
                     scancode,
+                    physical_key: KeyCode::Unidentified,
+                    logical_key: Key::Unidentified,
+                    text: None,
                     virtual_keycode,
                     modifiers: event_mods(event),
                 },
@@ -716,6 +719,9 @@ extern "C" fn key_up(this: &Object, _sel: Sel, event: id) {
                 input: KeyboardInput {
                     state: ElementState::Released,
                     scancode,
+                    physical_key: KeyCode::Unidentified,
+                    logical_key: Key::Unidentified,
+                    text: None,
                     virtual_keycode,
                     modifiers: event_mods(event),
                 },
@@ -835,6 +841,9 @@ extern "C" fn cancel_operation(this: &Object, _sel: Sel, _sender: id) {
                 input: KeyboardInput {
                     state: ElementState::Pressed,
                     scancode: scancode as _,
+                    physical_key: KeyCode::Unidentified,
+                    logical_key: Key::Unidentified,
+                    text: None,
                     virtual_keycode,
                     modifiers: event_mods(event),
                 },
@@ -2,7 +2,8 @@ use raw_window_handle::{macos::MacOSHandle, RawWindowHandle};
 use std::{
     collections::VecDeque,
     f64,
-    os::raw::c_void,
+    ffi::CStr,
+    os::raw::{c_char, c_void},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc, Mutex, Weak,
@@ -14,6 +15,7 @@ use crate::{
         LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size, Size::Logical,
     },
     error::{ExternalError, NotSupportedError, OsError as RootOsError},
+    event::DragAction,
     icon::Icon,
     monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
     platform::macos::{ActivationPolicy, RequestUserAttentionType, WindowExtMacOS},
@@ -27,7 +29,10 @@ use crate::{
         window_delegate::new_delegate,
         OsError,
     },
-    window::{CursorIcon, Fullscreen, WindowAttributes, WindowId as RootWindowId},
+    window::{
+        ClipboardContents, ClipboardKind, CursorIcon, CustomCursor, Fullscreen, ResizeDirection,
+        WindowAttributes, WindowId as RootWindowId,
+    },
 };
 use cocoa::{
     appkit::{
@@ -433,6 +438,14 @@ impl UnownedWindow {
         }
     }
 
+    pub fn title(&self) -> String {
+        unsafe {
+            let title: id = msg_send![*self.ns_window, title];
+            let utf8: *const c_char = msg_send![title, UTF8String];
+            CStr::from_ptr(utf8).to_string_lossy().into_owned()
+        }
+    }
+
     pub fn set_visible(&self, visible: bool) {
         match visible {
             true => unsafe { util::make_key_and_order_front_async(*self.ns_window) },
@@ -440,10 +453,21 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        let is_visible: BOOL = unsafe { msg_send![*self.ns_window, isVisible] };
+        Some(is_visible == YES)
+    }
+
     pub fn request_redraw(&self) {
         AppState::queue_redraw(RootWindowId(self.id()));
     }
 
+    #[inline]
+    pub fn request_frame_callback(&self) {
+        self.request_redraw();
+    }
+
     pub fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
         let frame_rect = unsafe { NSWindow::frame(*self.ns_window) };
         let position = LogicalPosition::new(
@@ -551,6 +575,11 @@ impl UnownedWindow {
         } // Otherwise, we don't change the mask until we exit fullscreen.
     }
 
+    #[inline]
+    pub fn is_resizable(&self) -> bool {
+        self.shared_state.lock().unwrap().resizable
+    }
+
     pub fn set_cursor_icon(&self, cursor: CursorIcon) {
         let cursor = util::Cursor::from(cursor);
         if let Some(cursor_access) = self.cursor_state.upgrade() {
@@ -563,6 +592,39 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn set_custom_cursor(&self, _cursor: CustomCursor) {}
+
+    #[inline]
+    pub fn set_clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn set_drag_response(&self, _response: Option<(String, DragAction)>) {}
+
+    #[inline]
+    pub fn start_drag(
+        &self,
+        _contents: ClipboardContents,
+        _allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
         // TODO: Do this for real https://stackoverflow.com/a/40922095/5435443
@@ -586,6 +648,16 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn drag_resize_window(&self, _direction: ResizeDirection) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn scale_factor(&self) -> f64 {
         unsafe { NSWindow::backingScaleFactor(*self.ns_window) as _ }
@@ -681,6 +753,12 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        let is_minimized: BOOL = unsafe { msg_send![*self.ns_window, isMiniaturized] };
+        Some(is_minimized == YES)
+    }
+
     #[inline]
     pub fn set_maximized(&self, maximized: bool) {
         let is_zoomed = self.is_zoomed();
@@ -697,6 +775,11 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        self.is_zoomed()
+    }
+
     #[inline]
     pub fn fullscreen(&self) -> Option<Fullscreen> {
         let shared_state_lock = self.shared_state.lock().unwrap();
@@ -909,6 +992,17 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn is_decorated(&self) -> bool {
+        self.decorations.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn has_focus(&self) -> bool {
+        let is_key_window: BOOL = unsafe { msg_send![*self.ns_window, isKeyWindow] };
+        is_key_window == YES
+    }
+
     #[inline]
     pub fn set_always_on_top(&self, always_on_top: bool) {
         let level = if always_on_top {
@@ -945,6 +1039,9 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn set_ime_allowed(&self, _allowed: bool) {}
+
     #[inline]
     pub fn current_monitor(&self) -> RootMonitorHandle {
         unsafe {
@@ -1,7 +1,8 @@
 use super::{backend, device, proxy::Proxy, runner, window};
 use crate::dpi::{PhysicalSize, Size};
-use crate::event::{DeviceId, ElementState, Event, KeyboardInput, TouchPhase, WindowEvent};
-use crate::event_loop::ControlFlow;
+use crate::error::{ExternalError, NotSupportedError};
+use crate::event::{DeviceId, ElementState, Event, Key, KeyboardInput, TouchPhase, WindowEvent};
+use crate::event_loop::{ControlFlow, Timer, TimerId};
 use crate::window::{Theme, WindowId};
 use std::clone::Clone;
 
@@ -28,6 +29,12 @@ impl<T> WindowTarget<T> {
         Proxy::new(self.runner.clone())
     }
 
+    pub fn set_timer(&self, _timer: Timer) -> Result<TimerId, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn cancel_timer(&self, _id: TimerId) {}
+
     pub fn run(&self, event_handler: Box<dyn FnMut(Event<'static, T>, &mut ControlFlow)>) {
         self.runner.set_listener(event_handler);
     }
@@ -56,7 +63,7 @@ impl<T> WindowTarget<T> {
         });
 
         let runner = self.runner.clone();
-        canvas.on_keyboard_press(move |scancode, virtual_keycode, modifiers| {
+        canvas.on_keyboard_press(move |scancode, physical_key, virtual_keycode, modifiers| {
             #[allow(deprecated)]
             runner.send_event(Event::WindowEvent {
                 window_id: WindowId(id),
@@ -64,6 +71,9 @@ impl<T> WindowTarget<T> {
                     device_id: DeviceId(unsafe { device::Id::dummy() }),
                     input: KeyboardInput {
                         scancode,
+                        physical_key,
+                        logical_key: Key::Unidentified,
+                        text: None,
                         state: ElementState::Pressed,
                         virtual_keycode,
                         modifiers,
@@ -74,7 +84,7 @@ impl<T> WindowTarget<T> {
         });
 
         let runner = self.runner.clone();
-        canvas.on_keyboard_release(move |scancode, virtual_keycode, modifiers| {
+        canvas.on_keyboard_release(move |scancode, physical_key, virtual_keycode, modifiers| {
             #[allow(deprecated)]
             runner.send_event(Event::WindowEvent {
                 window_id: WindowId(id),
@@ -82,6 +92,9 @@ impl<T> WindowTarget<T> {
                     device_id: DeviceId(unsafe { device::Id::dummy() }),
                     input: KeyboardInput {
                         scancode,
+                        physical_key,
+                        logical_key: Key::Unidentified,
+                        text: None,
                         state: ElementState::Released,
                         virtual_keycode,
                         modifiers,
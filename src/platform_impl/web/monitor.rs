@@ -24,6 +24,10 @@ impl Handle {
         }
     }
 
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        (self.position(), self.size())
+    }
+
     pub fn video_modes(&self) -> impl Iterator<Item = VideoMode> {
         std::iter::empty()
     }
@@ -1,7 +1,9 @@
 use super::event;
 use crate::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
 use crate::error::OsError as RootOE;
-use crate::event::{ModifiersState, MouseButton, MouseScrollDelta, ScanCode, VirtualKeyCode};
+use crate::event::{
+    KeyCode, ModifiersState, MouseButton, MouseScrollDelta, ScanCode, VirtualKeyCode,
+};
 use crate::platform_impl::{OsError, PlatformSpecificWindowBuilderAttributes};
 
 use std::cell::RefCell;
@@ -91,6 +93,10 @@ impl Canvas {
             .expect(&format!("Set attribute: {}", attribute));
     }
 
+    pub fn get_attribute(&self, attribute: &str) -> Option<String> {
+        self.raw.get_attribute(attribute)
+    }
+
     pub fn position(&self) -> LogicalPosition<f64> {
         let bounds = self.raw.get_bounding_client_rect();
 
@@ -131,12 +137,13 @@ impl Canvas {
 
     pub fn on_keyboard_release<F>(&mut self, mut handler: F)
     where
-        F: 'static + FnMut(ScanCode, Option<VirtualKeyCode>, ModifiersState),
+        F: 'static + FnMut(ScanCode, KeyCode, Option<VirtualKeyCode>, ModifiersState),
     {
         self.on_keyboard_release =
             Some(self.add_user_event("keyup", move |event: KeyboardEvent| {
                 handler(
                     event::scan_code(&event),
+                    event::physical_key(&event),
                     event::virtual_key_code(&event),
                     event::keyboard_modifiers(&event),
                 );
@@ -145,12 +152,13 @@ impl Canvas {
 
     pub fn on_keyboard_press<F>(&mut self, mut handler: F)
     where
-        F: 'static + FnMut(ScanCode, Option<VirtualKeyCode>, ModifiersState),
+        F: 'static + FnMut(ScanCode, KeyCode, Option<VirtualKeyCode>, ModifiersState),
     {
         self.on_keyboard_press =
             Some(self.add_user_event("keydown", move |event: KeyboardEvent| {
                 handler(
                     event::scan_code(&event),
+                    event::physical_key(&event),
                     event::virtual_key_code(&event),
                     event::keyboard_modifiers(&event),
                 );
@@ -1,8 +1,12 @@
 use crate::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{ExternalError, NotSupportedError, OsError as RootOE};
+use crate::event::DragAction;
 use crate::icon::Icon;
 use crate::monitor::MonitorHandle as RootMH;
-use crate::window::{CursorIcon, Fullscreen, WindowAttributes, WindowId as RootWI};
+use crate::window::{
+    ClipboardContents, ClipboardKind, CursorIcon, CustomCursor, Fullscreen, ResizeDirection,
+    WindowAttributes, WindowId as RootWI,
+};
 
 use raw_window_handle::web::WebHandle;
 
@@ -62,14 +66,27 @@ impl Window {
         self.canvas.set_attribute("alt", title);
     }
 
+    pub fn title(&self) -> String {
+        self.canvas.get_attribute("alt").unwrap_or_default()
+    }
+
     pub fn set_visible(&self, _visible: bool) {
         // Intentionally a no-op
     }
 
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        None
+    }
+
     pub fn request_redraw(&self) {
         (self.register_redraw_request)();
     }
 
+    pub fn request_frame_callback(&self) {
+        self.request_redraw();
+    }
+
     pub fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
         Ok(self.canvas.position().to_physical(self.scale_factor()))
     }
@@ -118,6 +135,11 @@ impl Window {
         // Intentionally a no-op: users can't resize canvas elements
     }
 
+    #[inline]
+    pub fn is_resizable(&self) -> bool {
+        false
+    }
+
     #[inline]
     pub fn scale_factor(&self) -> f64 {
         super::backend::scale_factor()
@@ -169,12 +191,47 @@ impl Window {
             .set_attribute("style", &format!("cursor: {}", text));
     }
 
+    #[inline]
+    pub fn set_custom_cursor(&self, _cursor: CustomCursor) {
+        // Currently an intentional no-op
+    }
+
     #[inline]
     pub fn set_cursor_position(&self, _position: Position) -> Result<(), ExternalError> {
         // Intentionally a no-op, as the web does not support setting cursor positions
         Ok(())
     }
 
+    #[inline]
+    pub fn set_clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn set_drag_response(&self, _response: Option<(String, DragAction)>) {}
+
+    #[inline]
+    pub fn start_drag(
+        &self,
+        _contents: ClipboardContents,
+        _allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn set_cursor_grab(&self, _grab: bool) -> Result<(), ExternalError> {
         // Intentionally a no-op, as the web does not (properly) support grabbing the cursor
@@ -191,16 +248,36 @@ impl Window {
         }
     }
 
+    #[inline]
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn drag_resize_window(&self, _direction: ResizeDirection) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn set_minimized(&self, _minimized: bool) {
         // Intentionally a no-op, as canvases cannot be 'minimized'
     }
 
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        None
+    }
+
     #[inline]
     pub fn set_maximized(&self, _maximized: bool) {
         // Intentionally a no-op, as canvases cannot be 'maximized'
     }
 
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        false
+    }
+
     #[inline]
     pub fn fullscreen(&self) -> Option<Fullscreen> {
         if self.canvas.is_fullscreen() {
@@ -224,6 +301,16 @@ impl Window {
         // Intentionally a no-op, no canvas decorations
     }
 
+    #[inline]
+    pub fn is_decorated(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    pub fn has_focus(&self) -> bool {
+        false
+    }
+
     #[inline]
     pub fn set_always_on_top(&self, _always_on_top: bool) {
         // Intentionally a no-op, no window ordering
@@ -239,6 +326,11 @@ impl Window {
         // Currently a no-op as it does not seem there is good support for this on web
     }
 
+    #[inline]
+    pub fn set_ime_allowed(&self, _allowed: bool) {
+        // Currently a no-op as it does not seem there is good support for this on web
+    }
+
     #[inline]
     pub fn current_monitor(&self) -> RootMH {
         RootMH {
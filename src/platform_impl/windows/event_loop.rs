@@ -31,8 +31,11 @@ use winapi::{
 
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize},
-    event::{DeviceEvent, Event, Force, KeyboardInput, Touch, TouchPhase, WindowEvent},
-    event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
+    error::{ExternalError, NotSupportedError},
+    event::{
+        DeviceEvent, Event, Force, Key, KeyCode, KeyboardInput, Touch, TouchPhase, WindowEvent,
+    },
+    event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW, Timer, TimerId},
     platform_impl::platform::{
         dark_mode::try_dark_mode,
         dpi::{become_dpi_aware, dpi_to_scale_factor, enable_non_client_dpi_scaling},
@@ -247,6 +250,14 @@ impl<T> EventLoopWindowTarget<T> {
             target_window: self.thread_msg_target,
         }
     }
+
+    #[inline]
+    pub fn set_timer(&self, _timer: Timer) -> Result<TimerId, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn cancel_timer(&self, _id: TimerId) {}
 }
 
 fn main_thread_id() -> DWORD {
@@ -1107,6 +1118,9 @@ unsafe extern "system" fn public_window_callback<T: 'static>(
                             input: KeyboardInput {
                                 state: Pressed,
                                 scancode,
+                                physical_key: KeyCode::Unidentified,
+                                logical_key: Key::Unidentified,
+                                text: None,
                                 virtual_keycode: vkey,
                                 modifiers: event::get_key_mods(),
                             },
@@ -1139,6 +1153,9 @@ unsafe extern "system" fn public_window_callback<T: 'static>(
                         input: KeyboardInput {
                             state: Released,
                             scancode,
+                            physical_key: KeyCode::Unidentified,
+                            logical_key: Key::Unidentified,
+                            text: None,
                             virtual_keycode: vkey,
                             modifiers: event::get_key_mods(),
                         },
@@ -1522,6 +1539,9 @@ unsafe extern "system" fn public_window_callback<T: 'static>(
                         device_id: DEVICE_ID,
                         input: KeyboardInput {
                             scancode,
+                            physical_key: KeyCode::Unidentified,
+                            logical_key: Key::Unidentified,
+                            text: None,
                             virtual_keycode,
                             state: Released,
                             modifiers: event::get_key_mods(),
@@ -1557,6 +1577,9 @@ unsafe extern "system" fn public_window_callback<T: 'static>(
                         device_id: DEVICE_ID,
                         input: KeyboardInput {
                             scancode,
+                            physical_key: KeyCode::Unidentified,
+                            logical_key: Key::Unidentified,
+                            text: None,
                             virtual_keycode,
                             state: Released,
                             modifiers: event::get_key_mods(),
@@ -2049,6 +2072,9 @@ unsafe extern "system" fn thread_event_target_callback<T: 'static>(
                                 device_id,
                                 event: Key(KeyboardInput {
                                     scancode,
+                                    physical_key: KeyCode::Unidentified,
+                                    logical_key: crate::event::Key::Unidentified,
+                                    text: None,
                                     state,
                                     virtual_keycode,
                                     modifiers: event::get_key_mods(),
@@ -202,6 +202,22 @@ impl MonitorHandle {
         }
     }
 
+    #[inline]
+    pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        let monitor_info = get_monitor_info(self.0).unwrap();
+        let work = monitor_info.rcWork;
+        (
+            PhysicalPosition {
+                x: work.left,
+                y: work.top,
+            },
+            PhysicalSize {
+                width: (work.right - work.left) as u32,
+                height: (work.bottom - work.top) as u32,
+            },
+        )
+    }
+
     #[inline]
     pub fn scale_factor(&self) -> f64 {
         dpi_to_scale_factor(get_monitor_dpi(self.0).unwrap_or(96))
@@ -32,6 +32,7 @@ use winapi::{
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
     error::{ExternalError, NotSupportedError, OsError as RootOsError},
+    event::DragAction,
     icon::Icon,
     monitor::MonitorHandle as RootMonitorHandle,
     platform_impl::platform::{
@@ -44,7 +45,10 @@ use crate::{
         window_state::{CursorFlags, SavedWindow, WindowFlags, WindowState},
         PlatformSpecificWindowBuilderAttributes, WindowId,
     },
-    window::{CursorIcon, Fullscreen, WindowAttributes},
+    window::{
+        ClipboardContents, ClipboardKind, CursorIcon, CustomCursor, Fullscreen, ResizeDirection,
+        WindowAttributes,
+    },
 };
 
 /// The Win32 implementation of the main `Window` object.
@@ -124,6 +128,16 @@ impl Window {
         }
     }
 
+    #[inline]
+    pub fn title(&self) -> String {
+        unsafe {
+            let len = winuser::GetWindowTextLengthW(self.window.0);
+            let mut buf = vec![0; len as usize + 1];
+            let len = winuser::GetWindowTextW(self.window.0, buf.as_mut_ptr(), len + 1);
+            util::wchar_to_string(&buf[..len as usize])
+        }
+    }
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         let window = self.window.clone();
@@ -135,6 +149,12 @@ impl Window {
         });
     }
 
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        let window_state = self.window_state.lock();
+        Some(window_state.window_flags().contains(WindowFlags::VISIBLE))
+    }
+
     #[inline]
     pub fn request_redraw(&self) {
         unsafe {
@@ -147,6 +167,11 @@ impl Window {
         }
     }
 
+    #[inline]
+    pub fn request_frame_callback(&self) {
+        self.request_redraw();
+    }
+
     #[inline]
     pub fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
         util::get_window_rect(self.window.0)
@@ -260,6 +285,12 @@ impl Window {
         });
     }
 
+    #[inline]
+    pub fn is_resizable(&self) -> bool {
+        let window_state = self.window_state.lock();
+        window_state.window_flags().contains(WindowFlags::RESIZABLE)
+    }
+
     /// Returns the `hwnd` of this window.
     #[inline]
     pub fn hwnd(&self) -> HWND {
@@ -290,6 +321,39 @@ impl Window {
         });
     }
 
+    #[inline]
+    pub fn set_custom_cursor(&self, _cursor: CustomCursor) {}
+
+    #[inline]
+    pub fn set_clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn clipboard_contents(
+        &self,
+        _kind: ClipboardKind,
+        _mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn set_drag_response(&self, _response: Option<(String, DragAction)>) {}
+
+    #[inline]
+    pub fn start_drag(
+        &self,
+        _contents: ClipboardContents,
+        _allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
         let window = self.window.clone();
@@ -324,6 +388,16 @@ impl Window {
         rx.recv().unwrap().ok();
     }
 
+    #[inline]
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn drag_resize_window(&self, _direction: ResizeDirection) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn scale_factor(&self) -> f64 {
         self.window_state.lock().scale_factor
@@ -363,6 +437,12 @@ impl Window {
         });
     }
 
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        let window_state = self.window_state.lock();
+        Some(window_state.window_flags().contains(WindowFlags::MINIMIZED))
+    }
+
     #[inline]
     pub fn set_maximized(&self, maximized: bool) {
         let window = self.window.clone();
@@ -375,6 +455,12 @@ impl Window {
         });
     }
 
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        let window_state = self.window_state.lock();
+        window_state.window_flags().contains(WindowFlags::MAXIMIZED)
+    }
+
     #[inline]
     pub fn fullscreen(&self) -> Option<Fullscreen> {
         let window_state = self.window_state.lock();
@@ -557,6 +643,19 @@ impl Window {
         });
     }
 
+    #[inline]
+    pub fn is_decorated(&self) -> bool {
+        let window_state = self.window_state.lock();
+        window_state
+            .window_flags()
+            .contains(WindowFlags::DECORATIONS)
+    }
+
+    #[inline]
+    pub fn has_focus(&self) -> bool {
+        unsafe { winuser::GetForegroundWindow() == self.window.0 }
+    }
+
     #[inline]
     pub fn set_always_on_top(&self, always_on_top: bool) {
         let window = self.window.clone();
@@ -605,6 +704,9 @@ impl Window {
         unimplemented!();
     }
 
+    #[inline]
+    pub fn set_ime_allowed(&self, _allowed: bool) {}
+
     #[inline]
     pub fn is_dark_mode(&self) -> bool {
         self.window_state.lock().is_dark_mode
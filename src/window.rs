@@ -4,12 +4,16 @@ use std::fmt;
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
     error::{ExternalError, NotSupportedError, OsError},
+    event::DragAction,
     event_loop::EventLoopWindowTarget,
     monitor::{MonitorHandle, VideoMode},
     platform_impl,
 };
 
-pub use crate::icon::{BadIcon, Icon};
+pub use crate::{
+    cursor::CustomCursor,
+    icon::{BadIcon, Icon},
+};
 
 /// Represents a window.
 ///
@@ -405,6 +409,29 @@ impl Window {
     pub fn request_redraw(&self) {
         self.window.request_redraw()
     }
+
+    /// Emits `RedrawRequested` for this window when the compositor wants its next frame, preceded
+    /// by `WindowEvent::FrameCallback` with the presentation time.
+    ///
+    /// Unlike [`request_redraw`](Window::request_redraw), which is served as soon as the loop
+    /// spins, this paces the redraws with the display, so calling it while handling each
+    /// `RedrawRequested`, before or after drawing, runs an animation at the refresh rate. It can
+    /// also be called at any other time, the first frame then not being held back.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Requires a compositing window manager supporting `_NET_WM_FRAME_DRAWN` and the
+    ///   XSync extension, without which it behaves like `request_redraw`. The window has to be
+    ///   drawn while handling `RedrawRequested` for the compositor to report the frame. When the
+    ///   compositor has no frame of the window to report, this behaves like `request_redraw`.
+    /// - **Wayland:** Follows the `wl_surface.frame` callbacks, which compositors stop sending
+    ///   while the window is hidden. When no callback is pending, the surface is committed for
+    ///   the request to take effect.
+    /// - **Windows / macOS / iOS / Android / Web / Headless:** Behaves like `request_redraw`.
+    #[inline]
+    pub fn request_frame_callback(&self) {
+        self.window.request_frame_callback()
+    }
 }
 
 /// Position and size functions.
@@ -544,6 +571,17 @@ impl Window {
         self.window.set_title(title)
     }
 
+    /// Gets the current title of the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android:** Always returns an empty string.
+    /// - **X11:** Reads `_NET_WM_NAME`, falling back to `WM_NAME`.
+    #[inline]
+    pub fn title(&self) -> String {
+        self.window.title()
+    }
+
     /// Modifies the window's visibility.
     ///
     /// If `false`, this will hide the window. If `true`, this will show the window.
@@ -557,6 +595,21 @@ impl Window {
         self.window.set_visible(visible)
     }
 
+    /// Gets the window's current visibility state.
+    ///
+    /// `None` means it couldn't be determined, so it's unknown whether the window is visible or
+    /// not.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Not visible until the window is mapped and shown by the window manager.
+    /// - **Wayland:** Always returns `None`, as surfaces can't be hidden.
+    /// - **Android / Web:** Always returns `None`.
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        self.window.is_visible()
+    }
+
     /// Sets whether the window is resizable or not.
     ///
     /// Note that making the window unresizable doesn't exempt you from handling `Resized`, as that event can still be
@@ -577,6 +630,16 @@ impl Window {
         self.window.set_resizable(resizable)
     }
 
+    /// Gets whether the window is resizable or not, as set with [`Window::set_resizable`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / Web:** Always returns `false`.
+    #[inline]
+    pub fn is_resizable(&self) -> bool {
+        self.window.is_resizable()
+    }
+
     /// Sets the window to minimized or back
     ///
     /// ## Platform-specific
@@ -587,6 +650,21 @@ impl Window {
         self.window.set_minimized(minimized);
     }
 
+    /// Gets whether the window is minimized.
+    ///
+    /// `None` means it couldn't be determined, so it's unknown whether the window is minimized or
+    /// not.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Reads `WM_STATE` and `_NET_WM_STATE_HIDDEN`.
+    /// - **Wayland:** Always returns `None`, as `xdg_toplevel` doesn't report it.
+    /// - **iOS / Android / Web:** Always returns `None`.
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        self.window.is_minimized()
+    }
+
     /// Sets the window to maximized or back.
     ///
     /// ## Platform-specific
@@ -598,6 +676,19 @@ impl Window {
         self.window.set_maximized(maximized)
     }
 
+    /// Gets the window's current maximized state.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Reads `_NET_WM_STATE`, so it's only `true` once the window manager maximized
+    ///   the window.
+    /// - **Wayland:** Follows the states of the last `xdg_toplevel` configure event.
+    /// - **iOS / Android / Web:** Always returns `false`.
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        self.window.is_maximized()
+    }
+
     /// Sets the window to fullscreen or back.
     ///
     /// ## Platform-specific
@@ -645,6 +736,31 @@ impl Window {
         self.window.set_decorations(decorations)
     }
 
+    /// Gets whether the window has decorations, as set with [`Window::set_decorations`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Reads the Motif hints, since that's what window managers follow.
+    /// - **iOS / Android / Web:** Always returns `false`.
+    #[inline]
+    pub fn is_decorated(&self) -> bool {
+        self.window.is_decorated()
+    }
+
+    /// Gets whether the window has keyboard focus.
+    ///
+    /// This is the state reported by the last [`WindowEvent::Focused`] event.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / Web:** Always returns `false`.
+    ///
+    /// [`WindowEvent::Focused`]: crate::event::WindowEvent::Focused
+    #[inline]
+    pub fn has_focus(&self) -> bool {
+        self.window.has_focus()
+    }
+
     /// Change whether or not the window will always be on top of other windows.
     ///
     /// ## Platform-specific
@@ -679,10 +795,36 @@ impl Window {
     ///
     /// **iOS:** Has no effect.
     /// - **Web:** Has no effect.
+    /// - **Wayland:** Only has an effect while input methods are allowed with
+    ///   [`Window::set_ime_allowed`].
     #[inline]
     pub fn set_ime_position<P: Into<Position>>(&self, position: P) {
         self.window.set_ime_position(position.into())
     }
+
+    /// Sets whether the window receives composition events from the input method.
+    ///
+    /// When allowed, the application is responsible for drawing the composition string reported
+    /// by [`WindowEvent::ImePreedit`], and committed text is reported by
+    /// [`WindowEvent::ImeCommit`]. Otherwise, the input method draws the composition itself and
+    /// committed text is reported by [`WindowEvent::ReceivedCharacter`].
+    ///
+    /// Input methods aren't allowed by default.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Falls back to letting the input method draw the composition if it doesn't
+    ///   support the `XIMPreeditCallbacks` style.
+    /// - **Wayland:** Input methods can't be used at all while they aren't allowed.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ///
+    /// [`WindowEvent::ImePreedit`]: crate::event::WindowEvent::ImePreedit
+    /// [`WindowEvent::ImeCommit`]: crate::event::WindowEvent::ImeCommit
+    /// [`WindowEvent::ReceivedCharacter`]: crate::event::WindowEvent::ReceivedCharacter
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.window.set_ime_allowed(allowed)
+    }
 }
 
 /// Cursor functions.
@@ -698,6 +840,22 @@ impl Window {
         self.window.set_cursor_icon(cursor);
     }
 
+    /// Sets the cursor of the window to a custom image, until it's changed with
+    /// [`Window::set_cursor_icon`].
+    ///
+    /// Animated cursors, created with [`CustomCursor::from_animation`], keep playing until the
+    /// cursor is changed.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Animated cursors are played by the X server, and rounded to millisecond delays.
+    /// - **Wayland:** Animated cursors only advance while the cursor is shown by the compositor.
+    /// - **Windows / macOS / iOS / Android / Web:** Has no effect.
+    #[inline]
+    pub fn set_custom_cursor(&self, cursor: &CustomCursor) {
+        self.window.set_custom_cursor(cursor.clone());
+    }
+
     /// Changes the position of the cursor in window coordinates.
     ///
     /// ## Platform-specific
@@ -742,6 +900,143 @@ impl Window {
     pub fn set_cursor_visible(&self, visible: bool) {
         self.window.set_cursor_visible(visible)
     }
+
+    /// Moves the window with the cursor until the mouse button is released.
+    ///
+    /// This is meant to be called while handling the [`WindowEvent::MouseInput`] pressing the
+    /// button, and lets undecorated windows implement their own title bar.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Requires a window manager supporting `_NET_WM_MOVERESIZE`.
+    /// - **Wayland:** Requires a recent input event, whose serial is checked by the compositor,
+    ///   on the seat of the pointer dragging the window.
+    /// - **Windows / macOS / iOS / Android / Web / Headless:** Always returns an `Err`.
+    ///
+    /// [`WindowEvent::MouseInput`]: crate::event::WindowEvent::MouseInput
+    #[inline]
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        self.window.drag_window()
+    }
+
+    /// Resizes the window with the cursor until the mouse button is released, moving the given
+    /// edge or corner.
+    ///
+    /// See [`Window::drag_window`] for the details.
+    #[inline]
+    pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
+        self.window.drag_resize_window(direction)
+    }
+}
+
+/// Clipboard functions.
+impl Window {
+    /// Replaces the contents of the given clipboard.
+    ///
+    /// The contents are served by this window until another client takes ownership of the
+    /// clipboard, at which point [`WindowEvent::ClipboardChanged`] is emitted.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The contents are lost when the window is destroyed.
+    /// - **Wayland:** Only [`ClipboardKind::Clipboard`] is supported, and the compositor will
+    ///   ignore the request unless the window has recently received input.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ///
+    /// [`WindowEvent::ClipboardChanged`]: crate::event::WindowEvent::ClipboardChanged
+    #[inline]
+    pub fn set_clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        contents: ClipboardContents,
+    ) -> Result<(), ExternalError> {
+        self.window.set_clipboard_contents(kind, contents)
+    }
+
+    /// Reads the contents of the given clipboard, converted to `mime_type`.
+    ///
+    /// Returns `Ok(None)` if the clipboard is empty or its owner cannot provide the data in the
+    /// requested format. This blocks until the owner of the clipboard answers the request.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Only [`ClipboardKind::Clipboard`] is supported. The compositor only
+    ///   exposes the clipboard to the window which has keyboard focus.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    #[inline]
+    pub fn clipboard_contents(
+        &self,
+        kind: ClipboardKind,
+        mime_type: &str,
+    ) -> Result<Option<Vec<u8>>, ExternalError> {
+        self.window.clipboard_contents(kind, mime_type)
+    }
+}
+
+/// Drag and drop functions.
+impl Window {
+    /// Accepts the drag and drop operation currently over the window, which delivers its data
+    /// in `mime_type` with [`WindowEvent::DragDropped`] if it's dropped.
+    ///
+    /// The answer holds until the drag leaves the window or the answer is changed, and is usually
+    /// given while handling [`WindowEvent::DragEntered`] or [`WindowEvent::DragMoved`], depending
+    /// on the position of the cursor. Has no effect if there is no drag over the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** An answer given outside of the handling of those events is only told to the
+    ///   source of the drag once the drag moves.
+    /// - **Wayland:** `DragAction::Link` is treated as `DragAction::Copy`.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ///
+    /// [`WindowEvent::DragDropped`]: crate::event::WindowEvent::DragDropped
+    /// [`WindowEvent::DragEntered`]: crate::event::WindowEvent::DragEntered
+    /// [`WindowEvent::DragMoved`]: crate::event::WindowEvent::DragMoved
+    #[inline]
+    pub fn accept_drag(&self, mime_type: &str, action: DragAction) {
+        self.window
+            .set_drag_response(Some((mime_type.to_owned(), action)))
+    }
+
+    /// Rejects the drag and drop operation currently over the window, so that dropping it emits
+    /// [`WindowEvent::DragLeft`].
+    ///
+    /// See [`Window::accept_drag`] for the details.
+    ///
+    /// [`WindowEvent::DragLeft`]: crate::event::WindowEvent::DragLeft
+    #[inline]
+    pub fn reject_drag(&self) {
+        self.window.set_drag_response(None)
+    }
+
+    /// Starts dragging `contents` out of the window, offering them in all of their MIME types.
+    ///
+    /// The drag follows the mouse button currently held down, so this is meant to be called while
+    /// handling the [`WindowEvent::MouseInput`] or [`WindowEvent::CursorMoved`] events which
+    /// start it. The target may perform any of the `allowed_actions`, the first of which is the
+    /// preferred one. The progress of the drag is reported with
+    /// [`WindowEvent::DragSourceAction`], and it ends with either
+    /// [`WindowEvent::DragSourceFinished`] or [`WindowEvent::DragSourceCancelled`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** `DragAction::Link` isn't supported, and targets using an old version of the
+    ///   protocol always copy the data.
+    /// - **Windows / macOS / iOS / Android / Web:** Unsupported.
+    ///
+    /// [`WindowEvent::MouseInput`]: crate::event::WindowEvent::MouseInput
+    /// [`WindowEvent::CursorMoved`]: crate::event::WindowEvent::CursorMoved
+    /// [`WindowEvent::DragSourceAction`]: crate::event::WindowEvent::DragSourceAction
+    /// [`WindowEvent::DragSourceFinished`]: crate::event::WindowEvent::DragSourceFinished
+    /// [`WindowEvent::DragSourceCancelled`]: crate::event::WindowEvent::DragSourceCancelled
+    #[inline]
+    pub fn start_drag(
+        &self,
+        contents: ClipboardContents,
+        allowed_actions: &[DragAction],
+    ) -> Result<(), ExternalError> {
+        self.window.start_drag(contents, allowed_actions)
+    }
 }
 
 /// Monitor info functions.
@@ -857,6 +1152,20 @@ impl Default for CursorIcon {
     }
 }
 
+/// The edge or corner of a window which is moved by [`Window::drag_resize_window`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ResizeDirection {
+    East,
+    North,
+    NorthEast,
+    NorthWest,
+    South,
+    SouthEast,
+    SouthWest,
+    West,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Fullscreen {
     Exclusive(VideoMode),
@@ -868,3 +1177,98 @@ pub enum Theme {
     Light,
     Dark,
 }
+
+/// Identifies one of the system clipboards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClipboardKind {
+    /// The regular clipboard, used by explicit copy and paste actions.
+    Clipboard,
+    /// The primary selection, which holds the most recently selected text and is usually pasted
+    /// with a middle click.
+    Primary,
+}
+
+/// Data offered through a clipboard, in one or more MIME types.
+///
+/// # Example
+///
+/// ```
+/// use winit::window::ClipboardContents;
+///
+/// let contents = ClipboardContents::from_text("Hello")
+///     .with_data("text/html", b"<b>Hello</b>".to_vec());
+///
+/// assert_eq!(contents.text(), Some("Hello"));
+/// assert_eq!(contents.get("text/html"), Some(&b"<b>Hello</b>"[..]));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClipboardContents {
+    data: Vec<(String, Vec<u8>)>,
+}
+
+impl ClipboardContents {
+    /// The MIME type used for UTF-8 encoded plain text.
+    pub const TEXT: &'static str = "text/plain;charset=utf-8";
+
+    /// Creates contents which don't offer any data.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates contents which offer `text` as UTF-8 encoded plain text.
+    pub fn from_text<S: Into<String>>(text: S) -> Self {
+        ClipboardContents::new().with_data(ClipboardContents::TEXT, text.into().into_bytes())
+    }
+
+    /// Offers `data` in the given MIME type, replacing any data previously offered in it.
+    pub fn with_data<S: Into<String>>(mut self, mime_type: S, data: Vec<u8>) -> Self {
+        let mime_type = mime_type.into();
+        self.data.retain(|(mime, _)| *mime != mime_type);
+        self.data.push((mime_type, data));
+        self
+    }
+
+    /// Returns the data offered in the given MIME type.
+    ///
+    /// Requests for any of the common plain text types (such as `text/plain` or the X11
+    /// `UTF8_STRING` target) are answered with the data offered as [`ClipboardContents::TEXT`].
+    pub fn get(&self, mime_type: &str) -> Option<&[u8]> {
+        self.data
+            .iter()
+            .find(|(mime, _)| mime == mime_type)
+            .or_else(|| {
+                if is_text_mime_type(mime_type) {
+                    self.data
+                        .iter()
+                        .find(|(mime, _)| mime == ClipboardContents::TEXT)
+                } else {
+                    None
+                }
+            })
+            .map(|(_, data)| &data[..])
+    }
+
+    /// Returns the offered plain text, if any.
+    pub fn text(&self) -> Option<&str> {
+        self.get(ClipboardContents::TEXT)
+            .and_then(|data| std::str::from_utf8(data).ok())
+    }
+
+    /// Returns the MIME types this data is offered in.
+    pub fn mime_types(&self) -> impl Iterator<Item = &str> {
+        self.data.iter().map(|(mime, _)| mime.as_str())
+    }
+}
+
+pub(crate) fn is_text_mime_type(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        ClipboardContents::TEXT
+            | "text/plain"
+            | "UTF8_STRING"
+            | "STRING"
+            | "TEXT"
+            | "COMPOUND_TEXT"
+    )
+}
@@ -0,0 +1,77 @@
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+
+use std::time::Duration;
+
+use winit::{
+    event::{Event, StartCause},
+    event_loop::{ControlFlow, EventLoop},
+    platform::unix::{EventLoopExtUnix, EventLoopWindowTargetExtUnix, FdInterest},
+};
+
+// File descriptors are watched along with the connection to the display server, which the
+// headless backend doesn't have, so this needs an X server and is skipped without one.
+#[test]
+fn registered_fd_is_reported_when_ready() {
+    let mut event_loop = match EventLoop::<()>::new_x11_any_thread() {
+        Ok(event_loop) => event_loop,
+        Err(_) => return,
+    };
+    event_loop.pump_events(Some(Duration::from_millis(0)), |_, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+    });
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let source = event_loop
+        .register_fd(fds[0], FdInterest::READABLE)
+        .unwrap();
+    assert_eq!(
+        unsafe { libc::write(fds[1], b"x".as_ptr() as *const _, 1) },
+        1
+    );
+
+    let mut causes = Vec::new();
+    let mut ready = Vec::new();
+    event_loop.pump_events(Some(Duration::from_secs(1)), |event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        match event {
+            Event::NewEvents(cause) => causes.push(cause),
+            Event::FdReady {
+                source,
+                readable,
+                writable,
+            } => ready.push((source, readable, writable)),
+            _ => (),
+        }
+    });
+    assert!(matches!(
+        causes[..],
+        [StartCause::WaitCancelled {
+            requested_resume: None,
+            ..
+        }]
+    ));
+    assert_eq!(ready, [(source, true, false)]);
+
+    // Once read, the pipe isn't ready anymore.
+    let mut byte = 0u8;
+    assert_eq!(
+        unsafe { libc::read(fds[0], &mut byte as *mut u8 as *mut _, 1) },
+        1
+    );
+    event_loop.pump_events(Some(Duration::from_millis(0)), |event, _, _| {
+        assert!(!matches!(event, Event::FdReady { .. }));
+    });
+
+    event_loop.unregister_fd(source);
+    unsafe {
+        libc::close(fds[0]);
+        libc::close(fds[1]);
+    }
+}
@@ -0,0 +1,296 @@
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+
+use std::time::Duration;
+
+use winit::{
+    dpi::PhysicalSize,
+    event::{
+        ElementState, Event, Key, KeyCode, KeyboardInput, StartCause, WindowEvent, WindowState,
+    },
+    event_loop::{ControlFlow, EventLoop, Timer},
+    platform::unix::{
+        EventLoopExtHeadless, EventLoopExtUnix, EventLoopWindowTargetExtHeadless,
+        EventLoopWindowTargetExtUnix, FdInterest, PumpStatus, StepStatus,
+    },
+    window::WindowBuilder,
+};
+
+#[test]
+fn injected_events_are_dispatched_in_order() {
+    let mut event_loop = EventLoop::<()>::new_headless();
+    let window = WindowBuilder::new()
+        .with_inner_size(PhysicalSize::new(640, 480))
+        .build(&event_loop)
+        .unwrap();
+    let device_id = event_loop.headless_device_id();
+
+    // Building a window requests an initial redraw.
+    let mut events = Vec::new();
+    let status = event_loop.step(|event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        events.push(event.to_static().unwrap());
+    });
+    assert_eq!(status, StepStatus::Dispatched);
+    assert_eq!(
+        events,
+        [
+            Event::NewEvents(StartCause::Init),
+            Event::MainEventsCleared,
+            Event::RedrawRequested(window.id()),
+            Event::RedrawEventsCleared,
+        ]
+    );
+
+    // Nothing is pending, so the loop keeps waiting.
+    assert_eq!(event_loop.step(|_, _, _| panic!()), StepStatus::Idle);
+
+    event_loop.inject_window_event(window.id(), WindowEvent::Resized(PhysicalSize::new(10, 20)));
+    #[allow(deprecated)]
+    event_loop.inject_window_event(
+        window.id(),
+        WindowEvent::KeyboardInput {
+            device_id,
+            input: KeyboardInput {
+                scancode: 30,
+                physical_key: KeyCode::KeyA,
+                state: ElementState::Pressed,
+                virtual_keycode: None,
+                logical_key: Key::Character("a".to_owned()),
+                text: Some("a".to_owned()),
+                modifiers: Default::default(),
+            },
+            is_synthetic: false,
+        },
+    );
+    window.request_redraw();
+
+    let mut window_events = Vec::new();
+    let mut redraws = 0;
+    event_loop.step(|event, _, _| match event {
+        Event::WindowEvent { window_id, event } => {
+            assert_eq!(window_id, window.id());
+            window_events.push(event.to_static().unwrap());
+        }
+        Event::RedrawRequested(window_id) => {
+            assert_eq!(window_id, window.id());
+            redraws += 1;
+        }
+        _ => (),
+    });
+    assert_eq!(window_events.len(), 2);
+    assert_eq!(
+        window_events[0],
+        WindowEvent::Resized(PhysicalSize::new(10, 20))
+    );
+    assert_eq!(window.inner_size(), PhysicalSize::new(10, 20));
+    assert_eq!(redraws, 1);
+}
+
+#[test]
+fn wait_until_uses_virtual_clock() {
+    let mut event_loop = EventLoop::<()>::new_headless();
+    let start = event_loop.now();
+    let deadline = start + Duration::from_secs(60);
+
+    event_loop.step(|_, _, control_flow| *control_flow = ControlFlow::WaitUntil(deadline));
+
+    let mut cause = None;
+    event_loop.step(|event, _, control_flow| {
+        if let Event::NewEvents(start_cause) = event {
+            cause = Some(start_cause);
+        }
+        *control_flow = ControlFlow::Exit;
+    });
+    assert_eq!(
+        cause,
+        Some(StartCause::ResumeTimeReached {
+            start,
+            requested_resume: deadline,
+        })
+    );
+    assert_eq!(event_loop.now(), deadline);
+    assert_eq!(event_loop.step(|_, _, _| panic!()), StepStatus::Exited);
+}
+
+#[test]
+fn window_state_getters_follow_setters() {
+    let mut event_loop = EventLoop::<()>::new_headless();
+    let window = WindowBuilder::new()
+        .with_title("first")
+        .with_resizable(false)
+        .build(&event_loop)
+        .unwrap();
+    assert_eq!(window.title(), "first");
+    assert!(!window.is_resizable());
+    assert!(window.is_decorated());
+    assert!(!window.is_maximized());
+    assert_eq!(window.is_minimized(), Some(false));
+    assert_eq!(window.is_visible(), Some(true));
+
+    window.set_title("second");
+    window.set_resizable(true);
+    window.set_decorations(false);
+    window.set_maximized(true);
+    window.set_visible(false);
+    assert_eq!(window.title(), "second");
+    assert!(window.is_resizable());
+    assert!(!window.is_decorated());
+    assert!(window.is_maximized());
+    assert_eq!(window.is_visible(), Some(false));
+
+    assert!(!window.has_focus());
+    event_loop.inject_window_event(window.id(), WindowEvent::Focused(true));
+    event_loop.step(|_, _, _| ());
+    assert!(window.has_focus());
+}
+
+#[test]
+fn window_state_setters_report_changes() {
+    let mut event_loop = EventLoop::<()>::new_headless();
+    let window = WindowBuilder::new().build(&event_loop).unwrap();
+    event_loop.step(|_, _, _| ());
+
+    fn step(event_loop: &mut EventLoop<()>) -> Vec<WindowEvent<'static>> {
+        let mut events = Vec::new();
+        event_loop.step(|event, _, _| {
+            if let Event::WindowEvent { event, .. } = event {
+                events.push(event.to_static().unwrap());
+            }
+        });
+        events
+    }
+
+    window.set_maximized(true);
+    window.set_maximized(true);
+    assert_eq!(
+        step(&mut event_loop),
+        [WindowEvent::StateChanged(WindowState::MAXIMIZED)]
+    );
+
+    window.set_minimized(true);
+    assert_eq!(
+        step(&mut event_loop),
+        [
+            WindowEvent::StateChanged(WindowState::MAXIMIZED | WindowState::MINIMIZED),
+            WindowEvent::Occluded(true),
+        ]
+    );
+
+    // Still hidden, so only the state changes.
+    window.set_visible(false);
+    window.set_minimized(false);
+    assert_eq!(
+        step(&mut event_loop),
+        [WindowEvent::StateChanged(WindowState::MAXIMIZED)]
+    );
+
+    window.set_visible(true);
+    window.set_decorations(false);
+    assert_eq!(step(&mut event_loop), [WindowEvent::Occluded(false)]);
+}
+
+#[test]
+fn pump_events_returns_after_each_iteration() {
+    let mut event_loop = EventLoop::<u32>::new_headless();
+    let proxy = event_loop.create_proxy();
+
+    let mut causes = Vec::new();
+    let status = event_loop.pump_events(Some(Duration::from_millis(0)), |event, _, _| {
+        if let Event::NewEvents(cause) = event {
+            causes.push(cause);
+        }
+    });
+    assert_eq!(status, PumpStatus::Continue);
+    assert_eq!(causes, [StartCause::Init]);
+
+    proxy.send_event(7).unwrap();
+    let mut user_events = Vec::new();
+    let status = event_loop.pump_events(None, |event, _, control_flow| {
+        if let Event::UserEvent(event) = event {
+            user_events.push(event);
+        }
+        *control_flow = ControlFlow::Exit;
+    });
+    assert_eq!(status, PumpStatus::Exit);
+    assert_eq!(user_events, [7]);
+    assert_eq!(
+        event_loop.pump_events(None, |_, _, _| panic!()),
+        PumpStatus::Exit
+    );
+}
+
+#[test]
+fn register_fd_is_unsupported() {
+    let event_loop = EventLoop::<()>::new_headless();
+    assert!(event_loop.register_fd(0, FdInterest::READABLE).is_err());
+}
+
+#[test]
+fn invalid_timer_intervals_are_rejected() {
+    let event_loop = EventLoop::<()>::new_headless();
+    assert!(event_loop
+        .set_timer(Timer::Interval(Duration::from_secs(0)))
+        .is_err());
+    assert!(event_loop
+        .set_timer(Timer::Interval(Duration::from_secs(u64::MAX)))
+        .is_err());
+}
+
+#[test]
+fn timers_fire_together_on_virtual_clock() {
+    let mut event_loop = EventLoop::<()>::new_headless();
+    let start = event_loop.now();
+    let interval = event_loop
+        .set_timer(Timer::Interval(Duration::from_secs(10)))
+        .unwrap();
+    let deadline = event_loop
+        .set_timer(Timer::Deadline(start + Duration::from_secs(20)))
+        .unwrap();
+    let cancelled = event_loop
+        .set_timer(Timer::Deadline(start + Duration::from_secs(5)))
+        .unwrap();
+    event_loop.cancel_timer(cancelled);
+
+    event_loop.step(|_, _, control_flow| *control_flow = ControlFlow::Wait);
+
+    let step = |event_loop: &mut EventLoop<()>| {
+        let mut events = Vec::new();
+        let status = event_loop.step(|event, _, _| match event {
+            Event::NewEvents(StartCause::TimerFired(id)) | Event::TimerExpired(id) => {
+                events.push(id)
+            }
+            _ => (),
+        });
+        assert_eq!(status, StepStatus::Dispatched);
+        (events, event_loop.now() - start)
+    };
+    assert_eq!(
+        step(&mut event_loop),
+        (vec![interval, interval], Duration::from_secs(10))
+    );
+    // The timers which fire at the same time are reported by the same iteration.
+    assert_eq!(
+        step(&mut event_loop),
+        (vec![deadline, deadline, interval], Duration::from_secs(20))
+    );
+
+    // Missed periods are only reported once.
+    event_loop.advance_clock(Duration::from_secs(35));
+    assert_eq!(
+        step(&mut event_loop),
+        (vec![interval, interval], Duration::from_secs(55))
+    );
+    assert_eq!(
+        step(&mut event_loop),
+        (vec![interval, interval], Duration::from_secs(60))
+    );
+
+    event_loop.cancel_timer(interval);
+    assert_eq!(event_loop.step(|_, _, _| panic!()), StepStatus::Idle);
+}
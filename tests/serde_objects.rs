@@ -4,10 +4,10 @@ use serde::{Deserialize, Serialize};
 use winit::{
     dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
     event::{
-        ElementState, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta, TouchPhase,
-        VirtualKeyCode,
+        DragAction, ElementState, Key, KeyCode, KeyboardInput, ModifiersState, MouseButton,
+        MouseScrollDelta, NamedKey, PenPhase, PenTool, TouchPhase, VirtualKeyCode,
     },
-    window::CursorIcon,
+    window::{ClipboardKind, CursorIcon, ResizeDirection},
 };
 
 #[allow(dead_code)]
@@ -16,16 +16,24 @@ fn needs_serde<S: Serialize + Deserialize<'static>>() {}
 #[test]
 fn window_serde() {
     needs_serde::<CursorIcon>();
+    needs_serde::<ClipboardKind>();
+    needs_serde::<ResizeDirection>();
 }
 
 #[test]
 fn events_serde() {
     needs_serde::<KeyboardInput>();
     needs_serde::<TouchPhase>();
+    needs_serde::<PenPhase>();
+    needs_serde::<PenTool>();
+    needs_serde::<DragAction>();
     needs_serde::<ElementState>();
     needs_serde::<MouseButton>();
     needs_serde::<MouseScrollDelta>();
     needs_serde::<VirtualKeyCode>();
+    needs_serde::<KeyCode>();
+    needs_serde::<Key>();
+    needs_serde::<NamedKey>();
     needs_serde::<ModifiersState>();
 }
 